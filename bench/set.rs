@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
-use fastset::Set;
+use fastset::{NanoRng, Set};
 use hashbrown::HashSet as HashBrownSet;
 use nanorand::{Rng, WyRand};
 use std::collections::HashSet;
@@ -9,7 +9,7 @@ fn bench_basic_operations(c: &mut Criterion) {
     let mut group = c.benchmark_group("basic_operations");
 
     // Setup test data
-    let mut set = Set::with_capacity(100_000);
+    let mut set: Set = Set::with_capacity(100_000);
     let mut hashset: HashSet<usize> = HashSet::with_capacity(100_000);
     let mut hashbrownset: HashBrownSet<usize> = HashBrownSet::with_capacity(100_000);
     let mut rng = WyRand::new();
@@ -18,10 +18,10 @@ fn bench_basic_operations(c: &mut Criterion) {
         .collect();
 
     // Pre-populate sets for remove/contains benchmarks
-    for i in 0..500 {
-        set.insert(data[i]);
-        hashset.insert(data[i]);
-        hashbrownset.insert(data[i]);
+    for &value in &data[..500] {
+        set.insert(value);
+        hashset.insert(value);
+        hashbrownset.insert(value);
     }
 
     group.bench_function("insert (Set)", |b| {
@@ -78,9 +78,10 @@ fn bench_basic_operations(c: &mut Criterion) {
         })
     });
 
+    let mut draws = NanoRng::new();
     group.bench_function("random (Set)", |b| {
         b.iter(|| {
-            set.random(&mut rng);
+            set.random(&mut draws);
         })
     });
 
@@ -97,7 +98,7 @@ fn bench_scaling(c: &mut Criterion) {
         // Benchmark insertion scaling
         group.bench_with_input(BenchmarkId::new("insert", size), &size, |b, &size| {
             b.iter_batched(
-                || Set::with_capacity(size * 2),
+                || Set::<usize>::with_capacity(size * 2),
                 |mut set| {
                     for _ in 0..size {
                         set.insert(rng.generate_range(0..size * 2));
@@ -109,12 +110,12 @@ fn bench_scaling(c: &mut Criterion) {
 
         // Benchmark memory allocation scaling
         group.bench_with_input(BenchmarkId::new("allocation", size), &size, |b, &size| {
-            b.iter(|| Set::with_capacity(black_box(size)));
+            b.iter(|| Set::<usize>::with_capacity(black_box(size)));
         });
 
         // Benchmark iteration scaling
         group.bench_with_input(BenchmarkId::new("iteration", size), &size, |b, &size| {
-            let mut set = Set::with_capacity(size);
+            let mut set: Set = Set::with_capacity(size);
             for i in 0..size {
                 set.insert(i);
             }
@@ -137,7 +138,7 @@ fn bench_access_patterns(c: &mut Criterion) {
     // Sequential access pattern
     group.bench_function("sequential_insert", |b| {
         b.iter_batched(
-            || Set::with_capacity(set_size),
+            || Set::<usize>::with_capacity(set_size),
             |mut set| {
                 for i in 0..set_size {
                     set.insert(i);
@@ -154,7 +155,7 @@ fn bench_access_patterns(c: &mut Criterion) {
                 let data: Vec<usize> = (0..set_size)
                     .map(|_| rng.generate_range(0..set_size * 2))
                     .collect();
-                (Set::with_capacity(set_size * 2), data)
+                (Set::<usize>::with_capacity(set_size * 2), data)
             },
             |(mut set, data)| {
                 for value in data {
@@ -168,7 +169,7 @@ fn bench_access_patterns(c: &mut Criterion) {
     // Sparse access pattern
     group.bench_function("sparse_insert", |b| {
         b.iter_batched(
-            || Set::with_capacity(1_000_000),
+            || Set::<usize>::with_capacity(1_000_000),
             |mut set| {
                 for i in 0..1000 {
                     set.insert(i * 1000);
@@ -181,7 +182,7 @@ fn bench_access_patterns(c: &mut Criterion) {
     // Dense access pattern
     group.bench_function("dense_insert", |b| {
         b.iter_batched(
-            || Set::with_capacity(1000),
+            || Set::<usize>::with_capacity(1000),
             |mut set| {
                 for i in 0..1000 {
                     set.insert(i);
@@ -205,8 +206,8 @@ fn bench_set_operations(c: &mut Criterion) {
         let id = format!("{}x{}", size1, size2);
 
         // Create test sets
-        let mut set1 = Set::with_capacity(size1 * 2);
-        let mut set2 = Set::with_capacity(size2 * 2);
+        let mut set1: Set = Set::with_capacity(size1 * 2);
+        let mut set2: Set = Set::with_capacity(size2 * 2);
         let mut hashset1 = HashSet::with_capacity(size1);
         let mut hashset2 = HashSet::with_capacity(size2);
 
@@ -287,7 +288,7 @@ fn bench_memory_operations(c: &mut Criterion) {
     group.bench_function("clear_small", |b| {
         b.iter_batched(
             || {
-                let mut set = Set::with_capacity(100);
+                let mut set: Set = Set::with_capacity(100);
                 for i in 0..100 {
                     set.insert(i);
                 }
@@ -303,7 +304,7 @@ fn bench_memory_operations(c: &mut Criterion) {
     group.bench_function("clear_large", |b| {
         b.iter_batched(
             || {
-                let mut set = Set::with_capacity(100_000);
+                let mut set: Set = Set::with_capacity(100_000);
                 for i in 0..10_000 {
                     set.insert(i * 10);
                 }
@@ -320,7 +321,7 @@ fn bench_memory_operations(c: &mut Criterion) {
     group.bench_function("shrink_to_fit", |b| {
         b.iter_batched(
             || {
-                let mut set = Set::with_capacity(100_000);
+                let mut set: Set = Set::with_capacity(100_000);
                 for i in 0..1000 {
                     set.insert(i);
                 }
@@ -335,7 +336,7 @@ fn bench_memory_operations(c: &mut Criterion) {
 
     // Benchmark clone operation
     group.bench_function("clone_small", |b| {
-        let mut set = Set::with_capacity(100);
+        let mut set: Set = Set::with_capacity(100);
         for i in 0..100 {
             set.insert(i);
         }
@@ -346,7 +347,7 @@ fn bench_memory_operations(c: &mut Criterion) {
     });
 
     group.bench_function("clone_large", |b| {
-        let mut set = Set::with_capacity(100_000);
+        let mut set: Set = Set::with_capacity(100_000);
         for i in 0..10_000 {
             set.insert(i * 10);
         }
@@ -366,7 +367,7 @@ fn bench_edge_cases(c: &mut Criterion) {
 
     // Benchmark repeated insertions of the same element
     group.bench_function("duplicate_inserts", |b| {
-        let mut set = Set::with_capacity(100);
+        let mut set: Set = Set::with_capacity(100);
         set.insert(50);
         b.iter(|| {
             set.insert(black_box(50));
@@ -375,7 +376,7 @@ fn bench_edge_cases(c: &mut Criterion) {
 
     // Benchmark alternating insert/remove
     group.bench_function("alternating_insert_remove", |b| {
-        let mut set = Set::with_capacity(100);
+        let mut set: Set = Set::with_capacity(100);
         let mut insert = true;
         b.iter(|| {
             if insert {
@@ -389,7 +390,7 @@ fn bench_edge_cases(c: &mut Criterion) {
 
     // Benchmark contains on non-existent elements
     group.bench_function("contains_miss", |b| {
-        let mut set = Set::with_capacity(10_000);
+        let mut set: Set = Set::with_capacity(10_000);
         for i in 0..5000 {
             set.insert(i * 2); // Only even numbers
         }
@@ -401,7 +402,7 @@ fn bench_edge_cases(c: &mut Criterion) {
 
     // Benchmark max/min operations
     group.bench_function("max_min_operations", |b| {
-        let mut set = Set::with_capacity(10_000);
+        let mut set: Set = Set::with_capacity(10_000);
         for _ in 0..1000 {
             set.insert(rng.generate_range(0..10_000));
         }
@@ -423,7 +424,7 @@ fn bench_iterators(c: &mut Criterion) {
 
     for size in sizes {
         // Create a set with the given size
-        let mut set = Set::with_capacity(size);
+        let mut set: Set = Set::with_capacity(size);
         for i in 0..size {
             set.insert(i);
         }
@@ -451,6 +452,150 @@ fn bench_iterators(c: &mut Criterion) {
                 black_box(evens);
             });
         });
+
+        // Benchmark ascending iteration over the indicator
+        group.bench_with_input(BenchmarkId::new("iter_sorted_sum", size), &set, |b, set| {
+            b.iter(|| {
+                let sum: usize = set.iter_sorted().sum();
+                black_box(sum);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Runs insert, contains and remove churn on a `Set` with the given page size
+fn bench_page_size<const PAGE: usize>(
+    group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>,
+    layout: &str,
+    data: &[usize],
+) {
+    let max = data.iter().copied().max().unwrap_or(0);
+
+    group.bench_with_input(
+        BenchmarkId::new(format!("insert_{}", layout), PAGE),
+        data,
+        |b, data| {
+            b.iter_batched(
+                || Set::<usize, PAGE>::with_max(max),
+                |mut set| {
+                    for &value in data {
+                        set.insert(black_box(value));
+                    }
+                    set
+                },
+                BatchSize::SmallInput,
+            );
+        },
+    );
+
+    let mut populated = Set::<usize, PAGE>::with_max(max);
+    populated.extend(data);
+
+    group.bench_with_input(
+        BenchmarkId::new(format!("contains_{}", layout), PAGE),
+        data,
+        |b, data| {
+            b.iter(|| {
+                for value in data {
+                    black_box(populated.contains(black_box(value)));
+                }
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new(format!("remove_{}", layout), PAGE),
+        data,
+        |b, data| {
+            b.iter_batched(
+                || populated.clone(),
+                |mut set| {
+                    for value in data {
+                        set.remove(black_box(value));
+                    }
+                    set
+                },
+                BatchSize::SmallInput,
+            );
+        },
+    );
+}
+
+/// Benchmark the page size trade-off on sparse and dense universes
+fn bench_page_sizes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("page_sizes");
+
+    let mut rng = WyRand::new_seed(42);
+    // 1,000 values spread over ten million slots
+    let sparse: Vec<usize> = (0..1_000)
+        .map(|_| rng.generate_range(0usize..10_000_000))
+        .collect();
+    // Every value below 10,000
+    let dense: Vec<usize> = (0..10_000).collect();
+
+    bench_page_size::<4>(&mut group, "sparse", &sparse);
+    bench_page_size::<8>(&mut group, "sparse", &sparse);
+    bench_page_size::<16>(&mut group, "sparse", &sparse);
+    bench_page_size::<16>(&mut group, "dense", &dense);
+    bench_page_size::<64>(&mut group, "dense", &dense);
+    bench_page_size::<256>(&mut group, "dense", &dense);
+
+    group.finish();
+}
+
+/// Benchmark bitmap-only against hybrid containers on mixed-density data
+fn bench_hybrid_containers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hybrid_containers");
+
+    let mut rng = WyRand::new_seed(42);
+    // Scattered values, one long run and one dense random block
+    let mut mixed: Vec<usize> = (0..2_000)
+        .map(|_| rng.generate_range(0usize..10_000_000))
+        .collect();
+    mixed.extend(20_000_000..20_050_000);
+    mixed.extend((0..30_000).map(|_| rng.generate_range(30_000_000usize..30_065_536)));
+    let max = mixed.iter().copied().max().unwrap_or(0);
+
+    for hybrid in [false, true] {
+        let mode = if hybrid { "hybrid" } else { "bitmap" };
+        let build = || {
+            let mut set: Set = Set::with_max(max);
+            if hybrid {
+                set.enable_hybrid_containers();
+            }
+            set
+        };
+
+        group.bench_function(BenchmarkId::new("insert", mode), |b| {
+            b.iter_batched(
+                build,
+                |mut set| {
+                    for &value in &mixed {
+                        set.insert(black_box(value));
+                    }
+                    set
+                },
+                BatchSize::SmallInput,
+            );
+        });
+
+        let mut populated = build();
+        populated.extend(&mixed);
+
+        group.bench_function(BenchmarkId::new("contains", mode), |b| {
+            b.iter(|| {
+                for value in &mixed {
+                    black_box(populated.contains(black_box(value)));
+                }
+            });
+        });
+
+        group.bench_function(BenchmarkId::new("random", mode), |b| {
+            let mut rng = NanoRng(WyRand::new_seed(7));
+            b.iter(|| black_box(populated.random(&mut rng)));
+        });
     }
 
     group.finish();
@@ -464,6 +609,8 @@ criterion_group!(
     bench_set_operations,
     bench_memory_operations,
     bench_edge_cases,
-    bench_iterators
+    bench_iterators,
+    bench_page_sizes,
+    bench_hybrid_containers
 );
 criterion_main!(benches);
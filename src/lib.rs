@@ -9,9 +9,10 @@
 //!
 //! ## Features
 //!
-//! - Tailored for unsigned integer (`usize`) elements, ideal for index-based applications
+//! - Tailored for unsigned integer elements (`u16`, `u32`, `u64` or the default `usize`), ideal for index-based applications
 //! - Fast insertion, removal, and membership check
-//! - `random` method for uniform random sampling
+//! - `random` method for uniform random sampling, driven by any `rand_core::RngCore`
+//!   (the default `nanorand` feature adds a `NanoRng` adapter for `nanorand` generators)
 //! - Paging mechanism to somewhat mitigate the large memory footprint[^1]
 //!
 //! Note that while paging improves the existing memory footprint,
@@ -19,6 +20,12 @@
 //! or for applications with storage need for sparse elements spread over an extended range.
 //! For integers twice as sparse as the page size, the `fastset::Set` with paging
 //! has peak heap allocation ~ 8x that of `std::collections::HashSet`.
+//! The page size is the `PAGE` parameter of `Set<T, PAGE>` (a power of two, 16 by
+//! default): smaller pages suit sparse universes, larger ones dense universes.
+//! Both the indicator and the pages are allocated in chunks only where values land, and
+//! freed again once emptied, so a large maximum costs little until it is populated.
+//! With `enable_hybrid_containers`, each 2¹⁶ block of the indicator is further stored
+//! as a sorted array, a bitmap or a run list, whichever suits its density.
 //!
 //! [^1]: A paging mechanism is introduced in `0.4.0` that reduces the memory-footprint of `fastset::Set`.
 //! With the paging feature, `fastset::Set` achieves ~ 50% reduction in peak heap memory allocations
@@ -41,7 +48,8 @@
 //!
 //! ```rust
 //! use fastset::{set, Set};
-//! use nanorand::WyRand;
+//! use rand_chacha::rand_core::SeedableRng;
+//! use rand_chacha::ChaCha8Rng;
 //!
 //!    let mut set = set![5, 10, 15, 20, 25, 30]; // Initialize set with elements
 //!    assert!(set.contains(&5)); // Check for element presence
@@ -56,7 +64,7 @@
 //!        assert_eq!(taken, 10);
 //!    }
 //!
-//!    let mut rng = WyRand::new();
+//!    let mut rng = ChaCha8Rng::seed_from_u64(42); // Any rand_core::RngCore works, NanoRng included
 //!    if let Some(element) = set.random(&mut rng) { // Get a random element
 //!        set.remove(&element); // Remove the randomly selected element
 //!        assert!(!set.contains(&element));
@@ -76,11 +84,15 @@
 //! - **Cardinality**: Determine the size of (S), i.e., (|S|).
 //! - **Sampling**: Draw a uniform random sample from (S).
 //!
+//! `fastset::DistinctCounter` runs the CVM distinct-elements estimator of \[1\] over a bounded `Set` buffer.
+//! `fastset::union_size_estimate` estimates the size of a union of `DelphicSet`s as in \[2\], without materializing it.
+//!
 //! A unit test in `src/set.rs` verifies the uniform sampling property with a basic [Chi-squared test](https://en.wikipedia.org/wiki/Chi-squared_test).
 //!
 //! ```rust
 //! use fastset::Set;
-//! use nanorand::WyRand;
+//! use rand_chacha::rand_core::SeedableRng;
+//! use rand_chacha::ChaCha8Rng;
 //! use statrs::distribution::{ChiSquared, ContinuousCDF};
 //!
 //! fn sampling_is_uniformly_at_random() {
@@ -88,8 +100,8 @@
 //!     const EDGE_OF_THE_UNIVERSE: usize = 10000;
 //!
 //!     let elements = (1..=EDGE_OF_THE_UNIVERSE).collect::<Vec<_>>();
-//!     let set = Set::from(elements.clone());
-//!     let mut rng = WyRand::new_seed(42u64);
+//!     let set: Set = Set::from(elements.clone());
+//!     let mut rng = ChaCha8Rng::seed_from_u64(42);
 //!     let mut counts = vec![0f64; elements.len()];
 //!
 //! (0..SAMPLES).for_each(|_| {
@@ -122,19 +134,20 @@
 //! \[2\]: **Meel, Kuldeep S., Sourav Chakraborty, and N. V. Vinodchandran.** *"Estimation of the Size of Union of Delphic Sets: Achieving Independence from Stream Size."* Proceedings of the 41st ACM SIGMOD-SIGACT-SIGAI Symposium on Principles of Database Systems. 2022.
 //!
 mod set;
-pub use set::{Set, SetOps};
-/// The maximum capacity for the Set.
-///
-/// CAUTION: Setting the set's largest element or capacity near MAX_CAPACITY
-/// can significantly impact memory usage. For instance, with MAX_CAPACITY
-/// set to 1 billion, the `Set` could require approximately 16 GB of memory
-/// under certain conditions due to storage of elements, their indicators,
-/// and indexing structures. Ensure adequate memory is available when
-/// approaching this limit.
-const MAX_CAPACITY: usize = 1_000_000_000;
+pub use rand_core;
+#[cfg(feature = "nanorand")]
+pub use set::NanoRng;
+pub use set::{
+    sample_from_union, union_size_estimate, DelphicSet, DistinctCounter, Element, Encoding, Error,
+    InsertOutcome, IntoSortedIter, KeySet, Set, SetOps, SetRange, SetView, SortedIter, SortedSlice,
+    WeightedSet,
+};
 
 /// Macro for creating a `Set` with the given elements.
 ///
+/// The resulting set has the default `usize` element type; build other
+/// widths with `Set::from` or `collect`.
+///
 /// # Example
 ///
 /// ```
@@ -144,7 +157,7 @@ const MAX_CAPACITY: usize = 1_000_000_000;
 #[macro_export]
 macro_rules! set {
     ($($element:expr),*) => {{
-        let mut new_set = Set::with_max(30000); // Adjusted for crate-level visibility
+        let mut new_set: Set = Set::with_max(30000); // Adjusted for crate-level visibility
         $(new_set.insert($element);)*
         new_set
     }};
@@ -156,7 +169,7 @@ macro_rules! set {
 ///
 /// ```
 /// # use fastset::{Set, remove};
-/// let mut my_set = Set::new(10);
+/// let mut my_set: Set = Set::new(10);
 /// my_set.insert(1);
 /// my_set.insert(2);
 ///
@@ -175,7 +188,7 @@ macro_rules! remove {
 ///
 /// ```
 /// # use fastset::{Set, insert};
-/// let mut my_set = Set::new(10);
+/// let mut my_set: Set = Set::new(10);
 ///
 /// insert!(my_set, 1, 2, 3);
 /// ```
@@ -188,29 +201,42 @@ macro_rules! insert {
 
 /// Macro for selecting a random element from a `Set`.
 ///
-/// If no random number generator is provided, it will use `WyRand` as the default.
+/// The generator may be any `rand_core::RngCore`. If none is provided, and the
+/// `nanorand` feature is enabled, a fresh [`NanoRng`] is used.
 ///
 /// # Example
 ///
 /// ```
 /// # use fastset::{Set, random};
-/// # use nanorand::{WyRand, Rng};
-/// let mut my_set = Set::new(10);
+/// # use rand_chacha::rand_core::SeedableRng;
+/// # use rand_chacha::ChaCha8Rng;
+/// let mut my_set: Set = Set::new(10);
 /// my_set.insert(1);
 /// my_set.insert(2);
 ///
 /// let random_elem = random!(my_set); // Use default RNG
 ///
-/// let mut rng = WyRand::new();
+/// let mut rng = ChaCha8Rng::seed_from_u64(42);
 /// let random_elem_custom_rng = random!(my_set, &mut rng); // Use custom RNG
 /// ```
+#[cfg(feature = "nanorand")]
 #[macro_export]
 macro_rules! random {
     ($set:expr, $rng:expr) => {{
         $set.random($rng)
     }};
     ($set:expr) => {{
-        let mut rng = WyRand::new();
+        let mut rng = $crate::NanoRng::new();
         $set.random(&mut rng)
     }};
 }
+
+/// Macro for selecting a random element from a `Set` with the given
+/// `rand_core::RngCore`.
+#[cfg(not(feature = "nanorand"))]
+#[macro_export]
+macro_rules! random {
+    ($set:expr, $rng:expr) => {{
+        $set.random($rng)
+    }};
+}
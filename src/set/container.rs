@@ -0,0 +1,613 @@
+use super::indicator::{CHUNK_BITS, WORD_BITS};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::TryReserveError;
+
+/// Number of words in each chunk.
+pub(super) const CHUNK_WORDS: usize = CHUNK_BITS / WORD_BITS;
+
+/// Largest cardinality stored as a sorted array; beyond it a bitmap is smaller.
+const ARRAY_MAX: usize = 4096;
+
+/// Largest number of runs stored as a run list; beyond it a bitmap is smaller.
+const RUNS_MAX: usize = 2048;
+
+/// Size of a bitmap container in bytes, the yardstick for the other two.
+const BITMAP_BYTES: usize = CHUNK_WORDS * 8;
+
+/// The set slots of one allocated chunk, in whichever container suits them.
+///
+/// Outside hybrid mode every chunk is a bitmap. In hybrid mode a chunk starts
+/// as a sorted array and is re-picked whenever it outgrows its container: an
+/// array past 4096 values, a run list past 2048 runs or averaging less than
+/// two slots per run, or a bitmap that has fallen to 2048 values. The gap
+/// between the array and bitmap thresholds keeps a chunk hovering around
+/// either from converting back and forth.
+#[derive(Clone)]
+pub(super) struct Chunk {
+    container: Container,
+    ones: usize,
+}
+
+#[derive(Clone)]
+enum Container {
+    /// The set slots in ascending order.
+    Array(Vec<u16>),
+    /// One bit per slot.
+    Bitmap(Box<Bitmap>),
+    /// Maximal runs of set slots as inclusive `(first, last)` pairs, in
+    /// ascending order and never adjacent.
+    Runs(Vec<(u16, u16)>),
+}
+
+/// A bitmap container with a two-level summary of its non-zero words.
+///
+/// Bit `j` of `summary[i]` is set iff word `64 * i + j` is non-zero, and bit
+/// `i` of `top` is set iff `summary[i]` is non-zero.
+#[derive(Clone)]
+struct Bitmap {
+    words: [u64; CHUNK_WORDS],
+    summary: [u64; CHUNK_WORDS / WORD_BITS],
+    top: u64,
+}
+
+/// Serialized form of a chunk, which is validated on the way back in.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Container")]
+pub(super) enum ContainerRepr<'a> {
+    Array(Cow<'a, [u16]>),
+    Bitmap(Cow<'a, [u64]>),
+    Runs(Cow<'a, [(u16, u16)]>),
+}
+
+impl Chunk {
+    /// Creates a chunk with every slot unset, to be filled by `insert`.
+    pub(super) fn new(hybrid: bool) -> Self {
+        let container = match hybrid {
+            true => Container::Array(Vec::new()),
+            false => Container::Bitmap(Bitmap::empty()),
+        };
+        Self { container, ones: 0 }
+    }
+
+    /// Creates a chunk like [`new`](Self::new), reporting a failure to
+    /// allocate its bitmap instead of aborting.
+    pub(super) fn try_new(hybrid: bool) -> Result<Self, TryReserveError> {
+        let container = match hybrid {
+            true => Container::Array(Vec::new()),
+            false => Container::Bitmap(Bitmap::try_empty()?),
+        };
+        Ok(Self { container, ones: 0 })
+    }
+
+    /// Creates a chunk from its words, or returns `None` if they are all zero.
+    pub(super) fn from_words(words: &[u64], hybrid: bool) -> Option<Self> {
+        let ones = words.iter().map(|w| w.count_ones() as usize).sum();
+        (ones > 0).then(|| Self {
+            container: Container::pick(words, ones, hybrid),
+            ones,
+        })
+    }
+
+    /// Rebuilds a chunk from its serialized form.
+    pub(super) fn from_repr(repr: ContainerRepr<'_>, hybrid: bool) -> Result<Self, &'static str> {
+        let container = match repr {
+            ContainerRepr::Array(values) => {
+                if values.windows(2).any(|pair| pair[0] >= pair[1]) {
+                    return Err("array container is not strictly ascending");
+                }
+                Container::Array(values.into_owned())
+            }
+            ContainerRepr::Bitmap(words) => {
+                if words.len() != CHUNK_WORDS {
+                    return Err("bitmap container has the wrong length");
+                }
+                Container::Bitmap(Bitmap::from_words(&words))
+            }
+            ContainerRepr::Runs(runs) => {
+                if runs.iter().any(|&(first, last)| first > last)
+                    || runs
+                        .windows(2)
+                        .any(|pair| pair[0].1 as usize + 1 >= pair[1].0 as usize)
+                {
+                    return Err("run container is not ascending and disjoint");
+                }
+                Container::Runs(runs.into_owned())
+            }
+        };
+        let ones = container.count_range(0, CHUNK_BITS);
+        if ones == 0 {
+            return Err("container is empty");
+        }
+        let mut chunk = Self { container, ones };
+        chunk.optimize(hybrid);
+        Ok(chunk)
+    }
+
+    /// Returns the serialized form of the chunk.
+    pub(super) fn to_repr(&self) -> ContainerRepr<'_> {
+        match &self.container {
+            Container::Array(values) => ContainerRepr::Array(Cow::Borrowed(values)),
+            Container::Bitmap(bitmap) => ContainerRepr::Bitmap(Cow::Borrowed(&bitmap.words)),
+            Container::Runs(runs) => ContainerRepr::Runs(Cow::Borrowed(runs)),
+        }
+    }
+
+    /// Returns the number of set slots.
+    #[inline(always)]
+    pub(super) fn ones(&self) -> usize {
+        self.ones
+    }
+
+    #[inline(always)]
+    pub(super) fn contains(&self, bit: usize) -> bool {
+        match &self.container {
+            Container::Array(values) => values.binary_search(&(bit as u16)).is_ok(),
+            Container::Bitmap(bitmap) => test_bit(|w| bitmap.words[w], bit),
+            Container::Runs(runs) => match runs.get(run_at_or_after(runs, bit)) {
+                Some(&(first, _)) => first as usize <= bit,
+                None => false,
+            },
+        }
+    }
+
+    /// Sets slot `bit`, returning `true` if it was previously unset.
+    #[inline(always)]
+    pub(super) fn insert(&mut self, bit: usize, hybrid: bool) -> bool {
+        let inserted = match &mut self.container {
+            Container::Array(values) => match values.binary_search(&(bit as u16)) {
+                Ok(_) => false,
+                Err(pos) => {
+                    values.insert(pos, bit as u16);
+                    true
+                }
+            },
+            Container::Bitmap(bitmap) => bitmap.insert(bit),
+            Container::Runs(runs) => insert_run(runs, bit),
+        };
+        if inserted {
+            self.ones += 1;
+            self.adapt(hybrid);
+        }
+        inserted
+    }
+
+    /// Unsets slot `bit`, returning `true` if it was previously set.
+    #[inline(always)]
+    pub(super) fn remove(&mut self, bit: usize, hybrid: bool) -> bool {
+        let removed = match &mut self.container {
+            Container::Array(values) => match values.binary_search(&(bit as u16)) {
+                Ok(pos) => {
+                    values.remove(pos);
+                    true
+                }
+                Err(_) => false,
+            },
+            Container::Bitmap(bitmap) => bitmap.remove(bit),
+            Container::Runs(runs) => remove_run(runs, bit),
+        };
+        if removed {
+            self.ones -= 1;
+            self.adapt(hybrid);
+        }
+        removed
+    }
+
+    /// Unsets every slot at or beyond `bit`.
+    pub(super) fn clear_from(&mut self, bit: usize, hybrid: bool) {
+        match &mut self.container {
+            Container::Array(values) => {
+                values.truncate(values.partition_point(|&v| (v as usize) < bit))
+            }
+            Container::Bitmap(bitmap) => {
+                let w = bit / WORD_BITS;
+                bitmap.words[w] &= (1u64 << (bit % WORD_BITS)) - 1;
+                bitmap.words[w + 1..].fill(0);
+                bitmap.resummarize();
+            }
+            Container::Runs(runs) => {
+                runs.truncate(runs.partition_point(|&(first, _)| (first as usize) < bit));
+                if let Some(last) = runs.last_mut() {
+                    last.1 = last.1.min((bit - 1) as u16);
+                }
+            }
+        }
+        self.ones = self.container.count_range(0, CHUNK_BITS);
+        self.adapt(hybrid);
+    }
+
+    /// Re-picks the container from scratch, shrinking it to fit.
+    pub(super) fn optimize(&mut self, hybrid: bool) {
+        let words = self.words().into_owned();
+        self.container = Container::pick(&words, self.ones, hybrid);
+    }
+
+    /// Returns the words of the chunk, borrowed if it is a bitmap.
+    pub(super) fn words(&self) -> Cow<'_, [u64]> {
+        match &self.container {
+            Container::Bitmap(bitmap) => Cow::Borrowed(&bitmap.words),
+            _ => Cow::Owned((0..CHUNK_WORDS).map(|w| self.word(w)).collect()),
+        }
+    }
+
+    /// Returns word `w`.
+    #[inline(always)]
+    pub(super) fn word(&self, w: usize) -> u64 {
+        let lo = w * WORD_BITS;
+        match &self.container {
+            Container::Array(values) => values[values.partition_point(|&v| (v as usize) < lo)..]
+                .iter()
+                .map(|&v| v as usize - lo)
+                .take_while(|&offset| offset < WORD_BITS)
+                .fold(0, |word, offset| word | 1u64 << offset),
+            Container::Bitmap(bitmap) => bitmap.words[w],
+            Container::Runs(runs) => runs[run_at_or_after(runs, lo)..]
+                .iter()
+                .take_while(|&&(first, _)| (first as usize) < lo + WORD_BITS)
+                .fold(0, |word, &(first, last)| {
+                    let from = (first as usize).max(lo) - lo;
+                    let to = (last as usize).min(lo + WORD_BITS - 1) - lo;
+                    word | (!0u64 >> (WORD_BITS - 1 - to)) & (!0u64 << from)
+                }),
+        }
+    }
+
+    /// Returns the first non-zero word at or after `w`, if any.
+    #[inline(always)]
+    pub(super) fn next_word(&self, w: usize) -> Option<usize> {
+        let lo = w * WORD_BITS;
+        match &self.container {
+            Container::Array(values) => {
+                let &v = values.get(values.partition_point(|&v| (v as usize) < lo))?;
+                Some(v as usize / WORD_BITS)
+            }
+            Container::Bitmap(bitmap) => bitmap.next_word(w),
+            Container::Runs(runs) => {
+                let &(first, _) = runs.get(run_at_or_after(runs, lo))?;
+                Some((first as usize).max(lo) / WORD_BITS)
+            }
+        }
+    }
+
+    /// Returns the last non-zero word at or before `w`, if any.
+    #[inline(always)]
+    pub(super) fn prev_word(&self, w: usize) -> Option<usize> {
+        let hi = w * WORD_BITS + WORD_BITS - 1;
+        match &self.container {
+            Container::Array(values) => {
+                let pos = values.partition_point(|&v| v as usize <= hi);
+                Some(values[pos.checked_sub(1)?] as usize / WORD_BITS)
+            }
+            Container::Bitmap(bitmap) => bitmap.prev_word(w),
+            Container::Runs(runs) => {
+                let pos = runs.partition_point(|&(first, _)| first as usize <= hi);
+                let (_, last) = runs[pos.checked_sub(1)?];
+                Some((last as usize).min(hi) / WORD_BITS)
+            }
+        }
+    }
+
+    /// Counts the set slots in `start..end`, which must be non-empty.
+    #[inline(always)]
+    pub(super) fn count_range(&self, start: usize, end: usize) -> usize {
+        match (start, end) {
+            (0, CHUNK_BITS) => self.ones,
+            _ => self.container.count_range(start, end),
+        }
+    }
+
+    /// Returns the `k`-th smallest set slot at or after word `w`, which must exist.
+    pub(super) fn select_from(&self, w: usize, mut k: usize) -> usize {
+        match &self.container {
+            Container::Array(values) => {
+                values[values.partition_point(|&v| (v as usize) < w * WORD_BITS) + k] as usize
+            }
+            Container::Bitmap(bitmap) => {
+                for (i, &bits) in bitmap.words[w..].iter().enumerate() {
+                    let ones = bits.count_ones() as usize;
+                    if k < ones {
+                        return (w + i) * WORD_BITS + select_in_word(bits, k);
+                    }
+                    k -= ones;
+                }
+                unreachable!("select past the last set slot")
+            }
+            Container::Runs(runs) => {
+                let lo = w * WORD_BITS;
+                for &(first, last) in &runs[run_at_or_after(runs, lo)..] {
+                    let first = (first as usize).max(lo);
+                    let len = last as usize - first + 1;
+                    if k < len {
+                        return first + k;
+                    }
+                    k -= len;
+                }
+                unreachable!("select past the last set slot")
+            }
+        }
+    }
+
+    /// Returns the container kind, for tests.
+    #[cfg(test)]
+    pub(super) fn kind(&self) -> &'static str {
+        match self.container {
+            Container::Array(_) => "array",
+            Container::Bitmap(_) => "bitmap",
+            Container::Runs(_) => "runs",
+        }
+    }
+
+    /// Re-picks the container in hybrid mode once it has outgrown its thresholds.
+    #[inline(always)]
+    fn adapt(&mut self, hybrid: bool) {
+        if !hybrid {
+            return;
+        }
+        let outgrown = match &self.container {
+            Container::Array(values) => values.len() > ARRAY_MAX,
+            Container::Bitmap(_) => self.ones <= ARRAY_MAX / 2,
+            Container::Runs(runs) => runs.len() > RUNS_MAX || 2 * runs.len() > self.ones,
+        };
+        if outgrown && self.ones > 0 {
+            self.optimize(hybrid);
+        }
+    }
+}
+
+impl Container {
+    /// Picks the smallest container for the given words, preferring an array,
+    /// then a bitmap, on ties. Outside hybrid mode this is always a bitmap.
+    fn pick(words: &[u64], ones: usize, hybrid: bool) -> Self {
+        if !hybrid {
+            return Container::Bitmap(Bitmap::from_words(words));
+        }
+        let mut carry = 0;
+        let mut runs = 0;
+        for &word in words {
+            runs += (word & !(word << 1 | carry)).count_ones() as usize;
+            carry = word >> (WORD_BITS - 1);
+        }
+        let array_bytes = if ones <= ARRAY_MAX {
+            2 * ones
+        } else {
+            usize::MAX
+        };
+        let runs_bytes = if runs <= RUNS_MAX {
+            4 * runs
+        } else {
+            usize::MAX
+        };
+        let values = || {
+            words.iter().enumerate().flat_map(|(w, &word)| {
+                (0..WORD_BITS)
+                    .filter(move |bit| word >> bit & 1 == 1)
+                    .map(move |bit| (w * WORD_BITS + bit) as u16)
+            })
+        };
+        if runs_bytes < array_bytes.min(BITMAP_BYTES) {
+            let mut list: Vec<(u16, u16)> = Vec::with_capacity(runs);
+            for value in values() {
+                match list.last_mut() {
+                    Some(run) if run.1 as usize + 1 == value as usize => run.1 = value,
+                    _ => list.push((value, value)),
+                }
+            }
+            Container::Runs(list)
+        } else if array_bytes <= BITMAP_BYTES {
+            let mut list = Vec::with_capacity(ones);
+            list.extend(values());
+            Container::Array(list)
+        } else {
+            Container::Bitmap(Bitmap::from_words(words))
+        }
+    }
+
+    /// Counts the set slots in `start..end`, which must be non-empty.
+    fn count_range(&self, start: usize, end: usize) -> usize {
+        match self {
+            Container::Array(values) => {
+                values.partition_point(|&v| (v as usize) < end)
+                    - values.partition_point(|&v| (v as usize) < start)
+            }
+            Container::Bitmap(bitmap) => count_ones(|w| bitmap.words[w], start, end),
+            Container::Runs(runs) => runs[run_at_or_after(runs, start)..]
+                .iter()
+                .take_while(|&&(first, _)| (first as usize) < end)
+                .map(|&(first, last)| (last as usize + 1).min(end) - (first as usize).max(start))
+                .sum(),
+        }
+    }
+}
+
+impl Bitmap {
+    fn empty() -> Box<Self> {
+        Box::new(Self {
+            words: [0; CHUNK_WORDS],
+            summary: [0; CHUNK_WORDS / WORD_BITS],
+            top: 0,
+        })
+    }
+
+    /// Allocates an empty bitmap, reporting failure instead of aborting.
+    fn try_empty() -> Result<Box<Self>, TryReserveError> {
+        let mut slot = Vec::new();
+        slot.try_reserve_exact(1)?;
+        slot.push(Self {
+            words: [0; CHUNK_WORDS],
+            summary: [0; CHUNK_WORDS / WORD_BITS],
+            top: 0,
+        });
+        let slot = Box::into_raw(slot.into_boxed_slice());
+        // SAFETY: a boxed one-element slice has the same allocation and layout
+        // as a box holding that element
+        Ok(unsafe { Box::from_raw(slot as *mut Self) })
+    }
+
+    fn from_words(words: &[u64]) -> Box<Self> {
+        let mut bitmap = Self::empty();
+        bitmap.words.copy_from_slice(words);
+        bitmap.resummarize();
+        bitmap
+    }
+
+    #[inline(always)]
+    fn insert(&mut self, bit: usize) -> bool {
+        let w = bit / WORD_BITS;
+        let mask = 1u64 << (bit % WORD_BITS);
+        let was = self.words[w];
+        if was & mask != 0 {
+            return false;
+        }
+        self.words[w] = was | mask;
+        if was == 0 {
+            self.summary[w / WORD_BITS] |= 1u64 << (w % WORD_BITS);
+            self.top |= 1u64 << (w / WORD_BITS);
+        }
+        true
+    }
+
+    #[inline(always)]
+    fn remove(&mut self, bit: usize) -> bool {
+        let w = bit / WORD_BITS;
+        let mask = 1u64 << (bit % WORD_BITS);
+        if self.words[w] & mask == 0 {
+            return false;
+        }
+        self.words[w] &= !mask;
+        if self.words[w] == 0 {
+            let s = w / WORD_BITS;
+            self.summary[s] &= !(1u64 << (w % WORD_BITS));
+            if self.summary[s] == 0 {
+                self.top &= !(1u64 << s);
+            }
+        }
+        true
+    }
+
+    /// Recomputes the summary from the words.
+    fn resummarize(&mut self) {
+        self.summary = [0; CHUNK_WORDS / WORD_BITS];
+        self.top = 0;
+        for (w, &word) in self.words.iter().enumerate() {
+            if word != 0 {
+                self.summary[w / WORD_BITS] |= 1u64 << (w % WORD_BITS);
+                self.top |= 1u64 << (w / WORD_BITS);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn next_word(&self, w: usize) -> Option<usize> {
+        let s = w / WORD_BITS;
+        let bits = self.summary.get(s)? & (!0u64 << (w % WORD_BITS));
+        if bits != 0 {
+            return Some(s * WORD_BITS + bits.trailing_zeros() as usize);
+        }
+        let rest = self.top & (!0u64 << s << 1);
+        if rest == 0 {
+            return None;
+        }
+        let s = rest.trailing_zeros() as usize;
+        Some(s * WORD_BITS + self.summary[s].trailing_zeros() as usize)
+    }
+
+    #[inline(always)]
+    fn prev_word(&self, w: usize) -> Option<usize> {
+        let s = w / WORD_BITS;
+        let bits = self.summary[s] & (!0u64 >> (WORD_BITS - 1 - w % WORD_BITS));
+        if bits != 0 {
+            return Some(s * WORD_BITS + (WORD_BITS - 1 - bits.leading_zeros() as usize));
+        }
+        let rest = self.top & ((1u64 << s) - 1);
+        if rest == 0 {
+            return None;
+        }
+        let s = WORD_BITS - 1 - rest.leading_zeros() as usize;
+        Some(s * WORD_BITS + (WORD_BITS - 1 - self.summary[s].leading_zeros() as usize))
+    }
+}
+
+/// Returns the index of the first run ending at or after `bit`.
+#[inline(always)]
+fn run_at_or_after(runs: &[(u16, u16)], bit: usize) -> usize {
+    runs.partition_point(|&(_, last)| (last as usize) < bit)
+}
+
+/// Adds `bit` to a run list, extending or merging neighbouring runs.
+fn insert_run(runs: &mut Vec<(u16, u16)>, bit: usize) -> bool {
+    let i = run_at_or_after(runs, bit);
+    if runs.get(i).is_some_and(|&(first, _)| first as usize <= bit) {
+        return false;
+    }
+    let joins_prev = i > 0 && runs[i - 1].1 as usize + 1 == bit;
+    let joins_next = runs
+        .get(i)
+        .is_some_and(|&(first, _)| first as usize == bit + 1);
+    match (joins_prev, joins_next) {
+        (true, true) => {
+            runs[i - 1].1 = runs[i].1;
+            runs.remove(i);
+        }
+        (true, false) => runs[i - 1].1 = bit as u16,
+        (false, true) => runs[i].0 = bit as u16,
+        (false, false) => runs.insert(i, (bit as u16, bit as u16)),
+    }
+    true
+}
+
+/// Removes `bit` from a run list, shrinking or splitting its run.
+fn remove_run(runs: &mut Vec<(u16, u16)>, bit: usize) -> bool {
+    let i = run_at_or_after(runs, bit);
+    let Some(&(first, last)) = runs.get(i).filter(|&&(first, _)| first as usize <= bit) else {
+        return false;
+    };
+    let bit = bit as u16;
+    match (first == bit, last == bit) {
+        (true, true) => {
+            runs.remove(i);
+        }
+        (true, false) => runs[i].0 = bit + 1,
+        (false, true) => runs[i].1 = bit - 1,
+        (false, false) => {
+            runs[i].1 = bit - 1;
+            runs.insert(i + 1, (bit + 1, last));
+        }
+    }
+    true
+}
+
+/// Returns `true` if bit `bit` of the words read by `word` is set.
+///
+/// Bitmap containers and [`SetView`](super::SetView) buffers share this and
+/// [`count_ones`], reading their words from memory or from the buffer.
+#[inline(always)]
+pub(super) fn test_bit(word: impl Fn(usize) -> u64, bit: usize) -> bool {
+    word(bit / WORD_BITS) >> (bit % WORD_BITS) & 1 == 1
+}
+
+/// Counts the set bits of the words read by `word` in `start..end`, which
+/// must be non-empty.
+#[inline(always)]
+pub(super) fn count_ones(word: impl Fn(usize) -> u64, start: usize, end: usize) -> usize {
+    let (first, last) = (start / WORD_BITS, (end - 1) / WORD_BITS);
+    let head = !0u64 << (start % WORD_BITS);
+    let tail = !0u64 >> (WORD_BITS - 1 - (end - 1) % WORD_BITS);
+    if first == last {
+        return (word(first) & head & tail).count_ones() as usize;
+    }
+    let middle: usize = (first + 1..last)
+        .map(|w| word(w).count_ones() as usize)
+        .sum();
+    (word(first) & head).count_ones() as usize + middle + (word(last) & tail).count_ones() as usize
+}
+
+/// Returns the position of the `k`-th set bit (0-based) of `word`.
+///
+/// The caller must ensure `word` has more than `k` set bits.
+#[inline(always)]
+fn select_in_word(mut word: u64, k: usize) -> usize {
+    for _ in 0..k {
+        word &= word - 1;
+    }
+    word.trailing_zeros() as usize
+}
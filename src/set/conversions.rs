@@ -1,7 +1,9 @@
-use super::core::Set;
+use super::core::{default_ceiling, Set};
+use super::element::Element;
+use super::error::Error;
 use std::collections::HashSet;
 
-/// Converts a `Vec<usize>` into a `Set`.
+/// Converts a `Vec<T>` into a `Set<T>`.
 ///
 /// # Examples
 ///
@@ -9,13 +11,13 @@ use std::collections::HashSet;
 /// use fastset::Set;
 ///
 /// let vec = vec![1, 2, 3, 4, 5];
-/// let set = Set::from(vec);
+/// let set: Set = Set::from(vec);
 ///
 /// assert!(set.contains(&3));
 /// ```
-impl From<Vec<usize>> for Set {
-    fn from(vec: Vec<usize>) -> Self {
-        let mut set = Set::with_max(vec.iter().max().cloned().unwrap_or(0));
+impl<T: Element, const PAGE: usize> From<Vec<T>> for Set<T, PAGE> {
+    fn from(vec: Vec<T>) -> Self {
+        let mut set = Set::with_max(vec.iter().max().cloned().unwrap_or_default());
         vec.iter().for_each(|&item| {
             set.insert(item);
         });
@@ -23,7 +25,7 @@ impl From<Vec<usize>> for Set {
     }
 }
 
-/// Converts a slice of `usize` into a `Set`.
+/// Converts a slice of `T` into a `Set<T>`.
 ///
 /// # Examples
 ///
@@ -31,12 +33,12 @@ impl From<Vec<usize>> for Set {
 /// use fastset::Set;
 ///
 /// let items = &[1, 2, 3, 4, 5];
-/// let set = Set::from(items);
+/// let set: Set = Set::from(items);
 ///
 /// assert!(set.contains(&3));
 /// ```
-impl<'a> From<&'a [usize]> for Set {
-    fn from(slice: &'a [usize]) -> Self {
+impl<'a, T: Element, const PAGE: usize> From<&'a [T]> for Set<T, PAGE> {
+    fn from(slice: &'a [T]) -> Self {
         let max_element = slice.iter().max().cloned().unwrap_or_default();
         let mut set = Set::with_max(max_element);
         slice.iter().for_each(|&item| {
@@ -46,7 +48,7 @@ impl<'a> From<&'a [usize]> for Set {
     }
 }
 
-/// Converts an array of `usize` into a `Set`.
+/// Converts an array of `T` into a `Set<T>`.
 ///
 /// # Examples
 ///
@@ -54,13 +56,13 @@ impl<'a> From<&'a [usize]> for Set {
 /// use fastset::Set;
 ///
 /// let items = &[1, 2, 3, 4, 5];
-/// let set = Set::from(items);
+/// let set: Set = Set::from(items);
 ///
 /// assert!(set.contains(&3));
 /// ```
-impl<const N: usize> From<&[usize; N]> for Set {
-    fn from(array: &[usize; N]) -> Self {
-        let max_element = *array.iter().max().unwrap_or(&0);
+impl<T: Element, const N: usize, const PAGE: usize> From<&[T; N]> for Set<T, PAGE> {
+    fn from(array: &[T; N]) -> Self {
+        let max_element = array.iter().max().cloned().unwrap_or_default();
         let mut set = Set::with_max(max_element);
         array.iter().for_each(|&item| {
             set.insert(item);
@@ -69,7 +71,7 @@ impl<const N: usize> From<&[usize; N]> for Set {
     }
 }
 
-/// Converts a `HashSet<usize>` into a `Set`.
+/// Converts a `HashSet<T>` into a `Set<T>`.
 ///
 /// # Examples
 ///
@@ -82,13 +84,13 @@ impl<const N: usize> From<&[usize; N]> for Set {
 /// hash_set.insert(2);
 /// hash_set.insert(3);
 ///
-/// let set = Set::from(hash_set);
+/// let set: Set = Set::from(hash_set);
 ///
 /// assert!(set.contains(&3));
 /// ```
-impl From<HashSet<usize>> for Set {
-    fn from(hashset: HashSet<usize>) -> Self {
-        let mut set = Set::with_max(*hashset.iter().max().unwrap_or(&0));
+impl<T: Element, const PAGE: usize> From<HashSet<T>> for Set<T, PAGE> {
+    fn from(hashset: HashSet<T>) -> Self {
+        let mut set = Set::with_max(hashset.iter().max().cloned().unwrap_or_default());
         hashset.iter().for_each(|&item| {
             set.insert(item);
         });
@@ -96,7 +98,7 @@ impl From<HashSet<usize>> for Set {
     }
 }
 
-/// Converts a reference to `HashSet<usize>` into a `Set`.
+/// Converts a reference to `HashSet<T>` into a `Set<T>`.
 ///
 /// # Examples
 ///
@@ -109,13 +111,13 @@ impl From<HashSet<usize>> for Set {
 /// hash_set.insert(2);
 /// hash_set.insert(3);
 ///
-/// let set = Set::from(&hash_set);
+/// let set: Set = Set::from(&hash_set);
 ///
 /// assert!(set.contains(&3));
 /// ```
-impl<'a> From<&'a HashSet<usize>> for Set {
-    fn from(hashset: &'a HashSet<usize>) -> Self {
-        let mut set = Set::with_max(*hashset.iter().max().unwrap_or(&0) + 1);
+impl<'a, T: Element, const PAGE: usize> From<&'a HashSet<T>> for Set<T, PAGE> {
+    fn from(hashset: &'a HashSet<T>) -> Self {
+        let mut set = Set::with_max(hashset.iter().max().cloned().unwrap_or_default());
         hashset.iter().for_each(|&item| {
             set.insert(item);
         });
@@ -123,48 +125,48 @@ impl<'a> From<&'a HashSet<usize>> for Set {
     }
 }
 
-/// Extends the `Set` with elements from an iterator over `usize` values.
+/// Extends the `Set` with elements from an iterator over values.
 ///
 /// # Examples
 ///
 /// ```
 /// use fastset::Set;
 ///
-/// let mut set = Set::with_max(0);
+/// let mut set: Set = Set::with_max(0);
 /// set.extend(vec![1, 2, 3]);
 ///
 /// assert!(set.contains(&2));
 /// ```
-impl Extend<usize> for Set {
-    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+impl<T: Element, const PAGE: usize> Extend<T> for Set<T, PAGE> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         iter.into_iter().for_each(|elem| {
             self.insert(elem);
         });
     }
 }
 
-/// Extends the `Set` with elements from an iterator over references to `usize` values.
+/// Extends the `Set` with elements from an iterator over references to values.
 ///
 /// # Examples
 ///
 /// ```
 /// use fastset::Set;
 ///
-/// let mut set = Set::with_max(0);
+/// let mut set: Set = Set::with_max(0);
 /// let values = vec![1, 2, 3];
 /// set.extend(values.iter());
 ///
 /// assert!(set.contains(&2));
 /// ```
-impl<'a> Extend<&'a usize> for Set {
-    fn extend<I: IntoIterator<Item = &'a usize>>(&mut self, iter: I) {
+impl<'a, T: Element, const PAGE: usize> Extend<&'a T> for Set<T, PAGE> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
         iter.into_iter().for_each(|&elem| {
             self.insert(elem);
         });
     }
 }
 
-/// Converts an iterator over `usize` values into a `Set`.
+/// Converts an iterator over values into a `Set`.
 ///
 /// # Examples
 ///
@@ -175,10 +177,10 @@ impl<'a> Extend<&'a usize> for Set {
 ///
 /// assert!(set.contains(&3));
 /// ```
-impl FromIterator<usize> for Set {
-    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
-        let collected: Vec<usize> = iter.into_iter().collect();
-        let max_element = collected.iter().max().cloned().unwrap_or(0);
+impl<T: Element, const PAGE: usize> FromIterator<T> for Set<T, PAGE> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let collected: Vec<T> = iter.into_iter().collect();
+        let max_element = collected.iter().max().cloned().unwrap_or_default();
         let mut set = Set::with_max(max_element);
         collected.into_iter().for_each(|i| {
             set.insert(i);
@@ -187,7 +189,7 @@ impl FromIterator<usize> for Set {
     }
 }
 
-/// Converts an iterator over references to `usize` values into a `Set`.
+/// Converts an iterator over references to values into a `Set`.
 ///
 /// # Examples
 ///
@@ -199,10 +201,10 @@ impl FromIterator<usize> for Set {
 ///
 /// assert!(set.contains(&2));
 /// ```
-impl<'a> FromIterator<&'a usize> for Set {
-    fn from_iter<I: IntoIterator<Item = &'a usize>>(iter: I) -> Self {
-        let collected: Vec<usize> = iter.into_iter().cloned().collect();
-        let max_element = collected.iter().max().cloned().unwrap_or(0);
+impl<'a, T: Element, const PAGE: usize> FromIterator<&'a T> for Set<T, PAGE> {
+    fn from_iter<I: IntoIterator<Item = &'a T>>(iter: I) -> Self {
+        let collected: Vec<T> = iter.into_iter().cloned().collect();
+        let max_element = collected.iter().max().cloned().unwrap_or_default();
         let mut set = Set::with_max(max_element);
         collected.into_iter().for_each(|i| {
             set.insert(i);
@@ -210,3 +212,354 @@ impl<'a> FromIterator<&'a usize> for Set {
         set
     }
 }
+
+impl<T: Element, const PAGE: usize> Set<T, PAGE> {
+    /// Builds a Set from `usize` ids, reporting ids that do not fit `T` or the
+    /// default [ceiling](Set::ceiling), and allocation failures, as errors.
+    ///
+    /// This covers every element type, including `usize`, whose `TryFrom`
+    /// conversions are the infallible ones derived from its `From` impls.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfRange`] for the first id above the default
+    /// ceiling, and [`Error::AllocationFailed`] if the Set cannot grow to
+    /// hold the ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::{Error, Set};
+    ///
+    /// let set: Set<u16> = Set::try_from_values(&[3, 1, 2])?;
+    /// assert_eq!(set.len(), 3);
+    ///
+    /// assert!(matches!(
+    ///     Set::<usize>::try_from_values(&[1, usize::MAX]),
+    ///     Err(Error::OutOfRange { .. })
+    /// ));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn try_from_values(values: &[usize]) -> Result<Self, Error> {
+        let ceiling = default_ceiling::<T>().to_index();
+        let mut converted = Vec::new();
+        converted.try_reserve(values.len())?;
+        for &value in values {
+            if value > ceiling {
+                return Err(Error::OutOfRange {
+                    value: value as u64,
+                    ceiling: ceiling as u64,
+                });
+            }
+            converted.push(T::from_index(value));
+        }
+        Set::try_from_iter(converted)
+    }
+}
+
+/// Implements `TryFrom<Vec<usize>>` and `TryFrom<&[usize]>` for sets of
+/// narrower element types, rejecting ids that do not fit.
+///
+/// `Set<usize>` already gets an infallible `TryFrom` through its `From`
+/// impls; use [`Set::try_from_values`] for a fallible build of one.
+macro_rules! impl_try_from_usize {
+    ($($t:ty),*) => {
+        $(
+            impl<const PAGE: usize> TryFrom<Vec<usize>> for Set<$t, PAGE> {
+                type Error = Error;
+
+                fn try_from(vec: Vec<usize>) -> Result<Self, Error> {
+                    Self::try_from_values(&vec)
+                }
+            }
+
+            impl<'a, const PAGE: usize> TryFrom<&'a [usize]> for Set<$t, PAGE> {
+                type Error = Error;
+
+                fn try_from(slice: &'a [usize]) -> Result<Self, Error> {
+                    Self::try_from_values(slice)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_usize!(u16, u32, u64);
+
+/// Cookie opening a Roaring stream without run containers.
+const ROARING_COOKIE_NO_RUNS: u32 = 12346;
+
+/// Cookie opening a Roaring stream with run containers, in its low 16 bits.
+const ROARING_COOKIE: u32 = 12347;
+
+/// Streams with run containers and fewer containers than this omit the offsets.
+const ROARING_NO_OFFSET_THRESHOLD: usize = 4;
+
+/// Largest cardinality of a Roaring array container.
+const ROARING_ARRAY_MAX: usize = 4096;
+
+impl<T: Element, const PAGE: usize> Set<T, PAGE> {
+    /// Reads a Set from the Roaring portable serialization format, as written
+    /// by the Java, Go and C implementations of 32-bit Roaring bitmaps.
+    ///
+    /// The stream is fully validated. Values are decoded container by container
+    /// and added through [`try_insert`](Set::try_insert), into a Set whose
+    /// maximum is the largest value. Trailing bytes after the last container
+    /// are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidRoaring`] if the stream is truncated or
+    /// malformed, [`Error::OutOfRange`] if a value does not fit `T`, and
+    /// [`Error::AllocationFailed`] if memory for the Set cannot be allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// // {1, 2, 3} as written by a Java `RoaringBitmap`
+    /// let bytes = [
+    ///     0x3a, 0x30, 0, 0, 1, 0, 0, 0, 0, 0, 2, 0, 0x10, 0, 0, 0, 1, 0, 2, 0, 3, 0,
+    /// ];
+    /// let set: Set<u32> = Set::from_roaring_bytes(&bytes).unwrap();
+    /// assert_eq!(set.iter_sorted().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// assert_eq!(set.to_roaring_bytes().unwrap(), bytes);
+    /// ```
+    pub fn from_roaring_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut input = RoaringReader { bytes };
+        let cookie = input.u32()?;
+        let (size, run_flags) = if cookie == ROARING_COOKIE_NO_RUNS {
+            (input.u32()? as usize, None)
+        } else if cookie & 0xffff == ROARING_COOKIE {
+            let size = (cookie >> 16) as usize + 1;
+            (size, Some(input.take(size.div_ceil(8))?))
+        } else {
+            return Err(Error::InvalidRoaring("unknown cookie"));
+        };
+        let header = input.take(
+            size.checked_mul(4)
+                .ok_or(Error::InvalidRoaring("too many containers"))?,
+        )?;
+        if run_flags.is_none() || size >= ROARING_NO_OFFSET_THRESHOLD {
+            input.take(size * 4)?;
+        }
+
+        let mut values = Vec::new();
+        let mut previous = None;
+        for (i, entry) in header.chunks_exact(4).enumerate() {
+            let key = u16::from_le_bytes([entry[0], entry[1]]) as usize;
+            let cardinality = u16::from_le_bytes([entry[2], entry[3]]) as usize + 1;
+            if previous.is_some_and(|previous| previous >= key) {
+                return Err(Error::InvalidRoaring("container keys are not ascending"));
+            }
+            previous = Some(key);
+            let start = values.len();
+            values.try_reserve(cardinality)?;
+            let base = key << 16;
+            if run_flags.is_some_and(|flags| flags[i / 8] >> (i % 8) & 1 == 1) {
+                let runs = input.u16()? as usize;
+                let mut next = 0;
+                for _ in 0..runs {
+                    let (first, length) = (input.u16()? as usize, input.u16()? as usize);
+                    if first < next || first + length > u16::MAX as usize {
+                        return Err(Error::InvalidRoaring("runs overlap or overflow"));
+                    }
+                    if values.len() - start + length + 1 > cardinality {
+                        return Err(Error::InvalidRoaring("runs exceed the cardinality"));
+                    }
+                    values.extend((first..=first + length).map(|low| base | low));
+                    next = first + length + 1;
+                }
+            } else if cardinality <= ROARING_ARRAY_MAX {
+                for _ in 0..cardinality {
+                    let low = input.u16()? as usize;
+                    if values.len() > start && values[values.len() - 1] >= base | low {
+                        return Err(Error::InvalidRoaring("array is not strictly ascending"));
+                    }
+                    values.push(base | low);
+                }
+            } else {
+                let words = input.take(8192)?;
+                for (w, word) in words.chunks_exact(8).enumerate() {
+                    let mut word = u64::from_le_bytes(word.try_into().expect("eight bytes"));
+                    while word != 0 {
+                        values.push(base | (w * 64 + word.trailing_zeros() as usize));
+                        word &= word - 1;
+                    }
+                }
+            }
+            if values.len() - start != cardinality {
+                return Err(Error::InvalidRoaring("container cardinality mismatch"));
+            }
+        }
+
+        let ceiling: T = default_ceiling();
+        let max = values.last().copied().unwrap_or_default();
+        if max > ceiling.to_index() {
+            return Err(Error::OutOfRange {
+                value: max as u64,
+                ceiling: ceiling.to_index() as u64,
+            });
+        }
+        let mut set = Set::try_with_max(T::from_index(max))?;
+        set.elements.try_reserve(values.len())?;
+        for value in values {
+            set.try_insert(T::from_index(value))?;
+        }
+        Ok(set)
+    }
+
+    /// Writes the Set in the Roaring portable serialization format, readable
+    /// by the Java, Go and C implementations of 32-bit Roaring bitmaps.
+    ///
+    /// Each 2¹⁶-value block is written as whichever of an array, a bitmap or a
+    /// run container is smallest, matching Roaring's own `runOptimize`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfRange`] if the Set holds a value above `u32::MAX`,
+    /// which 32-bit Roaring bitmaps cannot represent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set<u64> = (0..100_000).chain([7_000_000]).collect();
+    /// let bytes = set.to_roaring_bytes().unwrap();
+    ///
+    /// let back: Set<u64> = Set::from_roaring_bytes(&bytes).unwrap();
+    /// assert_eq!(back, set);
+    /// ```
+    pub fn to_roaring_bytes(&self) -> Result<Vec<u8>, Error> {
+        if let Some(max) = self.max().filter(|max| max.to_index() > u32::MAX as usize) {
+            return Err(Error::OutOfRange {
+                value: max.to_index() as u64,
+                ceiling: u32::MAX as u64,
+            });
+        }
+        // Pick each container by its serialized size, as `runOptimize` does
+        let containers: Vec<(usize, usize, bool)> = self
+            .indicator
+            .chunk_words()
+            .map(|(c, words)| {
+                let ones: usize = words.iter().map(|w| w.count_ones() as usize).sum();
+                let runs = runs_in(&words).count();
+                let others = match ones <= ROARING_ARRAY_MAX {
+                    true => 2 * ones,
+                    false => 8192,
+                };
+                (c, ones, 2 + 4 * runs < others)
+            })
+            .collect();
+        let size = containers.len();
+        let has_runs = containers.iter().any(|&(_, _, run)| run);
+        let with_offsets = !has_runs || size >= ROARING_NO_OFFSET_THRESHOLD;
+
+        let mut bytes = Vec::new();
+        if has_runs {
+            bytes.extend_from_slice(&(ROARING_COOKIE | ((size as u32 - 1) << 16)).to_le_bytes());
+            let mut flags = vec![0u8; size.div_ceil(8)];
+            for (i, &(_, _, run)) in containers.iter().enumerate() {
+                flags[i / 8] |= (run as u8) << (i % 8);
+            }
+            bytes.extend_from_slice(&flags);
+        } else {
+            bytes.extend_from_slice(&ROARING_COOKIE_NO_RUNS.to_le_bytes());
+            bytes.extend_from_slice(&(size as u32).to_le_bytes());
+        }
+        for &(c, ones, _) in &containers {
+            bytes.extend_from_slice(&(c as u16).to_le_bytes());
+            bytes.extend_from_slice(&((ones - 1) as u16).to_le_bytes());
+        }
+        let offsets = bytes.len();
+        if with_offsets {
+            bytes.resize(offsets + 4 * size, 0);
+        }
+        let chunks = self.indicator.chunk_words().zip(&containers);
+        for (i, ((_, words), &(_, ones, run))) in chunks.enumerate() {
+            if with_offsets {
+                let offset = (bytes.len() as u32).to_le_bytes();
+                bytes[offsets + 4 * i..offsets + 4 * i + 4].copy_from_slice(&offset);
+            }
+            if run {
+                bytes.extend_from_slice(&(runs_in(&words).count() as u16).to_le_bytes());
+                for (first, last) in runs_in(&words) {
+                    bytes.extend_from_slice(&(first as u16).to_le_bytes());
+                    bytes.extend_from_slice(&((last - first) as u16).to_le_bytes());
+                }
+            } else if ones <= ROARING_ARRAY_MAX {
+                for (w, &word) in words.iter().enumerate() {
+                    let mut word = word;
+                    while word != 0 {
+                        let low = w * 64 + word.trailing_zeros() as usize;
+                        bytes.extend_from_slice(&(low as u16).to_le_bytes());
+                        word &= word - 1;
+                    }
+                }
+            } else {
+                for word in words.iter() {
+                    bytes.extend_from_slice(&word.to_le_bytes());
+                }
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+/// Iterates over the maximal runs of set bits in a chunk's words, as
+/// inclusive `(first, last)` pairs.
+fn runs_in(words: &[u64]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut next = 0;
+    std::iter::from_fn(move || {
+        let first = find_bit(words, next, true)?;
+        let last = find_bit(words, first, false).unwrap_or(words.len() * 64) - 1;
+        next = last + 1;
+        Some((first, last))
+    })
+}
+
+/// Returns the first bit at or after `from` that equals `set`, if any.
+fn find_bit(words: &[u64], from: usize, set: bool) -> Option<usize> {
+    let mut w = from / 64;
+    let mut word = *words.get(w)?;
+    word = if set { word } else { !word };
+    word &= !0u64 << (from % 64);
+    loop {
+        if word != 0 {
+            return Some(w * 64 + word.trailing_zeros() as usize);
+        }
+        w += 1;
+        word = *words.get(w)?;
+        word = if set { word } else { !word };
+    }
+}
+
+/// Cursor over a Roaring stream that reports truncation as an error.
+struct RoaringReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> RoaringReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len > self.bytes.len() {
+            return Err(Error::InvalidRoaring("stream is truncated"));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_le_bytes(
+            self.take(2)?.try_into().expect("two bytes"),
+        ))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(
+            self.take(4)?.try_into().expect("four bytes"),
+        ))
+    }
+}
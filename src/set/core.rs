@@ -1,22 +1,60 @@
-use super::MAX_CAPACITY;
-use nanorand::{Rng, WyRand};
-use serde::{Deserialize, Serialize};
+use super::element::Element;
+use super::error::{Error, InsertOutcome};
+use super::indicator::{Indicator, Ones};
+use super::iterators::{IntoSortedIter, SetRange, SortedIter};
+use super::positions::Positions;
+use super::random::uniform_index;
+use rand_core::RngCore;
 
 /// Represents a custom Set implementation.
-#[derive(Clone, Serialize, Deserialize)]
-pub struct Set {
-    pub(super) indicator: Vec<bool>,
-    pub(super) elements: Vec<usize>,
-    pub(super) pages: Vec<Option<Vec<usize>>>,
-    pub(super) max: usize,
-    pub(super) current_max: Option<usize>,
-    pub(super) current_min: Option<usize>,
+///
+/// The element type `T` defaults to `usize` and can be any [`Element`]:
+/// `u16`, `u32`, `u64` or `usize`. Elements and their positions are stored
+/// as `T`, so a narrower type shrinks the set's memory footprint accordingly.
+///
+/// `PAGE` is the number of values covered by each page of element positions and
+/// must be a power of two; it defaults to 16. Smaller pages waste fewer slots when
+/// values are sparse, while larger pages cut per-page overhead when they are dense.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::Set;
+///
+/// // A sparse universe, paged 4 values at a time
+/// let mut sparse: Set<u32, 4> = Set::with_max(1_000_000);
+/// sparse.insert(999_999);
+/// assert!(sparse.contains(&999_999));
+/// ```
+///
+/// Every way of building a Set checks the page size, including loading one:
+///
+/// ```compile_fail
+/// use fastset::Set;
+///
+/// // Fails to compile: 12 is not a power of two
+/// let set = Set::<usize, 12>::read_from(&[][..]);
+/// ```
+#[derive(Clone)]
+pub struct Set<T = usize, const PAGE: usize = 16> {
+    pub(super) indicator: Indicator,
+    pub(super) elements: Vec<T>,
+    pub(super) positions: Positions<T, PAGE>,
+    pub(super) max: T,
+    pub(super) ceiling: T,
+    pub(super) current_max: Option<T>,
+    pub(super) current_min: Option<T>,
 }
 
-impl Set {
-    pub(super) const PAGE_SIZE: usize = 16;
-    pub(super) const PAGE_SHIFT: usize = Self::PAGE_SIZE.trailing_zeros() as usize;
-    pub(super) const PAGE_MASK: usize = Self::PAGE_SIZE - 1;
+/// Returns the largest value a Set of `T` can hold: `T::MAX`, or `usize::MAX - 1`
+/// when that does not fit, as the slots `0..=max` must be countable in a `usize`.
+pub(super) fn default_ceiling<T: Element>() -> T {
+    T::from_index(T::MAX.to_index().min(usize::MAX - 1))
+}
+
+impl<T: Element, const PAGE: usize> Set<T, PAGE> {
+    // Evaluated by the constructors, so a bad page size fails to compile
+    const PAGE_CHECK: () = assert!(PAGE.is_power_of_two(), "PAGE must be a power of two");
 
     /// Creates a new Set with the specified maximum element.
     ///
@@ -24,28 +62,112 @@ impl Set {
     ///
     /// * `max_element` - The maximum element that the Set can contain.
     ///
+    /// # Panics
+    ///
+    /// Panics if `max_element` is above the default [ceiling](Set::ceiling),
+    /// which only `usize::MAX` is.
+    ///
     /// # Examples
     ///
     /// ```
     /// use fastset::Set;
     ///
     /// // Create a new Set with a maximum capacity of 100 elements.
-    /// let set = Set::with_max(100);
+    /// let set: Set = Set::with_max(100);
     /// ```
-    pub fn with_max(max_element: usize) -> Self {
-        if max_element > MAX_CAPACITY {
-            panic!("max_element is larger than MAX_ELEMENTS");
-        }
+    pub fn with_max(max_element: T) -> Self {
+        let () = Self::PAGE_CHECK;
+        let ceiling = default_ceiling();
+        Self::check_ceiling(max_element, ceiling);
+        let max = max_element.to_index();
         Self {
-            indicator: vec![false; max_element.saturating_add(1)], // Always at least 1 slot
-            elements: Vec::with_capacity(std::cmp::min(max_element.saturating_add(1), 1024)),
-            pages: Vec::new(),
+            indicator: Indicator::with_len(max.saturating_add(1)), // Always at least 1 slot
+            elements: Vec::with_capacity(std::cmp::min(max.saturating_add(1), 1024)),
+            positions: Positions::new(),
             max: max_element,
+            ceiling,
             current_max: None,
             current_min: None,
         }
     }
 
+    /// Creates a new Set with the specified maximum element, reporting failures
+    /// instead of panicking or aborting.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfRange`] if `max_element` is above the default
+    /// [ceiling](Set::ceiling), and [`Error::AllocationFailed`] if the initial
+    /// element list cannot be allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::{Error, Set};
+    ///
+    /// let set: Set = Set::try_with_max(100)?;
+    /// assert_eq!(set.max_value(), 100);
+    ///
+    /// assert!(matches!(
+    ///     Set::<usize>::try_with_max(usize::MAX),
+    ///     Err(Error::OutOfRange { .. })
+    /// ));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn try_with_max(max_element: T) -> Result<Self, Error> {
+        let () = Self::PAGE_CHECK;
+        let ceiling = default_ceiling();
+        Self::try_check_ceiling(max_element, ceiling)?;
+        let max = max_element.to_index();
+        let mut elements = Vec::new();
+        elements.try_reserve(std::cmp::min(max + 1, 1024))?;
+        Ok(Self {
+            indicator: Indicator::with_len(max + 1),
+            elements,
+            positions: Positions::new(),
+            max: max_element,
+            ceiling,
+            current_max: None,
+            current_min: None,
+        })
+    }
+
+    /// Builds a Set from the given values, reporting failures instead of
+    /// panicking or aborting.
+    ///
+    /// This is the fallible counterpart of `collect` and `Set::from`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfRange`] if a value is above the default
+    /// [ceiling](Set::ceiling), and [`Error::AllocationFailed`] if the Set
+    /// cannot grow to hold the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set = Set::try_from_iter([3, 1, 2]).unwrap();
+    /// assert_eq!(set.len(), 3);
+    ///
+    /// assert!(Set::<usize>::try_from_iter([1, usize::MAX]).is_err());
+    /// ```
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Error> {
+        let values = iter.into_iter();
+        let mut collected = Vec::new();
+        collected.try_reserve(values.size_hint().0)?;
+        for value in values {
+            collected.try_reserve(1)?;
+            collected.push(value);
+        }
+        let mut set = Self::try_with_max(collected.iter().max().copied().unwrap_or_default())?;
+        for value in collected {
+            set.try_insert(value)?;
+        }
+        Ok(set)
+    }
+
     /// For backward compatibility - creates a new Set with the specified maximum element.
     /// This method is deprecated in favor of `with_max`.
     ///
@@ -59,10 +181,10 @@ impl Set {
     /// use fastset::Set;
     ///
     /// // Create a new Set with a maximum capacity of 100 elements.
-    /// let set = Set::new(100);
+    /// let set: Set = Set::new(100);
     /// ```
     #[deprecated(since = "0.5.0", note = "Use with_max instead")]
-    pub fn new(max_element: usize) -> Self {
+    pub fn new(max_element: T) -> Self {
         Self::with_max(max_element)
     }
 
@@ -79,21 +201,31 @@ impl Set {
     ///
     /// * `capacity` - The initial capacity of the Set (max value that can be stored).
     ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is above the default [ceiling](Set::ceiling),
+    /// which only `usize::MAX` is.
+    ///
     /// # Examples
     ///
     /// ```
     /// use fastset::Set;
     ///
     /// // Create a new Set with an initial capacity of 50 elements.
-    /// let set = Set::with_capacity(50);
+    /// let set: Set = Set::with_capacity(50);
     /// ```
     #[inline(always)]
-    pub fn with_capacity(capacity: usize) -> Self {
+    pub fn with_capacity(capacity: T) -> Self {
+        let () = Self::PAGE_CHECK;
+        let ceiling = default_ceiling();
+        Self::check_ceiling(capacity, ceiling);
+        let slots = capacity.to_index() + 1;
         Set {
-            indicator: vec![false; capacity.saturating_add(1)], // Always at least 1 slot
-            elements: Vec::with_capacity(std::cmp::min(capacity, 1024)),
-            pages: Vec::new(),
+            indicator: Indicator::with_len(slots), // Always at least 1 slot
+            elements: Vec::with_capacity(std::cmp::min(slots - 1, 1024)),
+            positions: Positions::new(),
             max: capacity, // max is now capacity, not capacity-1
+            ceiling,
             current_max: None,
             current_min: None,
         }
@@ -109,10 +241,10 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_capacity(50);
+    /// let mut set: Set = Set::with_capacity(50);
     /// assert_eq!(set.capacity(), 50);
     /// ```
-    pub fn capacity(&self) -> usize {
+    pub fn capacity(&self) -> T {
         self.max // Return max value that can be stored
     }
 
@@ -123,13 +255,90 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// assert_eq!(set.max_value(), 100);
     /// ```
-    pub fn max_value(&self) -> usize {
+    pub fn max_value(&self) -> T {
         self.max
     }
 
+    /// Caps the values the Set may ever hold at `ceiling`, consuming and returning it.
+    ///
+    /// Inserting or reserving above the ceiling panics instead of growing the Set,
+    /// which guards a long-lived Set against stray ids that would otherwise
+    /// allocate far beyond the expected range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ceiling` is below the Set's maximum value, or above the default
+    /// ceiling (see [`Set::ceiling`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let mut set: Set<u64> = Set::with_max(1_000).with_ceiling(5_000_000_000);
+    /// assert!(set.insert(4_999_999_999));
+    /// assert_eq!(set.ceiling(), 5_000_000_000);
+    /// ```
+    ///
+    /// ```should_panic
+    /// use fastset::Set;
+    ///
+    /// let mut set: Set = Set::with_max(100).with_ceiling(1_000);
+    /// set.insert(1_001); // panics: above the ceiling
+    /// ```
+    pub fn with_ceiling(mut self, ceiling: T) -> Self {
+        Self::check_ceiling(ceiling, default_ceiling());
+        if ceiling < self.max {
+            panic!(
+                "ceiling {} is below the set's maximum value {}",
+                ceiling, self.max
+            );
+        }
+        self.ceiling = ceiling;
+        self
+    }
+
+    /// Returns the largest value the Set may ever hold.
+    ///
+    /// Defaults to the largest value of `T`. Since every value up to the maximum
+    /// needs a slot counted in a `usize`, `usize::MAX` itself cannot be stored,
+    /// so the default for `usize` (and `u64` on 64-bit targets) is one less.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set<u32> = Set::with_max(100);
+    /// assert_eq!(set.ceiling(), u32::MAX);
+    /// ```
+    pub fn ceiling(&self) -> T {
+        self.ceiling
+    }
+
+    /// Panics if `value` is above `ceiling`, just like indexing out of bounds.
+    #[inline(always)]
+    pub(super) fn check_ceiling(value: T, ceiling: T) {
+        if let Err(error) = Self::try_check_ceiling(value, ceiling) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Returns [`Error::OutOfRange`] if `value` is above `ceiling`.
+    #[inline(always)]
+    fn try_check_ceiling(value: T, ceiling: T) -> Result<(), Error> {
+        if value > ceiling {
+            return Err(Error::OutOfRange {
+                value: value.to_index() as u64,
+                ceiling: ceiling.to_index() as u64,
+            });
+        }
+        Ok(())
+    }
+
     /// Reserves capacity for at least `new_max_element` additional elements
     /// in the Set.
     ///
@@ -141,31 +350,65 @@ impl Set {
     ///
     /// * `new_max_element` - The new maximum element that the Set can contain.
     ///
+    /// # Panics
+    ///
+    /// Panics if `new_max_element` is above the Set's [ceiling](Set::ceiling).
+    ///
     /// # Examples
     ///
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     ///
     /// // Reserve capacity for at least 200 elements.
     /// set.reserve(200);
     /// ```
     #[inline(always)]
-    pub fn reserve(&mut self, new_max_element: usize) {
+    pub fn reserve(&mut self, new_max_element: T) {
+        Self::check_ceiling(new_max_element, self.ceiling);
         if new_max_element > self.max {
-            let new_size = new_max_element + 1;
-            self.indicator.resize(new_size, false);
+            let new_size = new_max_element.to_index() + 1;
+            self.indicator.resize(new_size);
             // Don't over-reserve elements - they'll be allocated as needed
             self.max = new_max_element;
         }
     }
 
+    /// Raises the Set's maximum value to `new_max_element`, reporting a value
+    /// above the [ceiling](Set::ceiling) instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfRange`] if `new_max_element` is above the ceiling,
+    /// in which case the Set is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::{Error, Set};
+    ///
+    /// let mut set: Set = Set::with_max(100).with_ceiling(1_000);
+    /// assert_eq!(set.try_reserve(500), Ok(()));
+    /// assert_eq!(set.max_value(), 500);
+    /// assert_eq!(
+    ///     set.try_reserve(2_000),
+    ///     Err(Error::OutOfRange { value: 2_000, ceiling: 1_000 })
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn try_reserve(&mut self, new_max_element: T) -> Result<(), Error> {
+        Self::try_check_ceiling(new_max_element, self.ceiling)?;
+        self.reserve(new_max_element);
+        Ok(())
+    }
+
     /// Shrinks the capacity of the Set to the specified minimum capacity.
     ///
     /// It will reduce the capacity of the Set to fit the specified `min_capacity`.
     /// If the current capacity is already smaller than `min_capacity`, this method
-    /// does nothing.
+    /// does nothing. A `min_capacity` above the [ceiling](Set::ceiling) is
+    /// clamped to it.
     ///
     /// # Arguments
     ///
@@ -176,7 +419,7 @@ impl Set {
     /// ```
     /// use fastset::{set, Set};
     ///
-    /// let mut set = Set::with_capacity(10);
+    /// let mut set: Set = Set::with_capacity(10);
     ///
     /// set.insert(1);
     /// set.insert(2);
@@ -188,23 +431,18 @@ impl Set {
     /// assert!(set.capacity() >= 3);
     /// ```
     #[inline(always)]
-    pub fn shrink_to(&mut self, min_capacity: usize) {
-        self.elements.shrink_to(min_capacity);
+    pub fn shrink_to(&mut self, min_capacity: T) {
+        let min_capacity = min_capacity.min(self.ceiling);
+        self.elements.shrink_to(min_capacity.to_index());
         let new_max = if self.is_empty() {
             min_capacity
         } else {
-            std::cmp::max(self.current_max.unwrap_or(0), min_capacity)
+            std::cmp::max(self.current_max.unwrap_or_default(), min_capacity)
         };
         self.max = new_max;
-        self.indicator.resize(new_max + 1, false);
+        self.indicator.resize(new_max.to_index() + 1);
         self.indicator.shrink_to_fit();
-
-        // Clean up pages
-        if !self.pages.is_empty() {
-            let max_page_idx = Self::page_indices(new_max).0;
-            self.pages.truncate(max_page_idx + 1);
-            self.pages.shrink_to_fit();
-        }
+        self.positions.shrink_to_fit();
     }
 
     /// Shrinks the capacity of the Set as much as possible.
@@ -216,7 +454,7 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_capacity(10);
+    /// let mut set: Set = Set::with_capacity(10);
     /// set.insert(1);
     /// set.insert(2);
     /// set.insert(3);
@@ -230,30 +468,17 @@ impl Set {
 
         // If the set is empty, keep a minimal indicator size
         if self.is_empty() {
-            self.max = 0;
-            self.indicator = vec![false; 1];
+            self.max = T::default();
+            self.indicator = Indicator::with_len(1);
             self.indicator.shrink_to_fit();
-            self.pages.clear();
-            self.pages.shrink_to_fit();
+            self.positions.clear();
+            self.positions.shrink_to_fit();
         } else {
             // Otherwise resize to fit the current maximum value
-            self.max = self.current_max.unwrap_or(0);
-            self.indicator.resize(self.max + 1, false);
+            self.max = self.current_max.unwrap_or_default();
+            self.indicator.resize(self.max.to_index() + 1);
             self.indicator.shrink_to_fit();
-
-            // Clean up pages that are now out of range
-            if !self.pages.is_empty() {
-                let max_page_idx = Self::page_indices(self.max).0;
-                self.pages.truncate(max_page_idx + 1);
-                self.pages.shrink_to_fit();
-
-                // Shrink individual page allocations
-                for page in &mut self.pages {
-                    if let Some(p) = page {
-                        p.shrink_to_fit();
-                    }
-                }
-            }
+            self.positions.shrink_to_fit();
         }
     }
 
@@ -264,7 +489,7 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     ///
@@ -282,7 +507,7 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// assert!(set.is_empty());
     ///
     /// set.insert(5);
@@ -295,12 +520,15 @@ impl Set {
 
     /// Returns an iterator over the elements in the Set.
     ///
+    /// Elements are yielded in storage order, which depends on the history of
+    /// insertions and removals. Use [`Set::iter_sorted`] for ascending order.
+    ///
     /// # Examples
     ///
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     ///
@@ -309,10 +537,51 @@ impl Set {
     /// }
     /// ```
     #[inline(always)]
-    pub fn iter(&self) -> std::slice::Iter<'_, usize> {
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
         self.elements.iter()
     }
 
+    /// Returns an iterator over the elements in the Set, in ascending order.
+    ///
+    /// Unlike [`Set::iter`], whose order depends on the history of insertions and
+    /// removals, this scans the indicator 64 slots at a time and never sorts.
+    /// Empty words and unallocated chunks are skipped through their summaries.
+    /// Use `.rev()` for descending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let mut set: Set = Set::from(vec![5, 1, 4, 2, 3]);
+    /// set.remove(&1);
+    ///
+    /// assert_eq!(set.iter_sorted().collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+    /// assert_eq!(set.iter_sorted().rev().collect::<Vec<_>>(), vec![5, 4, 3, 2]);
+    /// ```
+    #[inline(always)]
+    pub fn iter_sorted(&self) -> SortedIter<'_, T> {
+        SortedIter::new(self.indicator.ones(), self.len())
+    }
+
+    /// Consumes the Set, returning an iterator over its elements in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set = Set::from(vec![9, 3, 6]);
+    ///
+    /// assert_eq!(set.into_sorted_iter().collect::<Vec<_>>(), vec![3, 6, 9]);
+    /// ```
+    #[inline(always)]
+    pub fn into_sorted_iter(self) -> IntoSortedIter<T> {
+        let remaining = self.len();
+        let len = self.indicator.len();
+        IntoSortedIter::new(Ones::new(self.indicator, 0, len), remaining)
+    }
+
     /// Removes all elements from the Set.
     ///
     /// # Examples
@@ -320,7 +589,7 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     ///
@@ -334,16 +603,11 @@ impl Set {
     pub fn clear(&mut self) {
         // More efficient clearing - only clear the parts that are actually used
         for &elem in &self.elements {
-            self.indicator[elem] = false;
+            self.indicator.remove(elem.to_index());
         }
         self.elements.clear();
 
-        // Clear pages more efficiently
-        for page in &mut self.pages {
-            if let Some(p) = page {
-                p.fill(0);
-            }
-        }
+        self.positions.clear();
 
         self.current_max = None;
         self.current_min = None;
@@ -353,17 +617,23 @@ impl Set {
     ///
     /// Returns `true` if the element was successfully inserted,
     /// and `false` if the element was already present in the Set.
+    /// Values above the current maximum grow the Set up to its ceiling.
     ///
     /// # Arguments
     ///
     /// * `value` - The value to insert into the Set.
     ///
+    /// # Panics
+    ///
+    /// Panics if `value` is above the Set's [ceiling](Set::ceiling), so that
+    /// an out-of-range value is never mistaken for a duplicate.
+    ///
     /// # Examples
     ///
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     ///
     /// // Inserting a new element
     /// assert!(set.insert(5));
@@ -372,28 +642,65 @@ impl Set {
     /// assert!(!set.insert(5));
     /// ```
     #[inline(always)]
-    pub fn insert(&mut self, value: usize) -> bool {
+    pub fn insert(&mut self, value: T) -> bool {
         // Fast path: already in bounds
-        if value < self.indicator.len() {
+        if value.to_index() < self.indicator.len() {
             return self.insert_unchecked(value);
         }
 
-        // Check max capacity
-        if value >= MAX_CAPACITY {
-            return false;
-        }
-
-        // Optimized resize for small increments
-        if value == self.max + 1 {
-            self.indicator.push(false);
-            self.max = value;
-        } else {
-            self.reserve(value);
-        }
+        // Growing the packed indicator is amortized, so a single reserve covers both
+        // small increments and large jumps
+        self.reserve(value);
 
         self.insert_unchecked(value)
     }
 
+    /// Inserts an element into the Set, telling a duplicate apart from a value
+    /// the Set may not hold and reporting allocation failures instead of aborting.
+    ///
+    /// The element list, the position page and the indicator chunk the value
+    /// needs are all allocated fallibly before the value is inserted, so on
+    /// error the Set holds the same values. In hybrid mode a container that
+    /// grows or converts on insert still allocates infallibly, but it is at
+    /// most one 8 KB bitmap.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfRange`] if `value` is above the Set's
+    /// [ceiling](Set::ceiling), and [`Error::AllocationFailed`] if the Set
+    /// cannot grow to hold it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::{Error, InsertOutcome, Set};
+    ///
+    /// let mut set: Set = Set::with_max(100).with_ceiling(1_000);
+    ///
+    /// assert_eq!(set.try_insert(5), Ok(InsertOutcome::Inserted));
+    /// assert_eq!(set.try_insert(5), Ok(InsertOutcome::AlreadyPresent));
+    /// assert_eq!(
+    ///     set.try_insert(1_001),
+    ///     Err(Error::OutOfRange { value: 1_001, ceiling: 1_000 })
+    /// );
+    /// ```
+    pub fn try_insert(&mut self, value: T) -> Result<InsertOutcome, Error> {
+        Self::try_check_ceiling(value, self.ceiling)?;
+        if self.contains(&value) {
+            return Ok(InsertOutcome::AlreadyPresent);
+        }
+        let index = value.to_index();
+        self.elements.try_reserve(1)?;
+        // A directory left empty by a failure below is harmless and is filled
+        // by the next insert that lands in it
+        self.positions.try_reserve(index)?;
+        // Last, as the chunk it may allocate must be filled by the insert below
+        self.indicator.try_reserve(index)?;
+        self.reserve(value);
+        self.insert_unchecked(value);
+        Ok(InsertOutcome::Inserted)
+    }
+
     /// Removes an element from the Set.
     ///
     /// Returns `true` if the element was successfully removed,
@@ -408,7 +715,7 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     ///
     /// // Removing an existing element
@@ -418,8 +725,8 @@ impl Set {
     /// assert!(!set.remove(&10));
     /// ```
     #[inline(always)]
-    pub fn remove(&mut self, value: &usize) -> bool {
-        if *value < self.indicator.len() {
+    pub fn remove(&mut self, value: &T) -> bool {
+        if value.to_index() < self.indicator.len() {
             unsafe { self.remove_unchecked(value) }
         } else {
             false
@@ -439,16 +746,16 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     ///
     /// assert!(set.contains(&5));
     /// assert!(!set.contains(&10));
     /// ```
     #[inline(always)]
-    pub fn contains(&self, value: &usize) -> bool {
+    pub fn contains(&self, value: &T) -> bool {
         // Safe and almost as fast as unsafe version
-        self.indicator.get(*value).copied().unwrap_or(false)
+        self.indicator.contains(value.to_index())
     }
 
     /// Retrieves the specified value from the Set, if it exists.
@@ -464,14 +771,14 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     ///
     /// assert_eq!(set.get(&5), Some(5));
     /// assert_eq!(set.get(&10), None);
     /// ```
     #[inline(always)]
-    pub fn get(&self, value: &usize) -> Option<usize> {
+    pub fn get(&self, value: &T) -> Option<T> {
         if self.contains(value) {
             Some(*value)
         } else {
@@ -493,14 +800,14 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     ///
     /// assert_eq!(set.take(&5), Some(5));
     /// assert_eq!(set.contains(&5), false);
     /// ```
     #[inline(always)]
-    pub fn take(&mut self, value: &usize) -> Option<usize> {
+    pub fn take(&mut self, value: &T) -> Option<T> {
         if self.remove(value) {
             Some(*value)
         } else {
@@ -517,14 +824,14 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     ///
     /// assert_eq!(set.max(), Some(10));
     /// ```
     #[inline(always)]
-    pub fn max(&self) -> Option<usize> {
+    pub fn max(&self) -> Option<T> {
         self.current_max
     }
 
@@ -537,14 +844,14 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     ///
     /// assert_eq!(set.min(), Some(5));
     /// ```
     #[inline(always)]
-    pub fn min(&self) -> Option<usize> {
+    pub fn min(&self) -> Option<T> {
         self.current_min
     }
 
@@ -555,14 +862,14 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     ///
     /// assert_eq!(set.peek_largest(), Some(10));
     /// ```
     #[inline(always)]
-    pub fn peek_largest(&self) -> Option<usize> {
+    pub fn peek_largest(&self) -> Option<T> {
         self.current_max
     }
 
@@ -573,26 +880,174 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     ///
     /// assert_eq!(set.peek_smallest(), Some(5));
     /// ```
     #[inline(always)]
-    pub fn peek_smallest(&self) -> Option<usize> {
+    pub fn peek_smallest(&self) -> Option<T> {
+        self.current_min
+    }
+
+    /// Returns the smallest value in the Set, if it is not empty.
+    ///
+    /// This mirrors `BTreeSet::first` and is equivalent to [`Set::min`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set = Set::from(vec![7, 3, 9]);
+    /// assert_eq!(set.first(), Some(3));
+    /// ```
+    #[inline(always)]
+    pub fn first(&self) -> Option<T> {
         self.current_min
     }
 
-    /// Calculate page index and in-page index for a value.
+    /// Returns the largest value in the Set, if it is not empty.
+    ///
+    /// This mirrors `BTreeSet::last` and is equivalent to [`Set::max`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set = Set::from(vec![7, 3, 9]);
+    /// assert_eq!(set.last(), Some(9));
+    /// ```
+    #[inline(always)]
+    pub fn last(&self) -> Option<T> {
+        self.current_max
+    }
+
+    /// Returns the smallest value in the Set that is strictly greater than `value`.
+    ///
+    /// `value` itself does not need to be present. This operation runs in
+    /// O(log C) time, where C is the number of allocated 2¹⁶-slot chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to search after.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set = Set::from(vec![5, 10, 15]);
+    /// assert_eq!(set.next_after(5), Some(10));
+    /// assert_eq!(set.next_after(11), Some(15));
+    /// assert_eq!(set.next_after(15), None);
+    /// ```
+    #[inline(always)]
+    pub fn next_after(&self, value: T) -> Option<T> {
+        self.indicator
+            .next_from(value.to_index().checked_add(1)?)
+            .map(T::from_index)
+    }
+
+    /// Returns the largest value in the Set that is strictly less than `value`.
+    ///
+    /// `value` itself does not need to be present. This operation runs in
+    /// O(log C) time, where C is the number of allocated 2¹⁶-slot chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to search before.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
     ///
-    /// The page index determines which page the value belongs to,
-    /// while the in-page index determines the value's position within that page.
+    /// let set: Set = Set::from(vec![5, 10, 15]);
+    /// assert_eq!(set.prev_before(15), Some(10));
+    /// assert_eq!(set.prev_before(9), Some(5));
+    /// assert_eq!(set.prev_before(5), None);
+    /// ```
     #[inline(always)]
-    pub(super) fn page_indices(value: usize) -> (usize, usize) {
-        let page_index = value >> Self::PAGE_SHIFT; // Shift right by PAGE_SHIFT to divide by PAGE_SIZE
-        let in_page_index = value & Self::PAGE_MASK; // Bitwise AND with PAGE_MASK to modulo by PAGE_SIZE
-        (page_index, in_page_index)
+    pub fn prev_before(&self, value: T) -> Option<T> {
+        self.indicator
+            .prev_from(value.to_index().checked_sub(1)?)
+            .map(T::from_index)
+    }
+
+    /// Returns an iterator over the values in the Set that fall within the specified range,
+    /// in ascending order.
+    ///
+    /// The iterator scans the indicator 64 slots at a time, skipping empty words
+    /// and unallocated chunks, so its cost tracks the k values yielded rather than |range|.
+    /// It can also be reversed to walk the range in descending order.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - The range of values to iterate over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set = Set::from(vec![20, 1, 15, 5, 10]);
+    /// assert_eq!(set.range(5..=15).collect::<Vec<_>>(), vec![5, 10, 15]);
+    /// assert_eq!(set.range(..10).rev().collect::<Vec<_>>(), vec![5, 1]);
+    /// ```
+    #[inline(always)]
+    pub fn range<R>(&self, range: R) -> SetRange<'_, T>
+    where
+        R: std::ops::RangeBounds<T>,
+    {
+        let (start, end) = self.slot_bounds(range);
+        SetRange::new(self.indicator.ones_in(start, end))
+    }
+
+    /// Resolves range bounds to a half-open `start..end` span of indicator slots.
+    #[inline(always)]
+    pub(super) fn slot_bounds<R>(&self, range: R) -> (usize, usize)
+    where
+        R: std::ops::RangeBounds<T>,
+    {
+        let start = match range.start_bound() {
+            std::ops::Bound::Included(&s) => s.to_index(),
+            std::ops::Bound::Excluded(&s) => s.to_index().saturating_add(1),
+            std::ops::Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            std::ops::Bound::Included(&e) => e.to_index().saturating_add(1),
+            std::ops::Bound::Excluded(&e) => e.to_index(),
+            std::ops::Bound::Unbounded => self.indicator.len(),
+        };
+        (start, end)
+    }
+
+    /// Builds a Set from a fully populated indicator.
+    ///
+    /// The elements, positions and extremes are derived from the set bits in a
+    /// single ascending pass, so the resulting `elements` are sorted.
+    pub(super) fn from_indicator(indicator: Indicator) -> Self {
+        let () = Self::PAGE_CHECK;
+        let mut set = Set {
+            max: T::from_index(indicator.len().saturating_sub(1)),
+            indicator,
+            elements: Vec::new(),
+            positions: Positions::new(),
+            ceiling: default_ceiling(),
+            current_max: None,
+            current_min: None,
+        };
+        for value in set.indicator.ones() {
+            set.positions
+                .insert(value, T::from_index(set.elements.len()));
+            set.elements.push(T::from_index(value));
+        }
+        set.current_min = set.elements.first().copied();
+        set.current_max = set.elements.last().copied();
+        set
     }
 
     /// Returns the number of elements in the Set that fall within the specified range.
@@ -601,7 +1056,8 @@ impl Set {
     /// and exclusive on the end bound. The method counts the elements within the range
     /// that exist in the Set.
     ///
-    /// This operation runs in O(|range|) time where |range| is the size of the range.
+    /// This operation counts whole 2¹⁶-slot chunks at once and scans words, 64 slots per
+    /// step, only in the two chunks at the ends of the range.
     ///
     /// # Arguments
     ///
@@ -612,7 +1068,7 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     /// set.insert(15);
@@ -622,31 +1078,19 @@ impl Set {
     #[inline(always)]
     pub fn range_cardinality<R>(&self, range: R) -> usize
     where
-        R: std::ops::RangeBounds<usize>,
+        R: std::ops::RangeBounds<T>,
     {
-        let start = match range.start_bound() {
-            std::ops::Bound::Included(&s) => s,
-            std::ops::Bound::Excluded(&s) => s + 1,
-            std::ops::Bound::Unbounded => 0,
-        };
-        let end = match range.end_bound() {
-            std::ops::Bound::Included(&e) => e + 1,
-            std::ops::Bound::Excluded(&e) => e,
-            std::ops::Bound::Unbounded => self.indicator.len(),
-        };
+        let (start, end) = self.slot_bounds(range);
 
-        // Optimized counting using indicator directly
-        if end <= self.indicator.len() && start < end {
-            self.indicator[start..end].iter().filter(|&&b| b).count()
-        } else {
-            0
-        }
+        // Word-level popcount over the packed indicator
+        self.indicator.count_range(start, end)
     }
 
     /// Returns the number of elements in the Set that are strictly less than the specified value.
     ///
     /// This method returns the count of elements in the Set that are less than the given value.
-    /// This operation runs in O(|S|) time where |S| is the size of the set.
+    /// This operation runs in O(1) time when the rank index is enabled
+    /// (see [`Set::enable_rank_index`]), and in O(value / 2¹⁶ + 1024) time otherwise.
     ///
     /// # Arguments
     ///
@@ -657,7 +1101,7 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     /// set.insert(15);
@@ -665,26 +1109,195 @@ impl Set {
     /// assert_eq!(set.rank(12), 2);
     /// ```
     #[inline(always)]
-    pub fn rank(&self, value: usize) -> usize {
+    pub fn rank(&self, value: T) -> usize {
         // Fast path for small values
+        let value = value.to_index();
         if value == 0 {
             return 0;
         }
 
-        // Use range_cardinality for efficiency
-        self.range_cardinality(0..value)
+        self.indicator.rank(value)
+    }
+
+    /// Returns the `k`-th smallest value in the Set (0-based), if the Set has more than `k` elements.
+    ///
+    /// This is the inverse of [`Set::rank`]: for any value `v` in the Set,
+    /// `select(rank(v)) == Some(v)`. This operation runs in O(log(U / 512)) time when
+    /// the rank index is enabled (see [`Set::enable_rank_index`]), and in O(U / 2¹⁶ + 1024)
+    /// time otherwise, where U is the size of the universe.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The 0-based position of the value in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set = Set::from(vec![40, 10, 30, 20]);
+    ///
+    /// assert_eq!(set.select(0), Some(10));
+    /// assert_eq!(set.select(2), Some(30));
+    /// assert_eq!(set.select(4), None);
+    /// ```
+    #[inline(always)]
+    pub fn select(&self, k: usize) -> Option<T> {
+        // Fast paths for the extremes and out-of-range positions
+        match k {
+            k if k >= self.len() => None,
+            0 => self.current_min,
+            k if k + 1 == self.len() => self.current_max,
+            k => self.indicator.select(k).map(T::from_index),
+        }
+    }
+
+    /// Enables the rank/select index.
+    ///
+    /// The index keeps a running count of elements before every 2¹⁶-value chunk and
+    /// every 512-value block within it, making [`Set::rank`] O(1) and [`Set::select`]
+    /// logarithmic. It costs one `usize` per chunk and one `u16` per block, up to the
+    /// largest element, is dropped whenever the Set is modified, and is rebuilt
+    /// on the next `rank` or `select`. It pays off when queries outnumber updates.
+    ///
+    /// The results of set algebra and of the operators keep the index enabled
+    /// when their left operand has it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let mut set: Set = Set::from_iter((0..10_000).step_by(7));
+    /// set.enable_rank_index();
+    ///
+    /// assert_eq!(set.rank(700), 100);
+    /// assert_eq!(set.select(100), Some(700));
+    /// ```
+    #[inline(always)]
+    pub fn enable_rank_index(&mut self) {
+        if !self.indicator.has_rank_index() {
+            self.indicator.set_rank_index(true);
+        }
+    }
+
+    /// Disables the rank/select index and frees its memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let mut set: Set = Set::from(vec![1, 2, 3]);
+    /// set.enable_rank_index();
+    /// assert!(set.has_rank_index());
+    ///
+    /// set.disable_rank_index();
+    /// assert!(!set.has_rank_index());
+    /// assert_eq!(set.rank(3), 2);
+    /// ```
+    #[inline(always)]
+    pub fn disable_rank_index(&mut self) {
+        self.indicator.set_rank_index(false);
+    }
+
+    /// Returns `true` if the rank/select index is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let mut set: Set = Set::with_max(100);
+    /// assert!(!set.has_rank_index());
+    ///
+    /// set.enable_rank_index();
+    /// assert!(set.has_rank_index());
+    /// ```
+    #[inline(always)]
+    pub fn has_rank_index(&self) -> bool {
+        self.indicator.has_rank_index()
+    }
+
+    /// Enables hybrid containers for the indicator.
+    ///
+    /// In hybrid mode each 2¹⁶-value block of the universe is stored as a sorted
+    /// array, a bitmap or a list of runs, whichever is smallest for its density,
+    /// and is converted as values come and go. Sparse blocks then cost a few bytes
+    /// per value and long runs a few bytes per run, while dense blocks stay
+    /// bitmaps. Membership checks in array and run blocks take a binary search
+    /// instead of a single bit probe. `random` is unaffected, as it draws from the
+    /// element list. Sets built by set algebra inherit the mode of the left operand.
+    ///
+    /// [`Set::shrink_to_fit`] re-picks the container of every block, which also
+    /// catches a bitmap that has since become a few long runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let mut set: Set = Set::with_max(10_000_000);
+    /// set.enable_hybrid_containers();
+    /// set.extend(0..50_000); // one long run
+    /// set.extend((5_000_000..6_000_000).step_by(1_000)); // sparse values
+    /// assert!(set.contains(&5_001_000));
+    /// assert_eq!(set.len(), 51_000);
+    /// ```
+    #[inline(always)]
+    pub fn enable_hybrid_containers(&mut self) {
+        self.indicator.set_hybrid(true);
+    }
+
+    /// Disables hybrid containers, turning every block back into a bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let mut set: Set = Set::from(vec![1, 2, 3]);
+    /// set.enable_hybrid_containers();
+    /// set.disable_hybrid_containers();
+    /// assert!(!set.has_hybrid_containers());
+    /// assert!(set.contains(&2));
+    /// ```
+    #[inline(always)]
+    pub fn disable_hybrid_containers(&mut self) {
+        self.indicator.set_hybrid(false);
+    }
+
+    /// Returns `true` if hybrid containers are enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let mut set: Set = Set::with_max(100);
+    /// assert!(!set.has_hybrid_containers());
+    ///
+    /// set.enable_hybrid_containers();
+    /// assert!(set.has_hybrid_containers());
+    /// ```
+    #[inline(always)]
+    pub fn has_hybrid_containers(&self) -> bool {
+        self.indicator.is_hybrid()
     }
 
     /// Removes and returns the largest value in the Set, if it is not empty.
     ///
     /// Returns `Some(value)` if the Set is not empty, and `None` if it is empty.
     ///
+    /// This operation runs in O(log C) time, where C is the number of allocated
+    /// 2¹⁶-slot chunks, so draining a Set in priority order never rescans it.
+    ///
     /// # Examples
     ///
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     ///
@@ -692,10 +1305,9 @@ impl Set {
     /// assert_eq!(set.contains(&10), false);
     /// ```
     #[inline(always)]
-    pub fn remove_largest(&mut self) -> Option<usize> {
-        self.current_max.and_then(|max_val| {
-            unsafe { self.remove_unchecked(&max_val) };
-            Some(max_val)
+    pub fn remove_largest(&mut self) -> Option<T> {
+        self.current_max.inspect(|max_val| {
+            unsafe { self.remove_unchecked(max_val) };
         })
     }
 
@@ -703,12 +1315,15 @@ impl Set {
     ///
     /// Returns `Some(value)` if the Set is not empty, and `None` if it is empty.
     ///
+    /// This operation runs in O(log C) time, where C is the number of allocated
+    /// 2¹⁶-slot chunks, so draining a Set in priority order never rescans it.
+    ///
     /// # Examples
     ///
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     ///
@@ -716,10 +1331,9 @@ impl Set {
     /// assert_eq!(set.contains(&5), false);
     /// ```
     #[inline(always)]
-    pub fn remove_smallest(&mut self) -> Option<usize> {
-        self.current_min.and_then(|min_val| {
-            unsafe { self.remove_unchecked(&min_val) };
-            Some(min_val)
+    pub fn remove_smallest(&mut self) -> Option<T> {
+        self.current_min.inspect(|min_val| {
+            unsafe { self.remove_unchecked(min_val) };
         })
     }
 
@@ -729,43 +1343,81 @@ impl Set {
     ///
     /// # Arguments
     ///
-    /// * `rng` - A mutable reference to a random number generator implementing the `Rng` trait.
+    /// * `rng` - A mutable reference to any random number generator implementing
+    ///   [`rand_core::RngCore`]. With the `nanorand` feature, wrap a `nanorand`
+    ///   generator in [`NanoRng`](crate::NanoRng).
     ///
     /// # Safety
     ///
-    /// This method relies on unsafe code due to pointer arithmetic to avoid bounds checks for performance reasons.
+    /// This method relies on unsafe code to skip the bounds check, since the drawn index is
+    /// always below the number of elements.
     ///
     /// # Examples
     ///
     /// ```
     /// use fastset::Set;
-    /// use nanorand::WyRand;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(5);
     /// set.insert(10);
     /// set.insert(15);
     ///
-    /// let mut rng = WyRand::new();
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
     /// let random_element = set.random(&mut rng);
     /// assert!(random_element.is_some());
     /// ```
     #[inline(always)]
-    pub fn random(&self, rng: &mut WyRand) -> Option<usize> {
+    pub fn random<R: RngCore + ?Sized>(&self, rng: &mut R) -> Option<T> {
         match self.elements.is_empty() {
-            // SAFETY: index is within bounds by design (generated within elements.len() range)
+            // SAFETY: uniform_index draws from 0..elements.len()
             false => unsafe {
                 Some(
                     *self
                         .elements
-                        .as_ptr()
-                        .add(rng.generate_range(0..self.elements.len())),
+                        .get_unchecked(uniform_index(rng, self.elements.len())),
                 )
             },
             true => None,
         }
     }
 
+    /// Removes and returns a uniformly random element, or `None` if the Set is empty.
+    ///
+    /// The element is picked by its position in the element list, so it is known
+    /// to be present: its indicator bit and page entry are cleared directly and the
+    /// last element is swapped into its slot, all in O(1) time unless it was the
+    /// Set's maximum or minimum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let mut set: Set = Set::from(vec![5, 10, 15]);
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    ///
+    /// let popped = set.pop_random(&mut rng).unwrap();
+    /// assert!(!set.contains(&popped));
+    /// assert_eq!(set.len(), 2);
+    /// ```
+    #[inline(always)]
+    pub fn pop_random<R: RngCore + ?Sized>(&mut self, rng: &mut R) -> Option<T> {
+        if self.elements.is_empty() {
+            return None;
+        }
+        let elem_index = uniform_index(rng, self.elements.len());
+        let value = self.elements[elem_index];
+        let index = value.to_index();
+        self.indicator.remove(index);
+        self.positions.remove(index);
+        self.detach(value, elem_index);
+        Some(value)
+    }
+
     /// Inserts a value into the Set without performing bounds checks.
     ///
     /// This method assumes that:
@@ -785,7 +1437,7 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     ///
     /// // Inserting values without performing bounds checks.
     /// let result = set.insert_unchecked(5);
@@ -794,29 +1446,17 @@ impl Set {
     /// assert!(result);
     /// ```
     #[inline(always)]
-    pub fn insert_unchecked(&mut self, value: usize) -> bool {
-        if self.indicator[value] {
+    pub fn insert_unchecked(&mut self, value: T) -> bool {
+        if !self.indicator.insert(value.to_index()) {
             // The value is already present.
             return false;
         }
 
-        self.indicator[value] = true;
-
-        // Calculate the page index and in-page index.
-        let (page_idx, in_page_idx) = Self::page_indices(value);
-
-        // Ensure the page exists.
-        if page_idx >= self.pages.len() {
-            self.pages.resize_with(page_idx + 1, Default::default);
-        }
-        if self.pages[page_idx].is_none() {
-            self.pages[page_idx] = Some(vec![0; Self::PAGE_SIZE]);
-        }
-
         // Insert the value into the elements vector and record its index in the page.
         let elem_index = self.elements.len();
         self.elements.push(value);
-        self.pages[page_idx].as_mut().unwrap()[in_page_idx] = elem_index;
+        self.positions
+            .insert(value.to_index(), T::from_index(elem_index));
 
         // Update current_max and current_min more efficiently
         match (self.current_max, self.current_min) {
@@ -844,7 +1484,7 @@ impl Set {
     /// This method is unsafe because it:
     /// 1. Assumes `value` is within the bounds of the `indicator` vector. Out-of-bounds access will cause undefined behavior.
     /// 2. Assumes no concurrent mutable references to `indicator`, `index`, or `elements`, to avoid mutable aliasing.
-    /// 3. Assumes the page for this value exists in the `pages` vector.
+    /// 3. Assumes the position of this value is recorded in `positions`.
     ///
     /// The caller must ensure these preconditions are met.
     ///
@@ -861,7 +1501,7 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let mut set = Set::with_max(100);
+    /// let mut set: Set = Set::with_max(100);
     ///
     /// // Inserting and removing values without performing bounds checks.
     /// unsafe {
@@ -871,20 +1511,24 @@ impl Set {
     /// }
     /// ```
     #[inline(always)]
-    pub unsafe fn remove_unchecked(&mut self, value: &usize) -> bool {
-        if !self.indicator[*value] {
+    pub unsafe fn remove_unchecked(&mut self, value: &T) -> bool {
+        let index = value.to_index();
+        if !self.indicator.remove(index) {
             // The value is not present.
             return false;
         }
 
-        self.indicator[*value] = false;
+        // Take the element index out of its page, freeing the page's directory if now empty
+        let elem_index = self.positions.remove(index).to_index();
+        self.detach(*value, elem_index);
 
-        // Calculate page index and in-page index.
-        let (page_idx, in_page_idx) = Self::page_indices(*value);
-
-        // Get the element index from the page
-        let elem_index = self.pages[page_idx].as_ref().unwrap()[in_page_idx];
+        true
+    }
 
+    /// Drops `value`, stored at `elem_index`, from the element list once its
+    /// indicator bit and page entry are cleared, and fixes up the extremes.
+    #[inline(always)]
+    fn detach(&mut self, value: T, elem_index: usize) {
         // Remove the element by swapping with the last
         let last_index = self.elements.len() - 1;
 
@@ -894,35 +1538,33 @@ impl Set {
 
             // Update the page entry for the swapped element
             let swapped_value = self.elements[elem_index];
-            let (swapped_page_idx, swapped_in_page_idx) = Self::page_indices(swapped_value);
-            self.pages[swapped_page_idx].as_mut().unwrap()[swapped_in_page_idx] = elem_index;
+            self.positions
+                .update(swapped_value.to_index(), T::from_index(elem_index));
         }
 
         // Remove the last element
         self.elements.pop();
 
-        // Zero the slot in the page to avoid stale entries
-        self.pages[page_idx].as_mut().unwrap()[in_page_idx] = 0;
-
         // Update current_max and current_min if necessary
         match (self.current_max, self.current_min) {
-            (Some(max), Some(min)) if *value == max || *value == min => {
+            (Some(max), Some(min)) if value == max || value == min => {
                 if self.is_empty() {
                     self.current_max = None;
                     self.current_min = None;
                 } else {
-                    // Only recalculate if we removed the max or min
-                    if *value == max {
-                        self.current_max = self.elements.iter().copied().max();
+                    // Only recalculate if we removed the max or min: a binary search
+                    // over the allocated chunks, then the chunk's two-level summary
+                    // of non-zero words, find the new extreme in O(log C)
+                    let index = value.to_index();
+                    if value == max {
+                        self.current_max = self.indicator.prev_from(index).map(T::from_index);
                     }
-                    if *value == min {
-                        self.current_min = self.elements.iter().copied().min();
+                    if value == min {
+                        self.current_min = self.indicator.next_from(index).map(T::from_index);
                     }
                 }
             }
             _ => {} // No update needed
         }
-
-        true
     }
 }
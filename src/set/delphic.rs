@@ -0,0 +1,289 @@
+use super::core::Set;
+use super::element::Element;
+use super::key_set::KeySet;
+use super::random::{binomial_pow2, halve_count, uniform_index};
+use super::view::SetView;
+use rand_core::RngCore;
+use std::hash::Hash;
+
+/// A set supporting the three queries of a Delphic family: membership,
+/// cardinality and uniform sampling.
+///
+/// Estimators written against this trait, such as [`union_size_estimate`]
+/// and [`sample_from_union`], never enumerate or materialize the sets they
+/// are given. The trait is object safe, so sets of different kinds can be
+/// mixed behind `&dyn DelphicSet<T>`.
+///
+/// It is implemented by [`Set`], by [`SetView`] over a serialized Set, and by
+/// [`KeySet`](super::KeySet) for hashable keys that are not small integers.
+/// `std::collections::HashSet` does not qualify, as it cannot sample in
+/// sublinear time. Implementing the three methods is also all a test double
+/// needs to stand in for a real set.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::rand_core::RngCore;
+/// use fastset::DelphicSet;
+///
+/// /// The even numbers below `n`.
+/// struct Evens(usize);
+///
+/// impl DelphicSet for Evens {
+///     fn contains(&self, value: &usize) -> bool {
+///         value % 2 == 0 && *value < self.0
+///     }
+///
+///     fn len(&self) -> usize {
+///         self.0.div_ceil(2)
+///     }
+///
+///     fn sample(&self, rng: &mut dyn RngCore) -> Option<usize> {
+///         let len = self.len() as u64;
+///         (len > 0).then(|| 2 * (rng.next_u64() % len) as usize)
+///     }
+/// }
+///
+/// let evens = Evens(10);
+/// assert!(evens.contains(&4) && !evens.is_empty());
+/// ```
+///
+/// The element type `T` defaults to `usize`, so `DelphicSet` on its own means
+/// `DelphicSet<usize>`.
+pub trait DelphicSet<T = usize> {
+    /// Returns `true` if the set contains `value`.
+    fn contains(&self, value: &T) -> bool;
+
+    /// Returns the number of values in the set.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the set holds no values.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a value of the set drawn uniformly at random, or `None` if the
+    /// set is empty.
+    fn sample(&self, rng: &mut dyn RngCore) -> Option<T>;
+}
+
+/// `Set` answers all three queries in O(1): an indicator probe, a stored
+/// length and a uniform index into its element list.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{DelphicSet, Set};
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+///
+/// let set: Set = Set::from(vec![5, 10, 15]);
+/// let delphic: &dyn DelphicSet = &set;
+/// let mut rng = ChaCha8Rng::seed_from_u64(42);
+/// assert_eq!(delphic.len(), 3);
+/// assert!(delphic.contains(&delphic.sample(&mut rng).unwrap()));
+/// ```
+impl<T: Element, const PAGE: usize> DelphicSet<T> for Set<T, PAGE> {
+    #[inline(always)]
+    fn contains(&self, value: &T) -> bool {
+        Set::contains(self, value)
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        Set::len(self)
+    }
+
+    #[inline(always)]
+    fn sample(&self, rng: &mut dyn RngCore) -> Option<T> {
+        self.random(rng)
+    }
+}
+
+/// A SetView answers membership and sampling in place, and its length is
+/// read from the header.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{DelphicSet, Set, SetView};
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+///
+/// let set: Set<u32> = Set::from(vec![5, 10, 15]);
+/// let mut bytes = Vec::new();
+/// set.write_view_to(&mut bytes).unwrap();
+///
+/// let view: SetView<u32> = SetView::new(&bytes).unwrap();
+/// let delphic: &dyn DelphicSet<u32> = &view;
+/// let mut rng = ChaCha8Rng::seed_from_u64(42);
+/// assert!(set.contains(&delphic.sample(&mut rng).unwrap()));
+/// ```
+impl<T: Element> DelphicSet<T> for SetView<'_, T> {
+    #[inline(always)]
+    fn contains(&self, value: &T) -> bool {
+        SetView::contains(self, value)
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        SetView::len(self)
+    }
+
+    #[inline(always)]
+    fn sample(&self, rng: &mut dyn RngCore) -> Option<T> {
+        self.random(rng)
+    }
+}
+
+/// Estimates the size of the union of `sets` without materializing it, using
+/// only their membership, cardinality and sampling queries.
+///
+/// This is the streaming algorithm of Meel, Vinodchandran and Chakraborty
+/// (PODS 2022). The sets are visited once each, in order, while a buffer
+/// holds a random subset of the union seen so far in which every value is
+/// kept with the same probability p:
+///
+/// 1. buffered values contained in the next set are dropped, so that they
+///    are re-drawn from it with probability p;
+/// 2. each of its values is drawn with probability p, by drawing a binomial
+///    count and sampling that many distinct values;
+/// 3. while the buffer and the draws together reach the threshold, both are
+///    thinned by coin flips and p is halved.
+///
+/// The estimate is the buffer's size over p. It lies within a factor
+/// 1 ± `epsilon` of the true union size with probability at least
+/// 1 − `delta`, and the buffer, a [`KeySet`], never holds more than
+/// ⌈12 / ε² · log₂(8 · `sets.len()` / δ)⌉ values, whatever the sets' sizes.
+/// Any hashable values will do, so sets of strings or tuples can be
+/// estimated as well as integer sets.
+///
+/// # Panics
+///
+/// Panics if `epsilon` or `delta` is not strictly between 0 and 1.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{union_size_estimate, DelphicSet, Set};
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+///
+/// let a: Set = Set::from_iter(0..60_000);
+/// let b: Set = Set::from_iter(40_000..100_000);
+/// let c: Set = Set::from_iter((0..200_000).step_by(2));
+/// let sets: [&dyn DelphicSet; 3] = [&a, &b, &c];
+///
+/// // The union holds 100_000 + 50_000 = 150_000 values
+/// let mut rng = ChaCha8Rng::seed_from_u64(42);
+/// let estimate = union_size_estimate(&sets, 0.1, 0.05, &mut rng);
+/// assert!((135_000.0..165_000.0).contains(&estimate));
+/// ```
+pub fn union_size_estimate<T, R>(
+    sets: &[&dyn DelphicSet<T>],
+    epsilon: f64,
+    delta: f64,
+    rng: &mut R,
+) -> f64
+where
+    T: Hash + Eq + Clone,
+    R: RngCore,
+{
+    assert!(
+        epsilon > 0.0 && epsilon < 1.0,
+        "epsilon {} must lie strictly between 0 and 1",
+        epsilon
+    );
+    assert!(
+        delta > 0.0 && delta < 1.0,
+        "delta {} must lie strictly between 0 and 1",
+        delta
+    );
+    let bound = (8.0 * sets.len().max(1) as f64 / delta).log2();
+    let threshold = (12.0 / (epsilon * epsilon) * bound).ceil() as usize;
+
+    let mut buffer = KeySet::new();
+    let mut halvings = 0;
+    let mut covered = Vec::new();
+    for set in sets {
+        covered.clear();
+        covered.extend(buffer.iter().filter(|value| set.contains(value)).cloned());
+        for value in &covered {
+            buffer.remove(value);
+        }
+        let mut draws = binomial_pow2(rng, set.len(), halvings);
+        while buffer.len() + draws >= threshold {
+            for _ in 0..halve_count(rng, buffer.len()) {
+                buffer.pop_random(rng);
+            }
+            draws = halve_count(rng, draws);
+            halvings += 1;
+        }
+        // The buffer holds none of the set's values, so every new one counts
+        let target = buffer.len() + draws;
+        while buffer.len() < target {
+            match set.sample(rng) {
+                Some(value) => {
+                    buffer.insert(value);
+                }
+                None => break,
+            }
+        }
+    }
+    buffer.len() as f64 * 2f64.powi(halvings as i32)
+}
+
+/// Returns a value drawn uniformly from the union of `sets`, without
+/// materializing it, or `None` if every set is empty.
+///
+/// This is Karp–Luby rejection sampling. A set is picked with probability
+/// proportional to its length and a uniform value is drawn from it, so a
+/// value held by c of the sets comes up c times as often as it should. The
+/// draw is kept only if the picked set is the first of `sets` holding the
+/// value, which happens with probability 1 / c, making every value of the
+/// union equally likely.
+///
+/// Each attempt costs one draw, a binary search over the sets and up to one
+/// `contains` per set before the picked one. Attempts succeed with
+/// probability at least `1 / sets.len()`, and with probability close to 1
+/// when the sets barely overlap.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{sample_from_union, Set};
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+///
+/// let a: Set = Set::from_iter(0..100);
+/// let b: Set = Set::from_iter(50..150);
+/// let mut rng = ChaCha8Rng::seed_from_u64(42);
+///
+/// let value = sample_from_union(&[&a, &b], &mut rng).unwrap();
+/// assert!(a.contains(&value) || b.contains(&value));
+/// assert_eq!(sample_from_union::<usize, Set, _>(&[], &mut rng), None);
+/// ```
+pub fn sample_from_union<T, S, R>(sets: &[&S], rng: &mut R) -> Option<T>
+where
+    S: DelphicSet<T> + ?Sized,
+    R: RngCore,
+{
+    let mut ends = Vec::with_capacity(sets.len());
+    let mut total = 0;
+    for set in sets {
+        total += set.len();
+        ends.push(total);
+    }
+    if total == 0 {
+        return None;
+    }
+    loop {
+        let draw = uniform_index(rng, total);
+        // The first set whose cumulative length exceeds the draw
+        let picked = ends.partition_point(|&end| end <= draw);
+        let value = sets[picked].sample(rng)?;
+        if !sets[..picked].iter().any(|set| set.contains(&value)) {
+            return Some(value);
+        }
+    }
+}
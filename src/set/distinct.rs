@@ -0,0 +1,207 @@
+use super::core::Set;
+use super::element::Element;
+use super::random::halve_count;
+use rand_core::RngCore;
+
+/// Estimates the number of distinct values in a stream with the CVM
+/// algorithm of Chakraborty, Vinodchandran and Meel, keeping a bounded
+/// [`Set`] as its buffer.
+///
+/// Every value is first removed from the buffer, then kept with probability
+/// p, which starts at 1. Whenever the buffer reaches its threshold, each
+/// buffered value is evicted with probability ½ and p is halved. The
+/// estimate is `len / p`, which lies within a factor 1 ± ε of the true count
+/// with probability at least 1 − δ.
+///
+/// The buffer only ever needs `remove`, `insert`, `len` and
+/// [`pop_random`](Set::pop_random): evicting each value with probability ½
+/// is the same as evicting a binomially distributed number of uniformly
+/// random values, so a halving costs O(len) coin flips packed 64 to a word
+/// and O(1) per eviction. The paper gives up when a halving evicts nothing;
+/// this counter halves again instead, which only matters with probability
+/// below δ.
+///
+/// The buffer uses hybrid containers and the Set's sparse position pages,
+/// so a value scattered over a wide universe costs a short sorted array and
+/// one page of positions, and memory stays proportional to the threshold.
+/// `T::MAX`, which a 64-bit Set cannot hold, is tracked beside it by a flag.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::DistinctCounter;
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+///
+/// let rng = ChaCha8Rng::seed_from_u64(42);
+/// let mut counter: DistinctCounter<_, u64> = DistinctCounter::new(0.1, 0.01, 1_000_000, rng);
+/// for i in 0..1_000_000u64 {
+///     counter.feed(i * 7_919 % 50_000);
+/// }
+/// let estimate = counter.estimate();
+/// assert!((45_000.0..55_000.0).contains(&estimate));
+/// ```
+#[derive(Clone)]
+pub struct DistinctCounter<R, T = usize, const PAGE: usize = 16> {
+    buffer: Set<T, PAGE>,
+    /// Whether `T::MAX` is buffered, when it lies above the Set's ceiling.
+    top: bool,
+    threshold: usize,
+    halvings: u32,
+    rng: R,
+}
+
+impl<R: RngCore, T: Element, const PAGE: usize> DistinctCounter<R, T, PAGE> {
+    /// Creates a counter whose estimate is within a factor 1 ± `epsilon` of
+    /// the true count with probability at least 1 − `delta`, for streams of
+    /// up to `stream_len` values.
+    ///
+    /// The buffer threshold is ⌈12 / ε² · log₂(8 · `stream_len` / δ)⌉, the
+    /// bound from the paper. Longer streams still yield estimates, with a
+    /// slightly weaker guarantee.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epsilon` or `delta` is not strictly between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::DistinctCounter;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let rng = ChaCha8Rng::seed_from_u64(42);
+    /// let counter: DistinctCounter<_> = DistinctCounter::new(0.5, 0.1, 1_000, rng);
+    /// assert_eq!(counter.threshold(), 782);
+    /// ```
+    pub fn new(epsilon: f64, delta: f64, stream_len: u64, rng: R) -> Self {
+        assert!(
+            epsilon > 0.0 && epsilon < 1.0,
+            "epsilon {} must lie strictly between 0 and 1",
+            epsilon
+        );
+        assert!(
+            delta > 0.0 && delta < 1.0,
+            "delta {} must lie strictly between 0 and 1",
+            delta
+        );
+        let bound = (8.0 * stream_len.max(1) as f64 / delta).log2();
+        let threshold = (12.0 / (epsilon * epsilon) * bound).ceil();
+        Self::with_threshold(threshold as usize, rng)
+    }
+
+    /// Creates a counter whose buffer halves once it holds `threshold` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is zero.
+    pub fn with_threshold(threshold: usize, rng: R) -> Self {
+        assert!(threshold > 0, "threshold must be positive");
+        let mut buffer = Set::with_max(T::default());
+        buffer.enable_hybrid_containers();
+        Self {
+            buffer,
+            top: false,
+            threshold,
+            halvings: 0,
+            rng,
+        }
+    }
+
+    /// Returns the number of buffered values that triggers a halving.
+    #[inline(always)]
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Processes the next value of the stream.
+    ///
+    /// Every value of `T` is accepted, including `T::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::DistinctCounter;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let rng = ChaCha8Rng::seed_from_u64(42);
+    /// let mut counter: DistinctCounter<_, u64> = DistinctCounter::with_threshold(100, rng);
+    /// counter.feed(u64::MAX);
+    /// counter.feed(0);
+    /// counter.feed(u64::MAX);
+    /// assert_eq!(counter.estimate(), 2.0);
+    /// ```
+    pub fn feed(&mut self, value: T) {
+        let top = value > self.buffer.ceiling();
+        match top {
+            true => self.top = false,
+            false => {
+                self.buffer.remove(&value);
+            }
+        }
+        if self.keep() {
+            match top {
+                true => self.top = true,
+                false => {
+                    self.buffer.insert(value);
+                }
+            }
+            while self.len() >= self.threshold {
+                self.halve();
+            }
+        }
+    }
+
+    /// Returns the current estimate of the number of distinct values fed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::DistinctCounter;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let rng = ChaCha8Rng::seed_from_u64(42);
+    /// let mut counter: DistinctCounter<_> = DistinctCounter::with_threshold(100, rng);
+    /// for value in [3, 1, 3, 2, 1] {
+    ///     counter.feed(value);
+    /// }
+    /// // Below the threshold, the count is exact
+    /// assert_eq!(counter.estimate(), 3.0);
+    /// ```
+    pub fn estimate(&self) -> f64 {
+        self.len() as f64 * 2f64.powi(self.halvings as i32)
+    }
+
+    /// Returns the number of buffered values, counting `T::MAX` if flagged.
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.buffer.len() + usize::from(self.top)
+    }
+
+    /// Returns `true` with probability p = 2^-halvings.
+    fn keep(&mut self) -> bool {
+        let mut remaining = self.halvings;
+        while remaining >= 64 {
+            if self.rng.next_u64() != 0 {
+                return false;
+            }
+            remaining -= 64;
+        }
+        self.rng.next_u64() & ((1 << remaining) - 1) == 0
+    }
+
+    /// Evicts each buffered value with probability ½ and halves p.
+    fn halve(&mut self) {
+        if self.top && self.rng.next_u64() & 1 == 1 {
+            self.top = false;
+        }
+        let evictions = halve_count(&mut self.rng, self.buffer.len());
+        for _ in 0..evictions {
+            self.buffer.pop_random(&mut self.rng);
+        }
+        self.halvings += 1;
+    }
+}
@@ -0,0 +1,78 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Unsigned integer types that can be stored in a `Set`.
+///
+/// Implemented for `u16`, `u32`, `u64` and `usize`. Narrower types halve or
+/// quarter the memory taken by a set's element list and position pages,
+/// while the universe is still indexed with `usize` internally.
+///
+/// This trait is sealed and cannot be implemented outside of `fastset`.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::Set;
+///
+/// let mut ids: Set<u32> = Set::with_max(1_000);
+/// ids.insert(42);
+/// assert!(ids.contains(&42));
+/// assert_eq!(ids.max(), Some(42u32));
+/// ```
+pub trait Element:
+    Copy
+    + Ord
+    + Hash
+    + Default
+    + Debug
+    + Display
+    + Send
+    + Sync
+    + Serialize
+    + DeserializeOwned
+    + 'static
+    + sealed::Sealed
+{
+    /// The largest value of this type.
+    const MAX: Self;
+
+    /// Converts the value to a slot index, saturating at `usize::MAX`.
+    fn to_index(self) -> usize;
+
+    /// Converts a slot index back to a value.
+    ///
+    /// The index must not exceed `Self::MAX`; slot indices taken from a set
+    /// of this element type always satisfy this.
+    fn from_index(index: usize) -> Self;
+}
+
+macro_rules! impl_element {
+    ($($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+
+            impl Element for $t {
+                const MAX: Self = <$t>::MAX;
+
+                #[inline(always)]
+                fn to_index(self) -> usize {
+                    usize::try_from(self).unwrap_or(usize::MAX)
+                }
+
+                #[inline(always)]
+                fn from_index(index: usize) -> Self {
+                    debug_assert!(<$t>::try_from(index).is_ok());
+                    index as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_element!(u16, u32, u64, usize);
@@ -0,0 +1,91 @@
+use std::collections::TryReserveError;
+use std::fmt;
+
+/// Errors reported by the fallible `Set` API.
+///
+/// Values are widened to `u64`, which holds every [`Element`](super::Element)
+/// type, so one error type serves sets of any element width.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{Error, Set};
+///
+/// let mut set: Set<u32> = Set::with_max(100).with_ceiling(1_000);
+/// assert_eq!(
+///     set.try_insert(5_000),
+///     Err(Error::OutOfRange { value: 5_000, ceiling: 1_000 })
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A value lies above the largest value the set may hold.
+    OutOfRange {
+        /// The rejected value.
+        value: u64,
+        /// The largest value the set may hold.
+        ceiling: u64,
+    },
+    /// Growing the set's storage failed.
+    AllocationFailed(TryReserveError),
+    /// A snapshot is malformed, for the given reason.
+    InvalidSnapshot(&'static str),
+    /// A snapshot was written in a layout version this release cannot read.
+    UnsupportedVersion(u16),
+    /// A snapshot's checksum does not match its contents.
+    ChecksumMismatch,
+    /// A Roaring stream is truncated or malformed, for the given reason.
+    InvalidRoaring(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::OutOfRange { value, ceiling } => {
+                write!(f, "value {} is above the set's ceiling {}", value, ceiling)
+            }
+            Error::AllocationFailed(_) => write!(f, "failed to allocate set storage"),
+            Error::InvalidSnapshot(reason) => write!(f, "invalid snapshot: {}", reason),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "unsupported snapshot version {}", version)
+            }
+            Error::ChecksumMismatch => write!(f, "snapshot checksum mismatch"),
+            Error::InvalidRoaring(reason) => write!(f, "invalid roaring bitmap: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::AllocationFailed(cause) => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+impl From<TryReserveError> for Error {
+    fn from(cause: TryReserveError) -> Self {
+        Error::AllocationFailed(cause)
+    }
+}
+
+/// The successful outcomes of [`Set::try_insert`](super::Set::try_insert).
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{InsertOutcome, Set};
+///
+/// let mut set: Set = Set::with_max(100);
+/// assert_eq!(set.try_insert(5), Ok(InsertOutcome::Inserted));
+/// assert_eq!(set.try_insert(5), Ok(InsertOutcome::AlreadyPresent));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InsertOutcome {
+    /// The value was not in the set and has been added.
+    Inserted,
+    /// The value was already in the set, which is unchanged.
+    AlreadyPresent,
+}
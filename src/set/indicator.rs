@@ -0,0 +1,824 @@
+use super::container::{Chunk, ContainerRepr, CHUNK_WORDS};
+use super::sparse_vec::SparseVec;
+use serde::de::Error as _;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::{Borrow, Cow};
+use std::collections::TryReserveError;
+use std::sync::OnceLock;
+
+/// Number of slots packed into each indicator word.
+pub(super) const WORD_BITS: usize = u64::BITS as usize;
+
+/// Number of slots covered by each lazily allocated chunk.
+pub(super) const CHUNK_BITS: usize = 1 << 16;
+
+/// Number of words covered by each entry of the rank directory.
+pub(super) const RANK_BLOCK_WORDS: usize = 8;
+
+/// Number of slots covered by each entry of the rank directory.
+pub(super) const BLOCK_BITS: usize = RANK_BLOCK_WORDS * WORD_BITS;
+
+/// Number of rank directory entries per chunk.
+pub(super) const CHUNK_BLOCKS: usize = CHUNK_WORDS / RANK_BLOCK_WORDS;
+
+/// Bit-packed membership indicator backing a `Set`.
+///
+/// The slots are split into chunks of 2¹⁶, and a chunk is only allocated once
+/// a slot in it is set and freed again when its last slot is unset. Within a
+/// chunk, slot `i` is bit `i % 64` of word `i / 64`, whichever container holds
+/// it. Slots at or beyond `len` are always unset, which lets word-level
+/// operations skip masking the tail.
+///
+/// Outside hybrid mode every chunk is a bitmap; in hybrid mode each chunk is
+/// a sorted array, a bitmap or a run list, chosen by its density. The chunks
+/// are kept in a [`SparseVec`] keyed by chunk index, so memory follows the
+/// allocated chunks rather than `len`, and navigation steps straight from one
+/// allocated chunk to the next.
+///
+/// An optional rank directory stores the number of set slots before every
+/// allocated chunk and every 512-slot block within it. It is dropped on
+/// mutation and rebuilt on the next query.
+#[derive(Clone)]
+pub struct Indicator {
+    chunks: SparseVec<Chunk>,
+    len: usize,
+    hybrid: bool,
+    ranks: RankDirectory,
+}
+
+/// Lazily built prefix counts over chunks and blocks of `RANK_BLOCK_WORDS` words.
+///
+/// `chunks[i]` holds the number of set slots before the `i`-th allocated chunk,
+/// with a final entry holding the total. `blocks[i * CHUNK_BLOCKS + b]` holds
+/// the number of set slots before block `b` within that chunk, which always
+/// fits a `u16`.
+#[derive(Clone, Default)]
+struct RankDirectory {
+    enabled: bool,
+    index: OnceLock<RankIndex>,
+}
+
+#[derive(Clone)]
+struct RankIndex {
+    chunks: Vec<usize>,
+    blocks: Vec<u16>,
+}
+
+/// Sorted allocated chunks, as held by an [`Indicator`] or read in place from
+/// a [`SetView`](super::SetView) buffer.
+///
+/// Implementors only expose the directory and per-chunk bit queries. The
+/// chunk lookup and the membership test are provided, so both backends answer
+/// them with the same code.
+pub(super) trait ChunkDirectory {
+    /// Returns the number of allocated chunks.
+    fn chunk_count(&self) -> usize;
+
+    /// Returns the index of the chunk at position `pos`.
+    fn chunk_key(&self, pos: usize) -> usize;
+
+    /// Returns `true` if slot `bit` of the chunk at position `pos` is set.
+    fn chunk_contains(&self, pos: usize, bit: usize) -> bool;
+
+    /// Counts the set slots in `start..end` of the chunk at position `pos`.
+    fn chunk_count_range(&self, pos: usize, start: usize, end: usize) -> usize;
+
+    /// Finds the position of chunk `c`, or where it would be.
+    ///
+    /// O(1) when the allocated chunks are contiguous, a binary search otherwise.
+    #[inline(always)]
+    fn position(&self, c: usize) -> Result<usize, usize> {
+        let count = self.chunk_count();
+        if count == 0 {
+            return Err(0);
+        }
+        let guess = c.wrapping_sub(self.chunk_key(0));
+        if guess < count && self.chunk_key(guess) == c {
+            return Ok(guess);
+        }
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let mid = (low + high) / 2;
+            match self.chunk_key(mid).cmp(&c) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    /// Returns `true` if slot `index` is set.
+    #[inline(always)]
+    fn contains_slot(&self, index: usize) -> bool {
+        match self.position(index / CHUNK_BITS) {
+            Ok(pos) => self.chunk_contains(pos, index % CHUNK_BITS),
+            Err(_) => false,
+        }
+    }
+}
+
+/// A [`ChunkDirectory`] with the prefix counts of a rank directory.
+pub(super) trait RankedDirectory: ChunkDirectory {
+    /// Returns the number of set slots before the chunk at position `pos`, or
+    /// the total when `pos` is the number of chunks.
+    fn before_chunk(&self, pos: usize) -> usize;
+
+    /// Returns the number of set slots before block `block` within the chunk
+    /// at position `pos`.
+    fn before_block(&self, pos: usize, block: usize) -> usize;
+
+    /// Counts the set slots strictly below `index`: one chunk lookup, the
+    /// chunk and block prefixes, then a count over less than one block.
+    #[inline(always)]
+    fn rank_slot(&self, index: usize) -> usize {
+        let pos = match self.position(index / CHUNK_BITS) {
+            Ok(pos) => pos,
+            Err(pos) => return self.before_chunk(pos),
+        };
+        let bit = index % CHUNK_BITS;
+        let block = bit / BLOCK_BITS;
+        let within = match bit % BLOCK_BITS {
+            0 => 0,
+            _ => self.chunk_count_range(pos, block * BLOCK_BITS, bit),
+        };
+        self.before_chunk(pos) + self.before_block(pos, block) + within
+    }
+}
+
+impl ChunkDirectory for SparseVec<Chunk> {
+    #[inline(always)]
+    fn chunk_count(&self) -> usize {
+        self.len()
+    }
+
+    #[inline(always)]
+    fn chunk_key(&self, pos: usize) -> usize {
+        self.key(pos)
+    }
+
+    #[inline(always)]
+    fn chunk_contains(&self, pos: usize, bit: usize) -> bool {
+        self.value(pos).contains(bit)
+    }
+
+    #[inline(always)]
+    fn chunk_count_range(&self, pos: usize, start: usize, end: usize) -> usize {
+        self.value(pos).count_range(start, end)
+    }
+}
+
+/// An indicator's chunks paired with its built rank index.
+struct Ranked<'a> {
+    chunks: &'a SparseVec<Chunk>,
+    index: &'a RankIndex,
+}
+
+impl ChunkDirectory for Ranked<'_> {
+    #[inline(always)]
+    fn chunk_count(&self) -> usize {
+        self.chunks.chunk_count()
+    }
+
+    #[inline(always)]
+    fn chunk_key(&self, pos: usize) -> usize {
+        self.chunks.chunk_key(pos)
+    }
+
+    #[inline(always)]
+    fn chunk_contains(&self, pos: usize, bit: usize) -> bool {
+        self.chunks.chunk_contains(pos, bit)
+    }
+
+    #[inline(always)]
+    fn chunk_count_range(&self, pos: usize, start: usize, end: usize) -> usize {
+        self.chunks.chunk_count_range(pos, start, end)
+    }
+}
+
+impl RankedDirectory for Ranked<'_> {
+    #[inline(always)]
+    fn before_chunk(&self, pos: usize) -> usize {
+        self.index.chunks[pos]
+    }
+
+    #[inline(always)]
+    fn before_block(&self, pos: usize, block: usize) -> usize {
+        self.index.blocks[pos * CHUNK_BLOCKS + block] as usize
+    }
+}
+
+impl Indicator {
+    /// Creates an indicator with `len` slots, all unset.
+    ///
+    /// No chunk is allocated until a slot is set, so this is O(1) in `len`.
+    #[inline(always)]
+    pub(super) fn with_len(len: usize) -> Self {
+        Self {
+            chunks: SparseVec::new(),
+            len,
+            hybrid: false,
+            ranks: RankDirectory::default(),
+        }
+    }
+
+    /// Returns the number of slots.
+    #[inline(always)]
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if slot `index` is set. Out-of-range slots are unset.
+    #[inline(always)]
+    pub(super) fn contains(&self, index: usize) -> bool {
+        self.chunks.contains_slot(index)
+    }
+
+    /// Sets slot `index`, returning `true` if it was previously unset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range, just like indexing a slice.
+    #[inline(always)]
+    pub(super) fn insert(&mut self, index: usize) -> bool {
+        self.check(index);
+        let hybrid = self.hybrid;
+        let chunk = self
+            .chunks
+            .get_or_insert_with(index / CHUNK_BITS, || Chunk::new(hybrid));
+        if !chunk.insert(index % CHUNK_BITS, hybrid) {
+            return false;
+        }
+        self.invalidate_ranks();
+        true
+    }
+
+    /// Allocates the chunk holding slot `index`, if it is missing, so that
+    /// setting the slot next allocates nothing outside hybrid mode.
+    ///
+    /// The new chunk is empty until the slot is set, which must follow at once:
+    /// navigation assumes every allocated chunk has a set slot.
+    pub(super) fn try_reserve(&mut self, index: usize) -> Result<(), TryReserveError> {
+        let c = index / CHUNK_BITS;
+        if self.chunks.get(c).is_none() {
+            self.chunks.try_reserve(1)?;
+            let chunk = Chunk::try_new(self.hybrid)?;
+            self.chunks.get_or_insert_with(c, || chunk);
+        }
+        Ok(())
+    }
+
+    /// Unsets slot `index`, returning `true` if it was previously set.
+    ///
+    /// A chunk whose last slot is unset is freed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range, just like indexing a slice.
+    #[inline(always)]
+    pub(super) fn remove(&mut self, index: usize) -> bool {
+        self.check(index);
+        let c = index / CHUNK_BITS;
+        let Some(chunk) = self.chunks.get_mut(c) else {
+            return false;
+        };
+        if !chunk.remove(index % CHUNK_BITS, self.hybrid) {
+            return false;
+        }
+        if chunk.ones() == 0 {
+            self.chunks.remove(c);
+        }
+        self.invalidate_ranks();
+        true
+    }
+
+    /// Grows or truncates the indicator to `len` slots.
+    ///
+    /// New slots are unset and cost nothing; slots cut off by truncation are
+    /// discarded, freeing any chunk left empty.
+    #[inline(always)]
+    pub(super) fn resize(&mut self, len: usize) {
+        if len < self.len {
+            self.chunks.truncate(len.div_ceil(CHUNK_BITS));
+            let bit = len % CHUNK_BITS;
+            if let Some((c, chunk)) = self.chunks.last_mut() {
+                if c == len / CHUNK_BITS && bit != 0 {
+                    chunk.clear_from(bit, self.hybrid);
+                    if chunk.ones() == 0 {
+                        self.chunks.pop();
+                    }
+                }
+            }
+        }
+        self.len = len;
+        self.invalidate_ranks();
+    }
+
+    /// Shrinks the backing storage to fit the allocated chunks.
+    ///
+    /// In hybrid mode this also re-picks the container of every chunk.
+    #[inline(always)]
+    pub(super) fn shrink_to_fit(&mut self) {
+        if self.hybrid {
+            for chunk in self.chunks.values_mut() {
+                chunk.optimize(true);
+            }
+        }
+        self.chunks.shrink_to_fit();
+    }
+
+    /// Enables or disables hybrid containers, converting every chunk.
+    pub(super) fn set_hybrid(&mut self, enabled: bool) {
+        self.hybrid = enabled;
+        for chunk in self.chunks.values_mut() {
+            chunk.optimize(enabled);
+        }
+    }
+
+    /// Returns `true` if chunks pick their container by density.
+    #[inline(always)]
+    pub(super) fn is_hybrid(&self) -> bool {
+        self.hybrid
+    }
+
+    /// Returns the number of allocated chunks.
+    pub(super) fn allocated_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Counts the allocated chunks by container kind, for tests.
+    #[cfg(test)]
+    pub(super) fn container_kinds(&self) -> std::collections::BTreeMap<&'static str, usize> {
+        let mut kinds = std::collections::BTreeMap::new();
+        for (_, chunk) in self.chunks.iter() {
+            *kinds.entry(chunk.kind()).or_default() += 1;
+        }
+        kinds
+    }
+
+    /// Returns the smallest set slot greater than or equal to `index`, if any.
+    #[inline(always)]
+    pub(super) fn next_from(&self, index: usize) -> Option<usize> {
+        let w = index / WORD_BITS;
+        let bits = self.word(w) & (!0u64 << (index % WORD_BITS));
+        if bits != 0 {
+            return Some(w * WORD_BITS + bits.trailing_zeros() as usize);
+        }
+        let w = self.next_word(w + 1)?;
+        Some(w * WORD_BITS + self.word(w).trailing_zeros() as usize)
+    }
+
+    /// Returns the largest set slot less than or equal to `index`, if any.
+    #[inline(always)]
+    pub(super) fn prev_from(&self, index: usize) -> Option<usize> {
+        let w = index / WORD_BITS;
+        let bits = self.word(w) & (!0u64 >> (WORD_BITS - 1 - index % WORD_BITS));
+        if bits != 0 {
+            return Some(w * WORD_BITS + (WORD_BITS - 1 - bits.leading_zeros() as usize));
+        }
+        let w = self.prev_word(w.checked_sub(1)?)?;
+        Some(w * WORD_BITS + (WORD_BITS - 1 - self.word(w).leading_zeros() as usize))
+    }
+
+    /// Counts the set slots in `start..end`, clamped to the indicator length.
+    ///
+    /// Chunks fully inside the range contribute their cached count, so this
+    /// only scans the two boundary chunks.
+    pub(super) fn count_range(&self, start: usize, end: usize) -> usize {
+        let end = end.min(self.len);
+        if start >= end {
+            return 0;
+        }
+        let (first, last) = (start / CHUNK_BITS, (end - 1) / CHUNK_BITS);
+        self.chunks
+            .range(first, last + 1)
+            .map(|(c, chunk)| {
+                let lo = if c == first { start % CHUNK_BITS } else { 0 };
+                let hi = if c == last {
+                    (end - 1) % CHUNK_BITS + 1
+                } else {
+                    CHUNK_BITS
+                };
+                chunk.count_range(lo, hi)
+            })
+            .sum()
+    }
+
+    /// Enables or disables the rank directory.
+    ///
+    /// Disabling it frees the directory; enabling it defers the build to the
+    /// next `rank` or `select`.
+    pub(super) fn set_rank_index(&mut self, enabled: bool) {
+        self.ranks.enabled = enabled;
+        self.ranks.index.take();
+    }
+
+    /// Returns `true` if the rank directory is enabled.
+    #[inline(always)]
+    pub(super) fn has_rank_index(&self) -> bool {
+        self.ranks.enabled
+    }
+
+    /// Counts the set slots strictly below `index`.
+    ///
+    /// With the rank directory this costs one chunk lookup, which is O(1) when
+    /// the allocated chunks are contiguous and O(log c) in the number of
+    /// allocated chunks otherwise. Without it, it is O(c + 1024).
+    #[inline(always)]
+    pub(super) fn rank(&self, index: usize) -> usize {
+        let index = index.min(self.len);
+        let Some(ranks) = self.rank_index() else {
+            return self.count_range(0, index);
+        };
+        let chunks = &self.chunks;
+        Ranked {
+            chunks,
+            index: ranks,
+        }
+        .rank_slot(index)
+    }
+
+    /// Returns the `k`-th smallest set slot (0-based), if there are more than `k`.
+    ///
+    /// Runs in O(log(len / 512)) with the rank directory. Without it, whole
+    /// chunks are skipped by their cached counts.
+    pub(super) fn select(&self, k: usize) -> Option<usize> {
+        let (pos, word, remaining) = match self.rank_index() {
+            Some(ranks) => {
+                if k >= *ranks.chunks.last()? {
+                    return None;
+                }
+                // The last chunk, then block, whose prefix count does not exceed `k` holds the answer
+                let pos = ranks.chunks.partition_point(|&count| count <= k) - 1;
+                let k = k - ranks.chunks[pos];
+                let blocks = &ranks.blocks[pos * CHUNK_BLOCKS..(pos + 1) * CHUNK_BLOCKS];
+                let block = blocks.partition_point(|&count| count as usize <= k) - 1;
+                (pos, block * RANK_BLOCK_WORDS, k - blocks[block] as usize)
+            }
+            None => {
+                let mut remaining = k;
+                let mut chunks = self.chunks.iter().enumerate();
+                loop {
+                    let (pos, (_, chunk)) = chunks.next()?;
+                    if remaining < chunk.ones() {
+                        break (pos, 0, remaining);
+                    }
+                    remaining -= chunk.ones();
+                }
+            }
+        };
+        let (c, chunk) = self.chunks.entry(pos)?;
+        Some(c * CHUNK_BITS + chunk.select_from(word, remaining))
+    }
+
+    /// Returns the rank directory, building it first if it is enabled but stale.
+    #[inline(always)]
+    fn rank_index(&self) -> Option<&RankIndex> {
+        if !self.ranks.enabled {
+            return None;
+        }
+        let index = self.ranks.index.get_or_init(|| {
+            let (chunks, blocks) = self.rank_layout();
+            RankIndex { chunks, blocks }
+        });
+        Some(index)
+    }
+
+    /// Computes the rank directory: the number of set slots before every
+    /// allocated chunk, plus the total, and before every block within each.
+    pub(super) fn rank_layout(&self) -> (Vec<usize>, Vec<u16>) {
+        let mut total = 0;
+        let mut chunks = Vec::with_capacity(self.chunks.len() + 1);
+        let mut blocks = Vec::with_capacity(self.chunks.len() * CHUNK_BLOCKS);
+        for (_, chunk) in self.chunks.iter() {
+            chunks.push(total);
+            let mut within = 0;
+            for b in 0..CHUNK_BLOCKS {
+                blocks.push(within as u16);
+                within += chunk.count_range(b * BLOCK_BITS, (b + 1) * BLOCK_BITS);
+            }
+            total += within;
+        }
+        chunks.push(total);
+        (chunks, blocks)
+    }
+
+    /// Drops a stale rank directory so the next query rebuilds it.
+    #[inline(always)]
+    fn invalidate_ranks(&mut self) {
+        if self.ranks.enabled {
+            self.ranks.index.take();
+        }
+    }
+
+    /// Returns an iterator over the set slots in ascending order.
+    #[inline(always)]
+    pub(super) fn ones(&self) -> Ones<&Self> {
+        Ones::new(self, 0, self.len)
+    }
+
+    /// Returns an iterator over the set slots in `start..end`, in ascending order.
+    ///
+    /// The range is clamped to the indicator length.
+    #[inline(always)]
+    pub(super) fn ones_in(&self, start: usize, end: usize) -> Ones<&Self> {
+        Ones::new(self, start, end)
+    }
+
+    /// Combines two indicators word by word into a new one with `len` slots.
+    ///
+    /// The result keeps this indicator's container mode and rank directory
+    /// setting, and its directory is built on the first query.
+    ///
+    /// Chunks missing from either operand are treated as zero, and chunks
+    /// missing from both are skipped, so `op(0, 0)` must be zero. `len` must
+    /// be at least as large as any slot `op` can produce.
+    pub(super) fn combine<F>(&self, other: &Self, len: usize, op: F) -> Self
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        let chunks = self.chunk_pairs(other).filter_map(|(c, a, b)| {
+            let (a, b) = (a.map(Chunk::words), b.map(Chunk::words));
+            let words: Vec<u64> = (0..CHUNK_WORDS)
+                .map(|i| {
+                    op(
+                        a.as_ref().map_or(0, |a| a[i]),
+                        b.as_ref().map_or(0, |b| b[i]),
+                    )
+                })
+                .collect();
+            Some((c, Chunk::from_words(&words, self.hybrid)?))
+        });
+        Self {
+            chunks: SparseVec::from_sorted(chunks),
+            len,
+            hybrid: self.hybrid,
+            ranks: RankDirectory {
+                enabled: self.ranks.enabled,
+                index: OnceLock::new(),
+            },
+        }
+    }
+
+    /// Returns `true` if `pred` holds for every pair of words.
+    ///
+    /// Chunks missing from either operand are treated as zero, and chunks
+    /// missing from both are skipped, so `pred(0, 0)` must hold.
+    pub(super) fn all_words<F>(&self, other: &Self, pred: F) -> bool
+    where
+        F: Fn(u64, u64) -> bool,
+    {
+        self.chunk_pairs(other).all(|(_, a, b)| {
+            let (a, b) = (a.map(Chunk::words), b.map(Chunk::words));
+            (0..CHUNK_WORDS).all(|i| {
+                pred(
+                    a.as_ref().map_or(0, |a| a[i]),
+                    b.as_ref().map_or(0, |b| b[i]),
+                )
+            })
+        })
+    }
+
+    /// Walks the chunks allocated in either indicator in ascending order,
+    /// pairing each with the chunk at the same index in the other, if any.
+    fn chunk_pairs<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> impl Iterator<Item = (usize, Option<&'a Chunk>, Option<&'a Chunk>)> + 'a {
+        let mut left = self.chunks.iter().peekable();
+        let mut right = other.chunks.iter().peekable();
+        std::iter::from_fn(move || {
+            let c = match (left.peek(), right.peek()) {
+                (None, None) => return None,
+                (Some(&(a, _)), None) => a,
+                (None, Some(&(b, _))) => b,
+                (Some(&(a, _)), Some(&(b, _))) => a.min(b),
+            };
+            let a = left.next_if(|&(key, _)| key == c).map(|(_, chunk)| chunk);
+            let b = right.next_if(|&(key, _)| key == c).map(|(_, chunk)| chunk);
+            Some((c, a, b))
+        })
+    }
+
+    /// Builds an indicator with `len` slots from `(chunk index, chunk)` pairs,
+    /// checking that they are ascending, in range and clear beyond `len`.
+    pub(super) fn from_chunks(
+        len: usize,
+        hybrid: bool,
+        chunks: Vec<(usize, Chunk)>,
+    ) -> Result<Self, &'static str> {
+        for (i, (c, chunk)) in chunks.iter().enumerate() {
+            let ordered = i == 0 || chunks[i - 1].0 < *c;
+            if !ordered || *c >= len.div_ceil(CHUNK_BITS) {
+                return Err("indicator chunks out of order or range");
+            }
+            let tail = len - c * CHUNK_BITS;
+            if tail < CHUNK_BITS && chunk.count_range(tail, CHUNK_BITS) > 0 {
+                return Err("indicator has slots set beyond its length");
+            }
+        }
+        Ok(Self {
+            chunks: SparseVec::from_sorted(chunks),
+            len,
+            hybrid,
+            ranks: RankDirectory::default(),
+        })
+    }
+
+    /// Returns the words of every allocated chunk, with its chunk index.
+    pub(super) fn chunk_words(&self) -> impl Iterator<Item = (usize, Cow<'_, [u64]>)> + '_ {
+        self.chunks.iter().map(|(c, chunk)| (c, chunk.words()))
+    }
+
+    /// Returns word `w`, which is zero if its chunk is not allocated.
+    #[inline(always)]
+    fn word(&self, w: usize) -> u64 {
+        match self.chunks.get(w / CHUNK_WORDS) {
+            Some(chunk) => chunk.word(w % CHUNK_WORDS),
+            None => 0,
+        }
+    }
+
+    /// Returns the first non-zero word at or after `w`, if any.
+    #[inline(always)]
+    fn next_word(&self, w: usize) -> Option<usize> {
+        let c = w / CHUNK_WORDS;
+        let (key, chunk) = self.chunks.next(c)?;
+        if key == c {
+            if let Some(found) = chunk.next_word(w % CHUNK_WORDS) {
+                return Some(c * CHUNK_WORDS + found);
+            }
+            let (key, chunk) = self.chunks.next(c + 1)?;
+            return Some(key * CHUNK_WORDS + chunk.next_word(0)?);
+        }
+        Some(key * CHUNK_WORDS + chunk.next_word(0)?)
+    }
+
+    /// Returns the last non-zero word at or before `w`, if any.
+    #[inline(always)]
+    fn prev_word(&self, w: usize) -> Option<usize> {
+        let c = w / CHUNK_WORDS;
+        let (key, chunk) = self.chunks.prev(c)?;
+        if key == c {
+            if let Some(found) = chunk.prev_word(w % CHUNK_WORDS) {
+                return Some(c * CHUNK_WORDS + found);
+            }
+            let (key, chunk) = self.chunks.prev(c.checked_sub(1)?)?;
+            return Some(key * CHUNK_WORDS + chunk.prev_word(CHUNK_WORDS - 1)?);
+        }
+        Some(key * CHUNK_WORDS + chunk.prev_word(CHUNK_WORDS - 1)?)
+    }
+
+    #[inline(always)]
+    fn check(&self, index: usize) {
+        if index >= self.len {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len, index
+            );
+        }
+    }
+}
+
+/// Serializes only the allocated chunks, as `(chunk index, container)` pairs.
+impl Serialize for Indicator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let chunks: Vec<(usize, ContainerRepr<'_>)> = self
+            .chunks
+            .iter()
+            .map(|(c, chunk)| (c, chunk.to_repr()))
+            .collect();
+        let mut state = serializer.serialize_struct("Indicator", 3)?;
+        state.serialize_field("len", &self.len)?;
+        state.serialize_field("hybrid", &self.hybrid)?;
+        state.serialize_field("chunks", &chunks)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Indicator {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Indicator")]
+        struct Repr<'a> {
+            len: usize,
+            #[serde(default)]
+            hybrid: bool,
+            chunks: Vec<(usize, ContainerRepr<'a>)>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let chunks = repr
+            .chunks
+            .into_iter()
+            .map(|(c, container)| Ok((c, Chunk::from_repr(container, repr.hybrid)?)))
+            .collect::<Result<Vec<_>, &'static str>>()
+            .map_err(D::Error::custom)?;
+        Self::from_chunks(repr.len, repr.hybrid, chunks).map_err(D::Error::custom)
+    }
+}
+
+/// Iterator over the set slots of an `Indicator`, in ascending order.
+///
+/// Generic over borrowed or owned indicators, so the same cursor logic backs
+/// both borrowing and consuming iterators. The front and back cursors each
+/// hold the remaining bits of their word and jump straight to the next
+/// non-zero word; once they meet on the same word, its bits live in
+/// `front_bits` only.
+#[derive(Clone)]
+pub(super) struct Ones<I> {
+    indicator: I,
+    front: usize,
+    front_bits: u64,
+    back: usize,
+    back_bits: u64,
+}
+
+impl<I: Borrow<Indicator>> Ones<I> {
+    /// Creates an iterator over the set slots in `start..end`, clamped to the indicator length.
+    pub(super) fn new(indicator: I, start: usize, end: usize) -> Self {
+        let inner = indicator.borrow();
+        let end = end.min(inner.len);
+        let (front, front_bits, back, back_bits) = if start >= end {
+            (0, 0, 0, 0)
+        } else {
+            let (first, last) = (start / WORD_BITS, (end - 1) / WORD_BITS);
+            let head = !0u64 << (start % WORD_BITS);
+            let tail = !0u64 >> (WORD_BITS - 1 - (end - 1) % WORD_BITS);
+            match first == last {
+                true => (first, inner.word(first) & head & tail, last, 0),
+                false => (
+                    first,
+                    inner.word(first) & head,
+                    last,
+                    inner.word(last) & tail,
+                ),
+            }
+        };
+        Ones {
+            indicator,
+            front,
+            front_bits,
+            back,
+            back_bits,
+        }
+    }
+}
+
+impl<I: Borrow<Indicator>> Iterator for Ones<I> {
+    type Item = usize;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<usize> {
+        while self.front_bits == 0 {
+            if self.front == self.back {
+                return None;
+            }
+            let indicator = self.indicator.borrow();
+            match indicator.next_word(self.front + 1) {
+                Some(w) if w < self.back => {
+                    self.front_bits = indicator.word(w);
+                    self.front = w;
+                }
+                _ => {
+                    self.front = self.back;
+                    self.front_bits = std::mem::take(&mut self.back_bits);
+                }
+            }
+        }
+        let bit = self.front_bits.trailing_zeros() as usize;
+        self.front_bits &= self.front_bits - 1;
+        Some(self.front * WORD_BITS + bit)
+    }
+}
+
+impl<I: Borrow<Indicator>> DoubleEndedIterator for Ones<I> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<usize> {
+        loop {
+            let bits = match self.front == self.back {
+                true => &mut self.front_bits,
+                false => &mut self.back_bits,
+            };
+            if *bits != 0 {
+                let bit = WORD_BITS - 1 - bits.leading_zeros() as usize;
+                *bits &= !(1u64 << bit);
+                return Some(self.back * WORD_BITS + bit);
+            }
+            if self.front == self.back {
+                return None;
+            }
+            let indicator = self.indicator.borrow();
+            match indicator.prev_word(self.back - 1) {
+                Some(w) if w > self.front => {
+                    self.back_bits = indicator.word(w);
+                    self.back = w;
+                }
+                _ => self.back = self.front,
+            }
+        }
+    }
+}
+
+impl<I: Borrow<Indicator>> std::iter::FusedIterator for Ones<I> {}
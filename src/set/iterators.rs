@@ -1,13 +1,16 @@
 use super::core::Set;
+use super::element::Element;
+use super::indicator::{Indicator, Ones};
+use std::marker::PhantomData;
 
-/// Consumes the `Set`, returning an iterator over owned `usize` values.
+/// Consumes the `Set`, returning an iterator over owned values.
 ///
 /// # Examples
 ///
 /// ```
 /// use fastset::Set;
 ///
-/// let set = Set::from(vec![1, 2, 3]);
+/// let set: Set = Set::from(vec![1, 2, 3]);
 /// let mut iter = set.into_iter();
 ///
 /// assert_eq!(iter.next(), Some(1));
@@ -15,55 +18,228 @@ use super::core::Set;
 /// assert_eq!(iter.next(), Some(3));
 /// assert_eq!(iter.next(), None);
 /// ```
-impl IntoIterator for Set {
-    type Item = usize;
-    type IntoIter = std::vec::IntoIter<usize>;
+impl<T: Element, const PAGE: usize> IntoIterator for Set<T, PAGE> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.elements.into_iter()
     }
 }
 
-/// Borrows the `Set`, returning an iterator over references to `usize` values.
+/// Borrows the `Set`, returning an iterator over references to its values.
 ///
 /// # Examples
 ///
 /// ```
 /// use fastset::Set;
 ///
-/// let set = Set::from(vec![1, 2, 3]);
+/// let set: Set = Set::from(vec![1, 2, 3]);
 ///
 /// for &value in &set {
 ///     println!("{}", value);
 /// }
 /// ```
-impl<'a> IntoIterator for &'a Set {
-    type Item = &'a usize;
-    type IntoIter = std::slice::Iter<'a, usize>;
+impl<'a, T: Element, const PAGE: usize> IntoIterator for &'a Set<T, PAGE> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.elements.iter()
     }
 }
 
-/// Mutably borrows the `Set`, returning an iterator over mutable references to `usize` values.
+/// Mutably borrows the `Set`, returning an iterator over mutable references to its values.
 ///
 /// # Examples
 ///
 /// ```
 /// use fastset::Set;
 ///
-/// let mut set = Set::from(vec![1, 2, 3]);
+/// let mut set: Set = Set::from(vec![1, 2, 3]);
 ///
 /// for value in &mut set {
 ///     *value += 1;
 /// }
 /// ```
-impl<'a> IntoIterator for &'a mut Set {
-    type Item = &'a mut usize;
-    type IntoIter = std::slice::IterMut<'a, usize>;
+impl<'a, T: Element, const PAGE: usize> IntoIterator for &'a mut Set<T, PAGE> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.elements.iter_mut()
     }
 }
+
+/// An iterator over a sub-range of values in a `Set`, in ascending order.
+///
+/// This `struct` is created by the [`Set::range`] method.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::Set;
+///
+/// let set: Set = Set::from(vec![3, 8, 1, 6]);
+/// let mut range = set.range(2..7);
+///
+/// assert_eq!(range.next(), Some(3));
+/// assert_eq!(range.next(), Some(6));
+/// assert_eq!(range.next(), None);
+/// ```
+#[derive(Clone)]
+pub struct SetRange<'a, T = usize> {
+    inner: Ones<&'a Indicator>,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: Element> SetRange<'a, T> {
+    pub(super) fn new(inner: Ones<&'a Indicator>) -> Self {
+        SetRange {
+            inner,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Element> Iterator for SetRange<'_, T> {
+    type Item = T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(T::from_index)
+    }
+}
+
+impl<T: Element> DoubleEndedIterator for SetRange<'_, T> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.next_back().map(T::from_index)
+    }
+}
+
+impl<T: Element> std::iter::FusedIterator for SetRange<'_, T> {}
+
+/// An iterator over the values in a `Set`, in ascending order.
+///
+/// This `struct` is created by the [`Set::iter_sorted`] method. Reverse it
+/// with `.rev()` to walk the values in descending order.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::Set;
+///
+/// let set: Set = Set::from(vec![3, 1, 2]);
+/// let mut iter = set.iter_sorted();
+///
+/// assert_eq!(iter.len(), 3);
+/// assert_eq!(iter.next(), Some(1));
+/// assert_eq!(iter.next_back(), Some(3));
+/// assert_eq!(iter.next(), Some(2));
+/// assert_eq!(iter.next(), None);
+/// ```
+#[derive(Clone)]
+pub struct SortedIter<'a, T = usize> {
+    inner: Ones<&'a Indicator>,
+    remaining: usize,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: Element> SortedIter<'a, T> {
+    pub(super) fn new(inner: Ones<&'a Indicator>, remaining: usize) -> Self {
+        SortedIter {
+            inner,
+            remaining,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Element> Iterator for SortedIter<'_, T> {
+    type Item = T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<T> {
+        let value = self.inner.next()?;
+        self.remaining -= 1;
+        Some(T::from_index(value))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: Element> DoubleEndedIterator for SortedIter<'_, T> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<T> {
+        let value = self.inner.next_back()?;
+        self.remaining -= 1;
+        Some(T::from_index(value))
+    }
+}
+
+impl<T: Element> ExactSizeIterator for SortedIter<'_, T> {}
+
+impl<T: Element> std::iter::FusedIterator for SortedIter<'_, T> {}
+
+/// An owning iterator over the values of a `Set`, in ascending order.
+///
+/// This `struct` is created by the [`Set::into_sorted_iter`] method.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::Set;
+///
+/// let set: Set = Set::from(vec![30, 10, 20]);
+///
+/// assert_eq!(set.into_sorted_iter().collect::<Vec<_>>(), vec![10, 20, 30]);
+/// ```
+#[derive(Clone)]
+pub struct IntoSortedIter<T = usize> {
+    inner: Ones<Indicator>,
+    remaining: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T: Element> IntoSortedIter<T> {
+    pub(super) fn new(inner: Ones<Indicator>, remaining: usize) -> Self {
+        IntoSortedIter {
+            inner,
+            remaining,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Element> Iterator for IntoSortedIter<T> {
+    type Item = T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<T> {
+        let value = self.inner.next()?;
+        self.remaining -= 1;
+        Some(T::from_index(value))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: Element> DoubleEndedIterator for IntoSortedIter<T> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<T> {
+        let value = self.inner.next_back()?;
+        self.remaining -= 1;
+        Some(T::from_index(value))
+    }
+}
+
+impl<T: Element> ExactSizeIterator for IntoSortedIter<T> {}
+
+impl<T: Element> std::iter::FusedIterator for IntoSortedIter<T> {}
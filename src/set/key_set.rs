@@ -0,0 +1,247 @@
+use super::delphic::DelphicSet;
+use super::random::uniform_index;
+use rand_core::RngCore;
+use std::collections::hash_map::{Entry, RandomState};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+/// A hash set of arbitrary keys that also samples uniformly in O(1).
+///
+/// It brings the element list and swap-remove layout of [`Set`](super::Set)
+/// to keys that are not small integers: the keys are stored in a dense
+/// list, and a hash map records each key's position in it. Removing a key
+/// moves the last key into its slot, so the list stays dense and `random`
+/// is a uniform index into it.
+///
+/// `insert`, `remove` and `contains` cost one hash lookup each, and `len`
+/// and `random` are O(1). Each key is stored twice, once in the list and
+/// once in the map, so cheaply cloned keys suit it best.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::KeySet;
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+///
+/// let mut users: KeySet<&str> = ["ada", "grace", "alan"].into_iter().collect();
+/// users.remove(&"grace");
+///
+/// let mut rng = ChaCha8Rng::seed_from_u64(42);
+/// let user = users.random(&mut rng).unwrap();
+/// assert!(*user == "ada" || *user == "alan");
+/// ```
+#[derive(Clone)]
+pub struct KeySet<K, S = RandomState> {
+    keys: Vec<K>,
+    positions: HashMap<K, usize, S>,
+}
+
+impl<K: Hash + Eq + Clone> KeySet<K> {
+    /// Creates an empty KeySet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty KeySet with room for `capacity` keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            keys: Vec::with_capacity(capacity),
+            positions: HashMap::with_capacity(capacity),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, S: BuildHasher> KeySet<K, S> {
+    /// Creates an empty KeySet hashing its keys with `hasher`.
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            keys: Vec::new(),
+            positions: HashMap::with_hasher(hasher),
+        }
+    }
+
+    /// Returns the number of keys.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the KeySet holds no keys.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns `true` if the KeySet holds `key`.
+    #[inline(always)]
+    pub fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// Inserts `key`, returning `true` if it was not already present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::KeySet;
+    ///
+    /// let mut set = KeySet::new();
+    /// assert!(set.insert("ada"));
+    /// assert!(!set.insert("ada"));
+    /// ```
+    pub fn insert(&mut self, key: K) -> bool {
+        match self.positions.entry(key) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                self.keys.push(entry.key().clone());
+                entry.insert(self.keys.len() - 1);
+                true
+            }
+        }
+    }
+
+    /// Removes `key`, returning `true` if it was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::KeySet;
+    ///
+    /// let mut set: KeySet<_> = ["ada", "alan"].into_iter().collect();
+    /// assert!(set.remove(&"ada"));
+    /// assert!(!set.remove(&"ada"));
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn remove(&mut self, key: &K) -> bool {
+        let Some(position) = self.positions.remove(key) else {
+            return false;
+        };
+        self.keys.swap_remove(position);
+        self.reposition(position);
+        true
+    }
+
+    /// Returns a key drawn uniformly at random, or `None` if the KeySet is
+    /// empty.
+    #[inline(always)]
+    pub fn random<R: RngCore + ?Sized>(&self, rng: &mut R) -> Option<&K> {
+        match self.keys.is_empty() {
+            false => Some(&self.keys[uniform_index(rng, self.keys.len())]),
+            true => None,
+        }
+    }
+
+    /// Removes and returns a key drawn uniformly at random, or `None` if the
+    /// KeySet is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::KeySet;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let mut set: KeySet<_> = ["ada", "alan"].into_iter().collect();
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let key = set.pop_random(&mut rng).unwrap();
+    /// assert!(!set.contains(&key));
+    /// ```
+    pub fn pop_random<R: RngCore + ?Sized>(&mut self, rng: &mut R) -> Option<K> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        let position = uniform_index(rng, self.keys.len());
+        let key = self.keys.swap_remove(position);
+        self.positions.remove(&key);
+        self.reposition(position);
+        Some(key)
+    }
+
+    /// Returns an iterator over the keys, in storage order.
+    pub fn iter(&self) -> std::slice::Iter<'_, K> {
+        self.keys.iter()
+    }
+
+    /// Removes every key.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.positions.clear();
+    }
+
+    /// Records the new position of the key a swap-remove moved into
+    /// `position`, if any.
+    #[inline(always)]
+    fn reposition(&mut self, position: usize) {
+        if let Some(moved) = self.keys.get(position) {
+            *self
+                .positions
+                .get_mut(moved)
+                .expect("every listed key has a position") = position;
+        }
+    }
+}
+
+impl<K, S: Default> Default for KeySet<K, S> {
+    fn default() -> Self {
+        Self {
+            keys: Vec::new(),
+            positions: HashMap::default(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, S> fmt::Debug for KeySet<K, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.keys.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq + Clone, S: BuildHasher> Extend<K> for KeySet<K, S> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, S: BuildHasher + Default> FromIterator<K> for KeySet<K, S> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+/// A KeySet answers membership with one hash lookup, and cardinality and
+/// sampling in O(1).
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{DelphicSet, KeySet};
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+///
+/// let set: KeySet<String> = ["ada".to_string()].into_iter().collect();
+/// let delphic: &dyn DelphicSet<String> = &set;
+/// let mut rng = ChaCha8Rng::seed_from_u64(42);
+/// assert_eq!(delphic.sample(&mut rng).as_deref(), Some("ada"));
+/// ```
+impl<K: Hash + Eq + Clone, S: BuildHasher> DelphicSet<K> for KeySet<K, S> {
+    #[inline(always)]
+    fn contains(&self, value: &K) -> bool {
+        KeySet::contains(self, value)
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        KeySet::len(self)
+    }
+
+    #[inline(always)]
+    fn sample(&self, rng: &mut dyn RngCore) -> Option<K> {
+        self.random(rng).cloned()
+    }
+}
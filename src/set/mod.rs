@@ -1,15 +1,37 @@
+mod container;
 mod conversions;
 mod core;
+mod delphic;
+mod distinct;
+mod element;
+mod error;
+mod indicator;
 mod iterators;
+mod key_set;
 mod operators;
 mod ops;
+mod positions;
+mod random;
+mod serialization;
+mod snapshot;
+mod sparse_vec;
 mod traits;
+mod view;
+mod weighted;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::core::Set;
-pub use self::ops::SetOps;
-
-// Re-export MAX_CAPACITY for internal use
-pub(crate) use crate::MAX_CAPACITY;
+pub use self::delphic::{sample_from_union, union_size_estimate, DelphicSet};
+pub use self::distinct::DistinctCounter;
+pub use self::element::Element;
+pub use self::error::{Error, InsertOutcome};
+pub use self::iterators::{IntoSortedIter, SetRange, SortedIter};
+pub use self::key_set::KeySet;
+pub use self::ops::{SetOps, SortedSlice};
+#[cfg(feature = "nanorand")]
+pub use self::random::NanoRng;
+pub use self::snapshot::Encoding;
+pub use self::view::SetView;
+pub use self::weighted::WeightedSet;
@@ -1,5 +1,8 @@
 use super::core::Set;
-use std::collections::HashSet;
+use super::element::Element;
+use super::ops::SortedSlice;
+use std::collections::{BTreeSet, HashSet};
+use std::hash::BuildHasher;
 
 /// Performs the union operation between two references to `Set` instances.
 ///
@@ -7,35 +10,15 @@ use std::collections::HashSet;
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let result = &set1 | &set2;
 /// assert_eq!(result, Set::from_iter(0..8));
 /// ```
-impl<'a> std::ops::BitOr<&'a Set> for &'a Set {
-    type Output = Set;
+impl<'a, T: Element, const PAGE: usize> std::ops::BitOr<&'a Set<T, PAGE>> for &'a Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitor(self, rhs: &'a Set) -> Set {
-        self.union(rhs)
-    }
-}
-
-/// Performs the union operation between a reference to `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset = HashSet::<usize>::from_iter(3..8);
-/// let result = &set | &hashset;
-/// assert_eq!(result, Set::from_iter(0..8));
-/// ```
-impl<'a> std::ops::BitOr<&'a HashSet<usize>> for &'a Set {
-    type Output = Set;
-
-    fn bitor(self, rhs: &'a HashSet<usize>) -> Set {
+    fn bitor(self, rhs: &'a Set<T, PAGE>) -> Set<T, PAGE> {
         self.union(rhs)
     }
 }
@@ -46,35 +29,15 @@ impl<'a> std::ops::BitOr<&'a HashSet<usize>> for &'a Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let result = set1 | &set2;
 /// assert_eq!(result, Set::from_iter(0..8));
 /// ```
-impl std::ops::BitOr<&Set> for Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::BitOr<&Set<T, PAGE>> for Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitor(self, rhs: &Set) -> Set {
-        self.union(rhs)
-    }
-}
-
-/// Performs the union operation between an owned `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let result = set | &hashset;
-/// assert_eq!(result, Set::from_iter(0..8));
-/// ```
-impl std::ops::BitOr<&HashSet<usize>> for Set {
-    type Output = Set;
-
-    fn bitor(self, rhs: &HashSet<usize>) -> Set {
+    fn bitor(self, rhs: &Set<T, PAGE>) -> Set<T, PAGE> {
         self.union(rhs)
     }
 }
@@ -85,35 +48,15 @@ impl std::ops::BitOr<&HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let result = &set1 | set2;
 /// assert_eq!(result, Set::from_iter(0..8));
 /// ```
-impl<'a> std::ops::BitOr<Set> for &'a Set {
-    type Output = Set;
-
-    fn bitor(self, rhs: Set) -> Set {
-        self.union(&rhs)
-    }
-}
-
-/// Performs the union operation between a reference to `Set` and an owned `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let result = &set | hashset;
-/// assert_eq!(result, Set::from_iter(0..8));
-/// ```
-impl<'a> std::ops::BitOr<HashSet<usize>> for &'a Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::BitOr<Set<T, PAGE>> for &Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitor(self, rhs: HashSet<usize>) -> Set {
+    fn bitor(self, rhs: Set<T, PAGE>) -> Set<T, PAGE> {
         self.union(&rhs)
     }
 }
@@ -124,35 +67,15 @@ impl<'a> std::ops::BitOr<HashSet<usize>> for &'a Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let result = set1 | set2;
 /// assert_eq!(result, Set::from_iter(0..8));
 /// ```
-impl std::ops::BitOr for Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::BitOr for Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitor(self, rhs: Set) -> Set {
-        self.union(&rhs)
-    }
-}
-
-/// Performs the union operation between an owned `Set` and an owned `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let result = set | hashset;
-/// assert_eq!(result, Set::from_iter(0..8));
-/// ```
-impl std::ops::BitOr<HashSet<usize>> for Set {
-    type Output = Set;
-
-    fn bitor(self, rhs: HashSet<usize>) -> Set {
+    fn bitor(self, rhs: Set<T, PAGE>) -> Set<T, PAGE> {
         self.union(&rhs)
     }
 }
@@ -163,32 +86,14 @@ impl std::ops::BitOr<HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let mut set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let mut set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// set1 |= &set2;
 /// assert_eq!(set1, Set::from_iter(0..8));
 /// ```
-impl<'a> std::ops::BitOrAssign<&'a Set> for Set {
-    fn bitor_assign(&mut self, rhs: &'a Set) {
-        *self = self.union(rhs);
-    }
-}
-
-/// Performs the union assignment operation between a reference to `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let mut set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// set |= &hashset;
-/// assert_eq!(set, Set::from_iter(0..8));
-/// ```
-impl<'a> std::ops::BitOrAssign<&'a HashSet<usize>> for Set {
-    fn bitor_assign(&mut self, rhs: &'a HashSet<usize>) {
-        *self = self.union(rhs);
+impl<'a, T: Element, const PAGE: usize> std::ops::BitOrAssign<&'a Set<T, PAGE>> for Set<T, PAGE> {
+    fn bitor_assign(&mut self, rhs: &'a Set<T, PAGE>) {
+        self.union_with(rhs);
     }
 }
 
@@ -198,35 +103,15 @@ impl<'a> std::ops::BitOrAssign<&'a HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let intersection = &set1 & &set2;
 /// assert_eq!(intersection, Set::from_iter(3..5));
 /// ```
-impl<'a> std::ops::BitAnd<&'a Set> for &'a Set {
-    type Output = Set;
-
-    fn bitand(self, rhs: &'a Set) -> Set {
-        self.intersection(rhs)
-    }
-}
-
-/// Performs the intersection operation between a reference to `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let intersection = &set & &hashset;
-/// assert_eq!(intersection, Set::from_iter(3..5));
-/// ```
-impl<'a> std::ops::BitAnd<&'a HashSet<usize>> for &'a Set {
-    type Output = Set;
+impl<'a, T: Element, const PAGE: usize> std::ops::BitAnd<&'a Set<T, PAGE>> for &'a Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitand(self, rhs: &'a HashSet<usize>) -> Set {
+    fn bitand(self, rhs: &'a Set<T, PAGE>) -> Set<T, PAGE> {
         self.intersection(rhs)
     }
 }
@@ -237,35 +122,15 @@ impl<'a> std::ops::BitAnd<&'a HashSet<usize>> for &'a Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let intersection = set1 & &set2;
 /// assert_eq!(intersection, Set::from_iter(3..5));
 /// ```
-impl std::ops::BitAnd<&Set> for Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::BitAnd<&Set<T, PAGE>> for Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitand(self, rhs: &Set) -> Set {
-        self.intersection(rhs)
-    }
-}
-
-/// Performs the intersection operation between an owned `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let intersection = set & &hashset;
-/// assert_eq!(intersection, Set::from_iter(3..5));
-/// ```
-impl std::ops::BitAnd<&HashSet<usize>> for Set {
-    type Output = Set;
-
-    fn bitand(self, rhs: &HashSet<usize>) -> Set {
+    fn bitand(self, rhs: &Set<T, PAGE>) -> Set<T, PAGE> {
         self.intersection(rhs)
     }
 }
@@ -276,35 +141,15 @@ impl std::ops::BitAnd<&HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let intersection = &set1 & set2;
 /// assert_eq!(intersection, Set::from_iter(3..5));
 /// ```
-impl<'a> std::ops::BitAnd<Set> for &'a Set {
-    type Output = Set;
-
-    fn bitand(self, rhs: Set) -> Set {
-        self.intersection(&rhs)
-    }
-}
-
-/// Performs the intersection operation between a reference to `Set` and an owned `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let intersection = &set & hashset;
-/// assert_eq!(intersection, Set::from_iter(3..5));
-/// ```
-impl<'a> std::ops::BitAnd<HashSet<usize>> for &'a Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::BitAnd<Set<T, PAGE>> for &Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitand(self, rhs: HashSet<usize>) -> Set {
+    fn bitand(self, rhs: Set<T, PAGE>) -> Set<T, PAGE> {
         self.intersection(&rhs)
     }
 }
@@ -315,35 +160,15 @@ impl<'a> std::ops::BitAnd<HashSet<usize>> for &'a Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let intersection = set1 & set2;
 /// assert_eq!(intersection, Set::from_iter(3..5));
 /// ```
-impl std::ops::BitAnd for Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::BitAnd for Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitand(self, rhs: Set) -> Set {
-        self.intersection(&rhs)
-    }
-}
-
-/// Performs the intersection operation between an owned `Set` and an owned `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let intersection = set & hashset;
-/// assert_eq!(intersection, Set::from_iter(3..5));
-/// ```
-impl std::ops::BitAnd<HashSet<usize>> for Set {
-    type Output = Set;
-
-    fn bitand(self, rhs: HashSet<usize>) -> Set {
+    fn bitand(self, rhs: Set<T, PAGE>) -> Set<T, PAGE> {
         self.intersection(&rhs)
     }
 }
@@ -354,32 +179,14 @@ impl std::ops::BitAnd<HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let mut set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let mut set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// set1 &= &set2;
 /// assert_eq!(set1, Set::from_iter(3..5));
 /// ```
-impl<'a> std::ops::BitAndAssign<&'a Set> for Set {
-    fn bitand_assign(&mut self, rhs: &'a Set) {
-        *self = self.intersection(rhs);
-    }
-}
-
-/// Performs the intersection assignment operation between a reference to `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let mut set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// set &= &hashset;
-/// assert_eq!(set, Set::from_iter(3..5));
-/// ```
-impl<'a> std::ops::BitAndAssign<&'a HashSet<usize>> for Set {
-    fn bitand_assign(&mut self, rhs: &'a HashSet<usize>) {
-        *self = self.intersection(rhs);
+impl<'a, T: Element, const PAGE: usize> std::ops::BitAndAssign<&'a Set<T, PAGE>> for Set<T, PAGE> {
+    fn bitand_assign(&mut self, rhs: &'a Set<T, PAGE>) {
+        self.intersect_with(rhs);
     }
 }
 
@@ -389,35 +196,15 @@ impl<'a> std::ops::BitAndAssign<&'a HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let result = &set1 - &set2;
 /// assert_eq!(result, Set::from_iter(0..3));
 /// ```
-impl<'a> std::ops::Sub<&'a Set> for &'a Set {
-    type Output = Set;
+impl<'a, T: Element, const PAGE: usize> std::ops::Sub<&'a Set<T, PAGE>> for &'a Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn sub(self, rhs: &'a Set) -> Set {
-        self.difference(rhs)
-    }
-}
-
-/// Performs the subtraction operation between a reference to `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let result = &set - &hashset;
-/// assert_eq!(result, Set::from_iter(0..3));
-/// ```
-impl<'a> std::ops::Sub<&'a HashSet<usize>> for &'a Set {
-    type Output = Set;
-
-    fn sub(self, rhs: &'a HashSet<usize>) -> Set {
+    fn sub(self, rhs: &'a Set<T, PAGE>) -> Set<T, PAGE> {
         self.difference(rhs)
     }
 }
@@ -428,35 +215,15 @@ impl<'a> std::ops::Sub<&'a HashSet<usize>> for &'a Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let result = set1 - &set2;
 /// assert_eq!(result, Set::from_iter(0..3));
 /// ```
-impl std::ops::Sub<&Set> for Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::Sub<&Set<T, PAGE>> for Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn sub(self, rhs: &Set) -> Set {
-        self.difference(rhs)
-    }
-}
-
-/// Performs the subtraction operation between an owned `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let result = set - &hashset;
-/// assert_eq!(result, Set::from_iter(0..3));
-/// ```
-impl std::ops::Sub<&HashSet<usize>> for Set {
-    type Output = Set;
-
-    fn sub(self, rhs: &HashSet<usize>) -> Set {
+    fn sub(self, rhs: &Set<T, PAGE>) -> Set<T, PAGE> {
         self.difference(rhs)
     }
 }
@@ -467,35 +234,15 @@ impl std::ops::Sub<&HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let result = &set1 - set2;
 /// assert_eq!(result, Set::from_iter(0..3));
 /// ```
-impl<'a> std::ops::Sub<Set> for &'a Set {
-    type Output = Set;
-
-    fn sub(self, rhs: Set) -> Set {
-        self.difference(&rhs)
-    }
-}
-
-/// Performs the subtraction operation between a reference to `Set` and an owned `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let result = &set - hashset;
-/// assert_eq!(result, Set::from_iter(0..3));
-/// ```
-impl<'a> std::ops::Sub<HashSet<usize>> for &'a Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::Sub<Set<T, PAGE>> for &Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn sub(self, rhs: HashSet<usize>) -> Set {
+    fn sub(self, rhs: Set<T, PAGE>) -> Set<T, PAGE> {
         self.difference(&rhs)
     }
 }
@@ -506,35 +253,15 @@ impl<'a> std::ops::Sub<HashSet<usize>> for &'a Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let result = set1 - set2;
 /// assert_eq!(result, Set::from_iter(0..3));
 /// ```
-impl std::ops::Sub for Set {
-    type Output = Set;
-
-    fn sub(self, rhs: Set) -> Set {
-        self.difference(&rhs)
-    }
-}
-
-/// Performs the subtraction operation between an owned `Set` and an owned `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let result = set - hashset;
-/// assert_eq!(result, Set::from_iter(0..3));
-/// ```
-impl std::ops::Sub<HashSet<usize>> for Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::Sub for Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn sub(self, rhs: HashSet<usize>) -> Set {
+    fn sub(self, rhs: Set<T, PAGE>) -> Set<T, PAGE> {
         self.difference(&rhs)
     }
 }
@@ -545,32 +272,14 @@ impl std::ops::Sub<HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let mut set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let mut set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// set1 -= &set2;
 /// assert_eq!(set1, Set::from_iter(0..3));
 /// ```
-impl<'a> std::ops::SubAssign<&'a Set> for Set {
-    fn sub_assign(&mut self, rhs: &'a Set) {
-        *self = self.difference(rhs);
-    }
-}
-
-/// Performs the subtraction assignment operation between a `Set` reference and a `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let mut set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// set -= &hashset;
-/// assert_eq!(set, Set::from_iter(0..3));
-/// ```
-impl<'a> std::ops::SubAssign<&'a HashSet<usize>> for Set {
-    fn sub_assign(&mut self, rhs: &'a HashSet<usize>) {
-        *self = self.difference(rhs);
+impl<'a, T: Element, const PAGE: usize> std::ops::SubAssign<&'a Set<T, PAGE>> for Set<T, PAGE> {
+    fn sub_assign(&mut self, rhs: &'a Set<T, PAGE>) {
+        self.difference_with(rhs);
     }
 }
 
@@ -580,32 +289,14 @@ impl<'a> std::ops::SubAssign<&'a HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let mut set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let mut set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// set1 -= set2;
 /// assert_eq!(set1, Set::from_iter(0..3));
 /// ```
-impl std::ops::SubAssign<Set> for Set {
-    fn sub_assign(&mut self, rhs: Set) {
-        *self = self.difference(&rhs);
-    }
-}
-
-/// Performs the subtraction assignment operation between a `Set` and a `HashSet<usize>` reference.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let mut set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// set -= hashset;
-/// assert_eq!(set, Set::from_iter(0..3));
-/// ```
-impl std::ops::SubAssign<HashSet<usize>> for Set {
-    fn sub_assign(&mut self, rhs: HashSet<usize>) {
-        *self = self.difference(&rhs);
+impl<T: Element, const PAGE: usize> std::ops::SubAssign<Set<T, PAGE>> for Set<T, PAGE> {
+    fn sub_assign(&mut self, rhs: Set<T, PAGE>) {
+        self.difference_with(&rhs);
     }
 }
 
@@ -615,35 +306,15 @@ impl std::ops::SubAssign<HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let symmetric_difference = &set1 ^ &set2;
-/// assert_eq!(symmetric_difference, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
+/// assert_eq!(symmetric_difference, Set::from_iter(0..3) | &Set::from_iter(5..8));
 /// ```
-impl<'a> std::ops::BitXor<&'a Set> for &'a Set {
-    type Output = Set;
+impl<'a, T: Element, const PAGE: usize> std::ops::BitXor<&'a Set<T, PAGE>> for &'a Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitxor(self, rhs: &'a Set) -> Set {
-        self.symmetric_difference(rhs)
-    }
-}
-
-/// Computes the symmetric difference between a reference to `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let symmetric_difference = &set ^ &hashset;
-/// assert_eq!(symmetric_difference, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
-/// ```
-impl<'a> std::ops::BitXor<&'a HashSet<usize>> for &'a Set {
-    type Output = Set;
-
-    fn bitxor(self, rhs: &'a HashSet<usize>) -> Set {
+    fn bitxor(self, rhs: &'a Set<T, PAGE>) -> Set<T, PAGE> {
         self.symmetric_difference(rhs)
     }
 }
@@ -654,35 +325,15 @@ impl<'a> std::ops::BitXor<&'a HashSet<usize>> for &'a Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let symmetric_difference = set1 ^ &set2;
-/// assert_eq!(symmetric_difference, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
+/// assert_eq!(symmetric_difference, Set::from_iter(0..3) | &Set::from_iter(5..8));
 /// ```
-impl std::ops::BitXor<&Set> for Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::BitXor<&Set<T, PAGE>> for Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitxor(self, rhs: &Set) -> Set {
-        self.symmetric_difference(rhs)
-    }
-}
-
-/// Computes the symmetric difference between an owned `Set` and a reference to `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let symmetric_difference = set ^ &hashset;
-/// assert_eq!(symmetric_difference, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
-/// ```
-impl std::ops::BitXor<&HashSet<usize>> for Set {
-    type Output = Set;
-
-    fn bitxor(self, rhs: &HashSet<usize>) -> Set {
+    fn bitxor(self, rhs: &Set<T, PAGE>) -> Set<T, PAGE> {
         self.symmetric_difference(rhs)
     }
 }
@@ -693,35 +344,15 @@ impl std::ops::BitXor<&HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let symmetric_difference = &set1 ^ set2;
-/// assert_eq!(symmetric_difference, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
+/// assert_eq!(symmetric_difference, Set::from_iter(0..3) | &Set::from_iter(5..8));
 /// ```
-impl<'a> std::ops::BitXor<Set> for &'a Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::BitXor<Set<T, PAGE>> for &Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitxor(self, rhs: Set) -> Set {
-        self.symmetric_difference(&rhs)
-    }
-}
-
-/// Computes the symmetric difference between a reference to `Set` and an owned `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let symmetric_difference = &set ^ hashset;
-/// assert_eq!(symmetric_difference, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
-/// ```
-impl<'a> std::ops::BitXor<HashSet<usize>> for &'a Set {
-    type Output = Set;
-
-    fn bitxor(self, rhs: HashSet<usize>) -> Set {
+    fn bitxor(self, rhs: Set<T, PAGE>) -> Set<T, PAGE> {
         self.symmetric_difference(&rhs)
     }
 }
@@ -732,35 +363,15 @@ impl<'a> std::ops::BitXor<HashSet<usize>> for &'a Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// let symmetric_difference = set1 ^ set2;
-/// assert_eq!(symmetric_difference, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
-/// ```
-impl std::ops::BitXor for Set {
-    type Output = Set;
-
-    fn bitxor(self, rhs: Set) -> Set {
-        self.symmetric_difference(&rhs)
-    }
-}
-
-/// Computes the symmetric difference between an owned `Set` and an owned `HashSet<usize>`.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// let symmetric_difference = set ^ hashset;
-/// assert_eq!(symmetric_difference, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
+/// assert_eq!(symmetric_difference, Set::from_iter(0..3) | &Set::from_iter(5..8));
 /// ```
-impl std::ops::BitXor<HashSet<usize>> for Set {
-    type Output = Set;
+impl<T: Element, const PAGE: usize> std::ops::BitXor for Set<T, PAGE> {
+    type Output = Set<T, PAGE>;
 
-    fn bitxor(self, rhs: HashSet<usize>) -> Set {
+    fn bitxor(self, rhs: Set<T, PAGE>) -> Set<T, PAGE> {
         self.symmetric_difference(&rhs)
     }
 }
@@ -771,31 +382,82 @@ impl std::ops::BitXor<HashSet<usize>> for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let mut set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(3..8);
+/// let mut set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(3..8);
 /// set1 ^= &set2;
-/// assert_eq!(set1, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
-/// ```
-impl<'a> std::ops::BitXorAssign<&'a Set> for Set {
-    fn bitxor_assign(&mut self, rhs: &'a Set) {
-        *self = self.symmetric_difference(rhs);
-    }
-}
-
-/// Computes the symmetric difference between a reference to `Set` and a reference to `HashSet<usize>` and assigns the result to the left operand.
-///
-/// # Examples
-///
-/// ```
-/// use fastset::Set;
-/// use std::collections::HashSet;
-/// let mut set = Set::from_iter(0..5);
-/// let hashset: HashSet<usize> = (3..8).collect();
-/// set ^= &hashset;
-/// assert_eq!(set, Set::from_iter(0..3).union(&Set::from_iter(5..8)));
-/// ```
-impl<'a> std::ops::BitXorAssign<&'a HashSet<usize>> for Set {
-    fn bitxor_assign(&mut self, rhs: &'a HashSet<usize>) {
-        *self = self.symmetric_difference(rhs);
-    }
-}
+/// assert_eq!(set1, Set::from_iter(0..3) | &Set::from_iter(5..8));
+/// ```
+impl<'a, T: Element, const PAGE: usize> std::ops::BitXorAssign<&'a Set<T, PAGE>> for Set<T, PAGE> {
+    fn bitxor_assign(&mut self, rhs: &'a Set<T, PAGE>) {
+        self.symmetric_difference_with(rhs);
+    }
+}
+
+/// Implements the four set operators and their assigning forms between `Set`
+/// and another [`SetOps`] implementor, taken by reference or by value. The
+/// assigning forms update the Set in place.
+macro_rules! impl_set_operators {
+    ($(#[$attr:meta])* [$($generics:tt)*] $rhs:ty) => {
+        impl_set_operators!(@op $(#[$attr])* [$($generics)*] $rhs, BitOr, bitor, BitOrAssign, bitor_assign, union, union_with);
+        impl_set_operators!(@op $(#[$attr])* [$($generics)*] $rhs, BitAnd, bitand, BitAndAssign, bitand_assign, intersection, intersect_with);
+        impl_set_operators!(@op $(#[$attr])* [$($generics)*] $rhs, Sub, sub, SubAssign, sub_assign, difference, difference_with);
+        impl_set_operators!(@op $(#[$attr])* [$($generics)*] $rhs, BitXor, bitxor, BitXorAssign, bitxor_assign, symmetric_difference, symmetric_difference_with);
+    };
+    (@op $(#[$attr:meta])* [$($generics:tt)*] $rhs:ty, $op:ident, $method:ident, $assign:ident, $assign_method:ident, $algebra:ident, $in_place:ident) => {
+        $(#[$attr])*
+        impl<'r, $($generics)* T: Element, const PAGE: usize> std::ops::$op<&'r $rhs> for &Set<T, PAGE> {
+            type Output = Set<T, PAGE>;
+
+            fn $method(self, rhs: &'r $rhs) -> Set<T, PAGE> {
+                self.$algebra(rhs)
+            }
+        }
+
+        $(#[$attr])*
+        impl<$($generics)* T: Element, const PAGE: usize> std::ops::$op<$rhs> for &Set<T, PAGE> {
+            type Output = Set<T, PAGE>;
+
+            fn $method(self, rhs: $rhs) -> Set<T, PAGE> {
+                self.$algebra(&rhs)
+            }
+        }
+
+        $(#[$attr])*
+        impl<'r, $($generics)* T: Element, const PAGE: usize> std::ops::$op<&'r $rhs> for Set<T, PAGE> {
+            type Output = Set<T, PAGE>;
+
+            fn $method(self, rhs: &'r $rhs) -> Set<T, PAGE> {
+                self.$algebra(rhs)
+            }
+        }
+
+        $(#[$attr])*
+        impl<$($generics)* T: Element, const PAGE: usize> std::ops::$op<$rhs> for Set<T, PAGE> {
+            type Output = Set<T, PAGE>;
+
+            fn $method(self, rhs: $rhs) -> Set<T, PAGE> {
+                self.$algebra(&rhs)
+            }
+        }
+
+        $(#[$attr])*
+        impl<'r, $($generics)* T: Element, const PAGE: usize> std::ops::$assign<&'r $rhs> for Set<T, PAGE> {
+            fn $assign_method(&mut self, rhs: &'r $rhs) {
+                self.$in_place(rhs);
+            }
+        }
+
+        $(#[$attr])*
+        impl<$($generics)* T: Element, const PAGE: usize> std::ops::$assign<$rhs> for Set<T, PAGE> {
+            fn $assign_method(&mut self, rhs: $rhs) {
+                self.$in_place(&rhs);
+            }
+        }
+    };
+}
+
+impl_set_operators!([S: BuildHasher,] HashSet<T, S>);
+impl_set_operators!([] BTreeSet<T>);
+impl_set_operators!([] std::ops::Range<T>);
+impl_set_operators!(['s,] SortedSlice<'s, T>);
+impl_set_operators!(#[cfg(feature = "hashbrown")] [S: BuildHasher,] hashbrown::HashSet<T, S>);
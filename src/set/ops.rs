@@ -1,8 +1,24 @@
 use super::core::Set;
-use std::collections::HashSet;
+use super::element::Element;
+use super::indicator::Indicator;
+use std::collections::{btree_set, hash_set, BTreeSet, HashSet};
+use std::hash::BuildHasher;
+use std::iter::{Copied, Map};
 
 /// Provides operations common to sets, such as containment check, iteration, and finding the maximum value.
-pub trait SetOps {
+///
+/// The element type `T` defaults to `usize`, so `SetOps` on its own means `SetOps<usize>`.
+///
+/// `SetOps` is implemented for `Set`, `std::collections::HashSet`, `BTreeSet`,
+/// sorted slices wrapped in [`SortedSlice`], ranges of values and, with the
+/// `hashbrown` feature, `hashbrown::HashSet`. The set algebra methods of `Set`,
+/// such as [`Set::is_subset`] and [`Set::union`], and its operators accept any of them.
+pub trait SetOps<T: Element = usize> {
+    /// The iterator returned by [`iter`](SetOps::iter).
+    type Iter<'a>: Iterator<Item = T>
+    where
+        Self: 'a;
+
     /// Checks whether the set contains the specified value.
     ///
     /// # Arguments
@@ -22,97 +38,55 @@ pub trait SetOps {
     /// assert!(set.contains(&42));
     /// assert!(!set.contains(&100));
     /// ```
-    fn contains(&self, value: &usize) -> bool;
+    fn contains(&self, value: &T) -> bool;
 
     /// Returns an iterator over the elements of the set.
     ///
     /// # Returns
     ///
-    /// A boxed iterator yielding references to the elements of the set.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use fastset::{Set, SetOps, set};
-    ///
-    /// let set = set![1, 2, 3, 4, 5];
-    /// for item in set.iter() {
-    ///     println!("{}", *item);
-    /// }
-    /// ```
-    fn iter(&self) -> Box<dyn Iterator<Item = &usize> + '_>;
-
-    /// Returns the maximum value in the set, if any.
-    ///
-    /// # Returns
-    ///
-    /// The maximum value in the set, or `None` if the set is empty.
+    /// An iterator yielding the elements of the set by value.
     ///
     /// # Examples
     ///
     /// ```
-    /// use fastset::{Set, insert};
+    /// use fastset::SetOps;
+    /// use std::collections::BTreeSet;
     ///
-    /// let mut set = Set::with_max(2);
-    /// insert!(set, 2, 42);
-    /// assert_eq!(set.max(), Some(42));
+    /// let set = BTreeSet::from([3, 1, 2]);
+    /// assert_eq!(SetOps::iter(&set).collect::<Vec<usize>>(), vec![1, 2, 3]);
     /// ```
-    fn max(&self) -> Option<usize>;
-}
+    fn iter(&self) -> Self::Iter<'_>;
 
-impl SetOps for Set {
-    /// Checks whether the set contains the specified value.
-    ///
-    /// # Arguments
-    ///
-    /// * `value` - The value to check for containment.
-    ///
-    /// # Returns
-    ///
-    /// `true` if the set contains the value, otherwise `false`.
+    /// Returns the number of elements in the set.
     ///
     /// # Examples
     ///
     /// ```
-    /// use fastset::Set;
     /// use fastset::SetOps;
     ///
-    /// let mut set = Set::with_max(1);
-    /// set.insert(42);
-    /// assert!(set.contains(&42));
-    /// assert!(!set.contains(&100));
+    /// assert_eq!(SetOps::<usize>::len(&(10..20)), 10);
     /// ```
-    fn contains(&self, value: &usize) -> bool {
-        self.contains(value)
-    }
+    fn len(&self) -> usize;
 
-    /// Returns an iterator over the elements of the set.
-    ///
-    /// # Returns
-    ///
-    /// A boxed iterator yielding references to the elements of the set.
+    /// Returns `true` if the set has no elements.
     ///
     /// # Examples
     ///
     /// ```
-    /// use fastset::Set;
     /// use fastset::SetOps;
     ///
-    /// let mut set = Set::with_max(100);
-    /// set.insert(42);
-    /// set.insert(100);
-    ///
-    /// let mut iter = set.iter();
-    /// assert_eq!(iter.next(), Some(&42));
-    /// assert_eq!(iter.next(), Some(&100));
-    /// assert_eq!(iter.next(), None);
+    /// assert!(SetOps::<usize>::is_empty(&(5..5)));
     /// ```
-    fn iter(&self) -> Box<dyn Iterator<Item = &usize> + '_> {
-        Box::new(self.elements.iter())
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     /// Returns the maximum value in the set, if any.
     ///
+    /// The default scans [`iter`](SetOps::iter); ordered implementors answer
+    /// directly.
+    ///
     /// # Returns
     ///
     /// The maximum value in the set, or `None` if the set is empty.
@@ -120,20 +94,72 @@ impl SetOps for Set {
     /// # Examples
     ///
     /// ```
-    /// use fastset::{Set, SetOps};
-    ///
-    /// let mut set = Set::with_max(100);
-    /// set.insert(42);
-    /// set.insert(100);
+    /// use fastset::{Set, insert};
     ///
-    /// assert_eq!(set.max(), Some(100));
+    /// let mut set: Set = Set::with_max(2);
+    /// insert!(set, 2, 42);
+    /// assert_eq!(set.max(), Some(42));
     /// ```
-    fn max(&self) -> Option<usize> {
-        self.current_max
+    fn max(&self) -> Option<T> {
+        self.iter().max()
+    }
+
+    /// Returns the packed indicator of the underlying `Set`, if this is one.
+    ///
+    /// Set algebra uses this to switch to word-level operations when both
+    /// operands are `Set`s, whatever their page size. Other implementors
+    /// should keep the default.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn as_indicator(&self) -> Option<&Indicator> {
+        None
     }
 }
 
-impl SetOps for HashSet<usize> {
+/// A slice known to be sorted in strictly ascending order, usable as a set.
+///
+/// Membership is a binary search and the maximum is the last element.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{Set, SetOps, SortedSlice};
+///
+/// let ids = [2usize, 3, 5, 7, 11];
+/// let primes = SortedSlice::new(&ids).unwrap();
+/// assert!(primes.contains(&7));
+/// assert_eq!(SetOps::max(&primes), Some(11));
+///
+/// let small: Set = Set::from_iter(0..8);
+/// assert_eq!(&small & primes, Set::from(vec![2, 3, 5, 7]));
+/// assert!(SortedSlice::new(&[3usize, 1]).is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SortedSlice<'a, T>(&'a [T]);
+
+impl<'a, T: Element> SortedSlice<'a, T> {
+    /// Wraps `slice`, or returns `None` if it is not sorted in strictly
+    /// ascending order.
+    pub fn new(slice: &'a [T]) -> Option<Self> {
+        slice
+            .windows(2)
+            .all(|pair| pair[0] < pair[1])
+            .then_some(SortedSlice(slice))
+    }
+
+    /// Returns the wrapped slice.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &'a [T] {
+        self.0
+    }
+}
+
+impl<T: Element, const PAGE: usize> SetOps<T> for Set<T, PAGE> {
+    type Iter<'a>
+        = Copied<std::slice::Iter<'a, T>>
+    where
+        Self: 'a;
+
     /// Checks whether the set contains the specified value.
     ///
     /// # Arguments
@@ -147,41 +173,42 @@ impl SetOps for HashSet<usize> {
     /// # Examples
     ///
     /// ```
-    /// use std::collections::HashSet;
+    /// use fastset::Set;
     /// use fastset::SetOps;
     ///
-    /// let mut set = HashSet::new();
+    /// let mut set: Set = Set::with_max(1);
     /// set.insert(42);
     /// assert!(set.contains(&42));
     /// assert!(!set.contains(&100));
     /// ```
-    fn contains(&self, value: &usize) -> bool {
-        HashSet::contains(self, value)
+    fn contains(&self, value: &T) -> bool {
+        self.contains(value)
     }
 
-    /// Returns an iterator over the elements of the set.
-    ///
-    /// # Returns
-    ///
-    /// A boxed iterator yielding references to the elements of the set.
+    /// Returns an iterator over the elements of the set, in storage order.
     ///
     /// # Examples
     ///
     /// ```
-    /// use std::collections::HashSet;
+    /// use fastset::Set;
     /// use fastset::SetOps;
     ///
-    /// let mut set = HashSet::<usize>::new();
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(42);
     /// set.insert(100);
-    /// let mut results = Vec::new();
-    /// set.iter().for_each(|&elem| results.push(elem));
-    /// let expected = HashSet::from([42, 100]);
-    /// let results_set: HashSet<usize> = results.into_iter().collect();
-    /// assert_eq!(results_set, expected);
+    ///
+    /// let mut iter = SetOps::iter(&set);
+    /// assert_eq!(iter.next(), Some(42));
+    /// assert_eq!(iter.next(), Some(100));
+    /// assert_eq!(iter.next(), None);
     /// ```
-    fn iter(&self) -> Box<dyn Iterator<Item = &usize> + '_> {
-        Box::new(self.iter())
+    fn iter(&self) -> Self::Iter<'_> {
+        self.elements.iter().copied()
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.elements.len()
     }
 
     /// Returns the maximum value in the set, if any.
@@ -193,21 +220,191 @@ impl SetOps for HashSet<usize> {
     /// # Examples
     ///
     /// ```
-    /// use std::collections::HashSet;
-    /// use fastset::SetOps;
+    /// use fastset::{Set, SetOps};
     ///
-    /// let mut set = HashSet::new();
+    /// let mut set: Set = Set::with_max(100);
     /// set.insert(42);
     /// set.insert(100);
     ///
     /// assert_eq!(set.max(), Some(100));
     /// ```
-    fn max(&self) -> Option<usize> {
-        self.iter().max().copied()
+    fn max(&self) -> Option<T> {
+        self.current_max
+    }
+
+    #[inline(always)]
+    fn as_indicator(&self) -> Option<&Indicator> {
+        Some(&self.indicator)
     }
 }
 
-impl Set {
+/// `max` scans every element, as a hash set keeps no order.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use fastset::SetOps;
+///
+/// let mut set: HashSet<usize> = HashSet::new();
+/// set.insert(42);
+/// set.insert(100);
+///
+/// assert!(SetOps::contains(&set, &42));
+/// assert_eq!(SetOps::max(&set), Some(100));
+/// ```
+impl<T: Element, S: BuildHasher> SetOps<T> for HashSet<T, S> {
+    type Iter<'a>
+        = Copied<hash_set::Iter<'a, T>>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn contains(&self, value: &T) -> bool {
+        HashSet::contains(self, value)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        HashSet::iter(self).copied()
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+}
+
+/// Membership and the maximum take O(log n), and iteration is ascending.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use fastset::{Set, SetOps};
+///
+/// let tree = BTreeSet::from([3, 40, 7]);
+/// assert_eq!(SetOps::max(&tree), Some(40));
+///
+/// let set: Set = Set::from_iter(0..10);
+/// assert!(!set.is_superset(&tree));
+/// assert_eq!(&set | &tree, Set::from_iter((0..10).chain([40])));
+/// ```
+impl<T: Element> SetOps<T> for BTreeSet<T> {
+    type Iter<'a>
+        = Copied<btree_set::Iter<'a, T>>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn contains(&self, value: &T) -> bool {
+        BTreeSet::contains(self, value)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeSet::iter(self).copied()
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
+
+    #[inline(always)]
+    fn max(&self) -> Option<T> {
+        self.last().copied()
+    }
+}
+
+/// Membership takes O(log n) by binary search, and iteration is ascending.
+impl<T: Element> SetOps<T> for SortedSlice<'_, T> {
+    type Iter<'a>
+        = Copied<std::slice::Iter<'a, T>>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn contains(&self, value: &T) -> bool {
+        self.0.binary_search(value).is_ok()
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.0.iter().copied()
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn max(&self) -> Option<T> {
+        self.0.last().copied()
+    }
+}
+
+/// A range holds every value from its start up to, and excluding, its end,
+/// so every query is O(1).
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{Set, SetOps};
+///
+/// let set: Set = Set::from_iter([2, 5, 9]);
+/// assert!(set.is_subset(&(0..10)));
+/// assert_eq!(&set - (4..6), Set::from_iter([2, 9]));
+/// assert_eq!(SetOps::max(&(0..10usize)), Some(9));
+/// ```
+impl<T: Element> SetOps<T> for std::ops::Range<T> {
+    type Iter<'a>
+        = Map<std::ops::Range<usize>, fn(usize) -> T>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn contains(&self, value: &T) -> bool {
+        std::ops::Range::contains(self, value)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        (self.start.to_index()..self.end.to_index()).map(T::from_index as fn(usize) -> T)
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.end.to_index().saturating_sub(self.start.to_index())
+    }
+
+    #[inline(always)]
+    fn max(&self) -> Option<T> {
+        (self.start < self.end).then(|| T::from_index(self.end.to_index() - 1))
+    }
+}
+
+/// `max` scans every element, as a hash set keeps no order.
+#[cfg(feature = "hashbrown")]
+impl<T: Element, S: BuildHasher> SetOps<T> for hashbrown::HashSet<T, S> {
+    type Iter<'a>
+        = Copied<hashbrown::hash_set::Iter<'a, T>>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn contains(&self, value: &T) -> bool {
+        hashbrown::HashSet::contains(self, value)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        hashbrown::HashSet::iter(self).copied()
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        hashbrown::HashSet::len(self)
+    }
+}
+
+impl<T: Element, const PAGE: usize> Set<T, PAGE> {
     /// Checks if the set is a subset of another set.
     ///
     /// # Arguments
@@ -223,14 +420,17 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let set1 = Set::from_iter(1..=5);
-    /// let set2 = Set::from_iter(1..=10);
+    /// let set1: Set = Set::from_iter(1..=5);
+    /// let set2: Set = Set::from_iter(1..=10);
     ///
     /// assert!(set1.is_subset(&set2));
     /// ```
     #[inline(always)]
-    pub fn is_subset<T: SetOps>(&self, other: &T) -> bool {
-        self.elements.iter().all(|&value| other.contains(&value))
+    pub fn is_subset<S: SetOps<T>>(&self, other: &S) -> bool {
+        if let Some(other) = other.as_indicator() {
+            return self.indicator.all_words(other, |a, b| a & !b == 0);
+        }
+        self.elements.iter().all(|value| other.contains(value))
     }
 
     /// Checks if the set is a superset of another set.
@@ -248,14 +448,17 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let set1 = Set::from_iter(1..=10);
-    /// let set2 = Set::from_iter(1..=5);
+    /// let set1: Set = Set::from_iter(1..=10);
+    /// let set2: Set = Set::from_iter(1..=5);
     ///
     /// assert!(set1.is_superset(&set2));
     /// ```
     #[inline(always)]
-    pub fn is_superset<T: SetOps>(&self, other: &T) -> bool {
-        other.iter().all(|value| self.contains(value))
+    pub fn is_superset<S: SetOps<T>>(&self, other: &S) -> bool {
+        if let Some(other) = other.as_indicator() {
+            return other.all_words(&self.indicator, |a, b| a & !b == 0);
+        }
+        other.iter().all(|value| self.contains(&value))
     }
 
     /// Checks if the set has no elements in common with another set.
@@ -273,14 +476,17 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let set1 = Set::from_iter(1..=5);
-    /// let set2 = Set::from_iter(6..=10);
+    /// let set1: Set = Set::from_iter(1..=5);
+    /// let set2: Set = Set::from_iter(6..=10);
     ///
     /// assert!(set1.is_disjoint(&set2));
     /// ```
     #[inline(always)]
-    pub fn is_disjoint<T: SetOps>(&self, other: &T) -> bool {
-        !self.iter().any(|&value| other.contains(&value))
+    pub fn is_disjoint<S: SetOps<T>>(&self, other: &S) -> bool {
+        if let Some(other) = other.as_indicator() {
+            return self.indicator.all_words(other, |a, b| a & b == 0);
+        }
+        !self.elements.iter().any(|value| other.contains(value))
     }
 
     /// Returns the union of the set with another set.
@@ -293,25 +499,37 @@ impl Set {
     ///
     /// Returns a new `Set` containing all elements present in either set.
     ///
+    /// # Panics
+    ///
+    /// Panics if `other` holds a value above this Set's [ceiling](Set::ceiling),
+    /// which the result keeps.
+    ///
     /// # Examples
     ///
     /// ```
     /// use fastset::Set;
     ///
-    /// let set1 = Set::from_iter(1..=5);
-    /// let set2 = Set::from_iter(4..=8);
+    /// let set1: Set = Set::from_iter(1..=5);
+    /// let set2: Set = Set::from_iter(4..=8);
     ///
     /// let union = set1.union(&set2);
     ///
     /// assert_eq!(union.len(), 8);
     /// ```
     #[inline(always)]
-    pub fn union<T: SetOps>(&self, other: &T) -> Self {
-        let max_other = other.max().unwrap_or(0);
-        let mut result = Set::with_max(std::cmp::max(self.max, max_other));
-        self.iter().chain(other.iter()).for_each(|&value| {
-            result.insert(value);
-        });
+    pub fn union<S: SetOps<T>>(&self, other: &S) -> Self {
+        if let Some(other) = other.as_indicator() {
+            return self.combine(other, |a, b| a | b);
+        }
+        let max_other = other.max().unwrap_or_default();
+        let mut result = self.empty_like(std::cmp::max(self.max, max_other));
+        self.elements
+            .iter()
+            .copied()
+            .chain(other.iter())
+            .for_each(|value| {
+                result.insert(value);
+            });
         result
     }
 
@@ -330,17 +548,20 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let set1 = Set::from_iter(1..=5);
-    /// let set2 = Set::from_iter(4..=8);
+    /// let set1: Set = Set::from_iter(1..=5);
+    /// let set2: Set = Set::from_iter(4..=8);
     ///
     /// let intersection = set1.intersection(&set2);
     ///
     /// assert_eq!(intersection.len(), 2);
     /// ```
     #[inline(always)]
-    pub fn intersection<T: SetOps>(&self, other: &T) -> Self {
-        let max_other = other.max().unwrap_or(0);
-        let mut result = Set::with_max(std::cmp::max(self.max, max_other));
+    pub fn intersection<S: SetOps<T>>(&self, other: &S) -> Self {
+        if let Some(other) = other.as_indicator() {
+            return self.combine(other, |a, b| a & b);
+        }
+        let max_other = other.max().unwrap_or_default();
+        let mut result = self.empty_like(std::cmp::max(self.max, max_other));
         self.elements
             .iter()
             .filter(|&&value| other.contains(&value))
@@ -365,17 +586,20 @@ impl Set {
     /// ```
     /// use fastset::Set;
     ///
-    /// let set1 = Set::from_iter(1..=5);
-    /// let set2 = Set::from_iter(4..=8);
+    /// let set1: Set = Set::from_iter(1..=5);
+    /// let set2: Set = Set::from_iter(4..=8);
     ///
     /// let difference = set1.difference(&set2);
     ///
     /// assert_eq!(difference.len(), 3);
     /// ```
     #[inline(always)]
-    pub fn difference<T: SetOps>(&self, other: &T) -> Self {
-        let max_other = other.max().unwrap_or(0);
-        let mut result = Set::with_max(std::cmp::max(self.max, max_other));
+    pub fn difference<S: SetOps<T>>(&self, other: &S) -> Self {
+        if let Some(other) = other.as_indicator() {
+            return self.combine(other, |a, b| a & !b);
+        }
+        let max_other = other.max().unwrap_or_default();
+        let mut result = self.empty_like(std::cmp::max(self.max, max_other));
         self.iter()
             .filter(|&&value| !other.contains(&value))
             .for_each(|&value| {
@@ -394,28 +618,127 @@ impl Set {
     ///
     /// Returns a new `Set` containing elements present in either set but not in both.
     ///
+    /// # Panics
+    ///
+    /// Panics if `other` holds a value above this Set's [ceiling](Set::ceiling),
+    /// which the result keeps.
+    ///
     /// # Examples
     ///
     /// ```
     /// use fastset::{Set, SetOps};
     ///
-    /// let set1 = Set::from_iter(1..=5);
-    /// let set2 = Set::from_iter(4..=8);
+    /// let set1: Set = Set::from_iter(1..=5);
+    /// let set2: Set = Set::from_iter(4..=8);
     ///
     /// let symmetric_difference = set1.symmetric_difference(&set2);
     ///
     /// assert_eq!(symmetric_difference.len(), 6);
     /// ```
     #[inline(always)]
-    pub fn symmetric_difference<T: SetOps>(&self, other: &T) -> Self {
-        let max_other = other.max().unwrap_or(0);
-        let mut result = Set::with_max(std::cmp::max(self.max, max_other));
-        self.iter()
-            .filter(|&&value| !other.contains(&value))
-            .chain(other.iter().filter(|&value| !self.contains(value)))
-            .for_each(|&value| {
+    pub fn symmetric_difference<S: SetOps<T>>(&self, other: &S) -> Self {
+        if let Some(other) = other.as_indicator() {
+            return self.combine(other, |a, b| a ^ b);
+        }
+        let max_other = other.max().unwrap_or_default();
+        let mut result = self.empty_like(std::cmp::max(self.max, max_other));
+        self.elements
+            .iter()
+            .copied()
+            .filter(|value| !other.contains(value))
+            .chain(other.iter().filter(|value| !self.contains(value)))
+            .for_each(|value| {
                 result.insert(value);
             });
         result
     }
+
+    /// Creates an empty Set able to hold values up to `max`, with the same
+    /// ceiling, container mode and rank index setting as this one.
+    fn empty_like(&self, max: T) -> Self {
+        let mut set = Set::with_max(max.min(self.ceiling));
+        set.ceiling = self.ceiling;
+        if self.has_hybrid_containers() {
+            set.enable_hybrid_containers();
+        }
+        if self.has_rank_index() {
+            set.enable_rank_index();
+        }
+        set
+    }
+
+    /// Combines two Sets word by word, 64 slots at a time.
+    ///
+    /// The result spans the larger of the two universes and its elements are
+    /// rebuilt in ascending order from the combined indicator.
+    #[inline(always)]
+    fn combine<F>(&self, other: &Indicator, op: F) -> Self
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        let len = std::cmp::max(self.indicator.len(), other.len());
+        let mut result = Set::from_indicator(self.indicator.combine(other, len, op));
+        if let Some(max) = result.current_max {
+            Self::check_ceiling(max, self.ceiling);
+        }
+        if result.max > self.ceiling {
+            result.indicator.resize(self.ceiling.to_index() + 1);
+            result.max = self.ceiling;
+        }
+        result.ceiling = self.ceiling;
+        result
+    }
+
+    /// Adds every value of `other` to the Set, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` holds a value above the Set's [ceiling](Set::ceiling).
+    pub(super) fn union_with<S: SetOps<T>>(&mut self, other: &S) {
+        for value in other.iter() {
+            self.insert(value);
+        }
+    }
+
+    /// Removes every value not in `other` from the Set, in place.
+    pub(super) fn intersect_with<S: SetOps<T>>(&mut self, other: &S) {
+        // Walking backwards, a removal only swaps in an element already kept
+        for i in (0..self.elements.len()).rev() {
+            let value = self.elements[i];
+            if !other.contains(&value) {
+                self.remove(&value);
+            }
+        }
+    }
+
+    /// Removes every value of `other` from the Set, in place, walking
+    /// whichever of the two is shorter.
+    pub(super) fn difference_with<S: SetOps<T>>(&mut self, other: &S) {
+        if other.len() < self.len() {
+            for value in other.iter() {
+                self.remove(&value);
+            }
+            return;
+        }
+        for i in (0..self.elements.len()).rev() {
+            let value = self.elements[i];
+            if other.contains(&value) {
+                self.remove(&value);
+            }
+        }
+    }
+
+    /// Toggles every value of `other` in the Set, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` holds a value above the Set's [ceiling](Set::ceiling)
+    /// that the Set does not.
+    pub(super) fn symmetric_difference_with<S: SetOps<T>>(&mut self, other: &S) {
+        for value in other.iter() {
+            if !self.remove(&value) {
+                self.insert(value);
+            }
+        }
+    }
 }
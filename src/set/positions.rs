@@ -0,0 +1,149 @@
+use super::element::Element;
+use super::sparse_vec::SparseVec;
+use std::collections::TryReserveError;
+
+/// Number of pages grouped under each directory.
+const DIRECTORY_PAGES: usize = 1024;
+
+/// Lazily allocated map from values to their index in a set's element list.
+///
+/// Values are grouped into pages of `PAGE` positions, and pages into
+/// directories of 1024 pages. A directory and its pages are only allocated
+/// once a value lands in them, and a directory is freed again when its last
+/// value leaves. Both the directories and the pages within each are kept
+/// sparse, so an unpopulated stretch of the universe costs nothing at all
+/// and a scattered value costs one page rather than a full directory.
+#[derive(Clone)]
+pub(super) struct Positions<T, const PAGE: usize> {
+    directories: SparseVec<Directory<T>>,
+}
+
+/// The allocated pages of one directory, and how many positions they
+/// currently hold.
+#[derive(Clone)]
+struct Directory<T> {
+    pages: SparseVec<Box<[T]>>,
+    live: usize,
+}
+
+impl<T: Element, const PAGE: usize> Positions<T, PAGE> {
+    const PAGE_SHIFT: u32 = PAGE.trailing_zeros();
+
+    /// Creates an empty table.
+    #[inline(always)]
+    pub(super) fn new() -> Self {
+        Self {
+            directories: SparseVec::new(),
+        }
+    }
+
+    /// Returns `true` if no directory is allocated.
+    #[cfg(test)]
+    pub(super) fn is_empty(&self) -> bool {
+        self.directories.is_empty()
+    }
+
+    /// Returns the position recorded for `value`, if its page is allocated.
+    #[inline(always)]
+    pub(super) fn get(&self, value: usize) -> Option<T> {
+        let (d, p, i) = Self::locate(value);
+        Some(self.directories.get(d)?.pages.get(p)?[i])
+    }
+
+    /// Records the position of a value that has none yet.
+    #[inline(always)]
+    pub(super) fn insert(&mut self, value: usize, position: T) {
+        let (d, p, i) = Self::locate(value);
+        let directory = self.directories.get_or_insert_with(d, || Directory {
+            pages: SparseVec::new(),
+            live: 0,
+        });
+        directory.live += 1;
+        directory
+            .pages
+            .get_or_insert_with(p, || vec![T::default(); PAGE].into_boxed_slice())[i] = position;
+    }
+
+    /// Allocates the directory and page for `value` up front, failing instead
+    /// of aborting, so that a following `insert` does not allocate.
+    pub(super) fn try_reserve(&mut self, value: usize) -> Result<(), TryReserveError> {
+        let (d, p, _) = Self::locate(value);
+        if self.directories.get(d).is_none() {
+            self.directories.try_reserve(1)?;
+            self.directories.get_or_insert_with(d, || Directory {
+                pages: SparseVec::new(),
+                live: 0,
+            });
+        }
+        let directory = self
+            .directories
+            .get_mut(d)
+            .expect("directory was just allocated");
+        if directory.pages.get(p).is_none() {
+            let mut page = Vec::new();
+            page.try_reserve_exact(PAGE)?;
+            page.resize(PAGE, T::default());
+            directory.pages.try_reserve(1)?;
+            directory
+                .pages
+                .get_or_insert_with(p, || page.into_boxed_slice());
+        }
+        Ok(())
+    }
+
+    /// Overwrites the position of a value that already has one.
+    #[inline(always)]
+    pub(super) fn update(&mut self, value: usize, position: T) {
+        *self.slot_mut(value) = position;
+    }
+
+    /// Removes and returns the position of a value that has one.
+    ///
+    /// The value's directory is freed if this was its last position.
+    #[inline(always)]
+    pub(super) fn remove(&mut self, value: usize) -> T {
+        let position = std::mem::take(self.slot_mut(value));
+        let d = Self::locate(value).0;
+        let directory = self.directories.get_mut(d).expect("value has a position");
+        directory.live -= 1;
+        if directory.live == 0 {
+            self.directories.remove(d);
+        }
+        position
+    }
+
+    /// Frees every directory.
+    #[inline(always)]
+    pub(super) fn clear(&mut self) {
+        self.directories.clear();
+    }
+
+    /// Shrinks the directory list to fit the allocated directories.
+    #[inline(always)]
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.directories.shrink_to_fit();
+    }
+
+    #[inline(always)]
+    fn slot_mut(&mut self, value: usize) -> &mut T {
+        let (d, p, i) = Self::locate(value);
+        let page = self
+            .directories
+            .get_mut(d)
+            .and_then(|directory| directory.pages.get_mut(p))
+            .expect("value has a position");
+        &mut page[i]
+    }
+
+    /// Splits a value into its directory, page within the directory, and
+    /// slot within the page.
+    #[inline(always)]
+    fn locate(value: usize) -> (usize, usize, usize) {
+        let page = value >> Self::PAGE_SHIFT;
+        (
+            page / DIRECTORY_PAGES,
+            page % DIRECTORY_PAGES,
+            value & (PAGE - 1),
+        )
+    }
+}
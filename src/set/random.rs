@@ -0,0 +1,328 @@
+use super::core::Set;
+use super::element::Element;
+use rand_core::RngCore;
+use std::collections::HashSet;
+use std::ops::RangeBounds;
+
+/// Number of rejection draws [`Set::random_where`] makes before falling back
+/// to a scan of the element list.
+const WHERE_TRIES: usize = 64;
+
+/// Largest sample for which Floyd's algorithm in [`Set::sample_into`] finds
+/// repeated picks by scanning the output rather than a scratch hash set.
+const SCAN_SAMPLE: usize = 64;
+
+/// Draws an index uniformly from `0..n`, which must be non-zero.
+///
+/// Uses Lemire's multiply-shift reduction with rejection, so every index is
+/// equally likely whatever the generator and `n`, at the cost of one 128-bit
+/// multiply per draw and a rare retry.
+#[inline(always)]
+pub(super) fn uniform_index<R: RngCore + ?Sized>(rng: &mut R, n: usize) -> usize {
+    debug_assert!(n > 0);
+    let n = n as u64;
+    let mut product = u128::from(rng.next_u64()) * u128::from(n);
+    if (product as u64) < n {
+        let threshold = n.wrapping_neg() % n;
+        while (product as u64) < threshold {
+            product = u128::from(rng.next_u64()) * u128::from(n);
+        }
+    }
+    (product >> 64) as usize
+}
+
+/// Draws a float uniformly from `[0, 1)`, from the top 53 bits of one draw.
+#[inline(always)]
+pub(super) fn unit_interval<R: RngCore + ?Sized>(rng: &mut R) -> f64 {
+    (rng.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+/// Counts the successes among `n` fair coin flips, 64 flips per draw.
+pub(super) fn halve_count<R: RngCore + ?Sized>(rng: &mut R, mut n: usize) -> usize {
+    let mut heads = 0;
+    while n > 0 {
+        let flips = n.min(64);
+        heads += (rng.next_u64() & (u64::MAX >> (64 - flips))).count_ones() as usize;
+        n -= flips;
+    }
+    heads
+}
+
+/// Counts the successes among `n` trials that each succeed with probability
+/// 2^-`k`.
+///
+/// Halves with packed coin flips while that is cheaper, then skips between
+/// successes with geometric gaps, so the cost is O(min(n / 64, n · 2^-k) + 1)
+/// draws.
+pub(super) fn binomial_pow2<R: RngCore + ?Sized>(rng: &mut R, mut n: usize, mut k: u32) -> usize {
+    while k > 0 && k < 6 && n > 0 {
+        n = halve_count(rng, n);
+        k -= 1;
+    }
+    if k == 0 || n == 0 {
+        return n;
+    }
+    let p = (-f64::from(k)).exp2();
+    let scale = (-p).ln_1p();
+    let mut successes = 0;
+    let mut trial = 0;
+    loop {
+        // Failures before the next success, by inversion of the geometric law
+        let gap = ((1.0 - unit_interval(rng)).ln() / scale).floor();
+        if gap >= (n - trial) as f64 {
+            return successes;
+        }
+        trial += gap as usize + 1;
+        successes += 1;
+    }
+}
+
+impl<T: Element, const PAGE: usize> Set<T, PAGE> {
+    /// Returns `k` distinct elements drawn uniformly without replacement, or
+    /// every element if the Set holds fewer than `k`.
+    ///
+    /// Every subset of size `k` is equally likely. The order of the returned
+    /// elements is unspecified. See [`sample_into`](Self::sample_into) for
+    /// the algorithm and costs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let set: Set = Set::from_iter(0..100);
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    ///
+    /// let batch = set.sample(10, &mut rng);
+    /// assert_eq!(batch.len(), 10);
+    /// assert!(batch.iter().all(|value| set.contains(value)));
+    /// assert_eq!(set.sample(1_000, &mut rng).len(), 100);
+    /// ```
+    pub fn sample<R: RngCore + ?Sized>(&self, k: usize, rng: &mut R) -> Vec<T> {
+        let mut out = Vec::with_capacity(k.min(self.elements.len()));
+        self.sample_into(&mut out, k, rng);
+        out
+    }
+
+    /// Replaces the contents of `out` with `k` distinct elements drawn
+    /// uniformly without replacement, or every element if the Set holds
+    /// fewer than `k`.
+    ///
+    /// `out` keeps its capacity, so drawing batches of the same size into
+    /// the same vector allocates nothing for the output after the first call.
+    ///
+    /// Both strategies draw exactly `k` indices, without rejection:
+    ///
+    /// - when `k` is at least half the Set's length, `out` is filled with
+    ///   the elements and a partial Fisher–Yates shuffle moves `k` of them
+    ///   to the front, in O(len) time;
+    /// - otherwise Floyd's algorithm picks `k` indices. Up to 64 of them, a
+    ///   repeated pick is found by scanning `out`, in O(k²) time without
+    ///   allocating. Above that, the picks are tracked in a hash set of
+    ///   capacity `k` allocated for the call, in O(k) expected time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let set: Set = Set::from_iter(0..1_000);
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let mut batch = Vec::new();
+    ///
+    /// for _ in 0..3 {
+    ///     set.sample_into(&mut batch, 32, &mut rng);
+    ///     assert_eq!(batch.len(), 32);
+    /// }
+    /// ```
+    pub fn sample_into<R: RngCore + ?Sized>(&self, out: &mut Vec<T>, k: usize, rng: &mut R) {
+        let n = self.elements.len();
+        let k = k.min(n);
+        out.clear();
+        if k == 0 {
+            return;
+        }
+        if 2 * k >= n {
+            out.extend_from_slice(&self.elements);
+            for i in 0..k {
+                out.swap(i, i + uniform_index(rng, n - i));
+            }
+            out.truncate(k);
+        } else {
+            out.reserve(k);
+            // Elements are distinct, so a pick is repeated exactly when its
+            // element is already in `out`
+            let scan = k <= SCAN_SAMPLE;
+            let mut chosen = HashSet::with_capacity(if scan { 0 } else { k });
+            for j in n - k..n {
+                let t = uniform_index(rng, j + 1);
+                let repeated = match scan {
+                    true => out.contains(&self.elements[t]),
+                    false => !chosen.insert(t),
+                };
+                if repeated && !scan {
+                    chosen.insert(j);
+                }
+                out.push(self.elements[if repeated { j } else { t }]);
+            }
+        }
+    }
+
+    /// Returns a uniformly random member within `range`, or `None` if the
+    /// range holds no member.
+    ///
+    /// The members in range are counted with
+    /// [`range_cardinality`](Set::range_cardinality), one of them is picked by
+    /// its offset, and [`select`](Set::select) finds it from the rank of the
+    /// range's start. Nothing is copied out, and with the
+    /// [rank index](Set::enable_rank_index) enabled the draw takes
+    /// O(log(U / 512)) time after the count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let mut set: Set = Set::from_iter(0..10_000);
+    /// set.enable_rank_index();
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    ///
+    /// let value = set.random_in_range(2_000..3_000, &mut rng).unwrap();
+    /// assert!((2_000..3_000).contains(&value));
+    /// assert_eq!(set.random_in_range(20_000.., &mut rng), None);
+    /// ```
+    pub fn random_in_range<B, R>(&self, range: B, rng: &mut R) -> Option<T>
+    where
+        B: RangeBounds<T>,
+        R: RngCore + ?Sized,
+    {
+        let (start, end) = self.slot_bounds(range);
+        let count = self.indicator.count_range(start, end);
+        if count == 0 {
+            return None;
+        }
+        let before = self.indicator.rank(start);
+        self.indicator
+            .select(before + uniform_index(rng, count))
+            .map(T::from_index)
+    }
+
+    /// Returns a uniformly random member satisfying `predicate`, or `None` if
+    /// no member does.
+    ///
+    /// Up to 64 members are drawn and tested first, which is quick whenever
+    /// a fair share of the Set matches. If all of them are rejected, the
+    /// element list is scanned once, and a member is picked uniformly among
+    /// the matches, so a rare predicate costs O(len) rather than an unbounded
+    /// number of retries. `predicate` must give the same answer for a member
+    /// every time it is asked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let set: Set = Set::from_iter(0..1_000);
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    ///
+    /// let even = set.random_where(|value| value % 2 == 0, &mut rng).unwrap();
+    /// assert_eq!(even % 2, 0);
+    /// assert_eq!(set.random_where(|&value| value == 777, &mut rng), Some(777));
+    /// assert_eq!(set.random_where(|&value| value > 5_000, &mut rng), None);
+    /// ```
+    pub fn random_where<P, R>(&self, mut predicate: P, rng: &mut R) -> Option<T>
+    where
+        P: FnMut(&T) -> bool,
+        R: RngCore + ?Sized,
+    {
+        if self.elements.is_empty() {
+            return None;
+        }
+        for _ in 0..WHERE_TRIES {
+            let value = self.elements[uniform_index(rng, self.elements.len())];
+            if predicate(&value) {
+                return Some(value);
+            }
+        }
+        let matches = self
+            .elements
+            .iter()
+            .filter(|value| predicate(value))
+            .count();
+        if matches == 0 {
+            return None;
+        }
+        self.elements
+            .iter()
+            .filter(|value| predicate(value))
+            .nth(uniform_index(rng, matches))
+            .copied()
+    }
+}
+
+/// Adapts a `nanorand` generator to [`rand_core::RngCore`], so it can drive
+/// [`Set::random`](super::Set::random) and the other sampling methods.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{NanoRng, Set};
+/// use nanorand::WyRand;
+///
+/// let set: Set = Set::from(vec![5, 10, 15]);
+/// let mut rng = NanoRng(WyRand::new_seed(42));
+/// assert!(set.contains(&set.random(&mut rng).unwrap()));
+/// ```
+#[cfg(feature = "nanorand")]
+#[derive(Clone, Debug)]
+pub struct NanoRng<R = nanorand::WyRand>(pub R);
+
+#[cfg(feature = "nanorand")]
+impl NanoRng {
+    /// Creates a `WyRand`-backed generator seeded from the system's entropy.
+    pub fn new() -> Self {
+        NanoRng(nanorand::WyRand::new())
+    }
+}
+
+#[cfg(feature = "nanorand")]
+impl Default for NanoRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nanorand")]
+impl<R: nanorand::Rng<8>> From<R> for NanoRng<R> {
+    fn from(rng: R) -> Self {
+        NanoRng(rng)
+    }
+}
+
+#[cfg(feature = "nanorand")]
+impl<R: nanorand::Rng<8>> RngCore for NanoRng<R> {
+    #[inline(always)]
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.0.rand())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.0.rand();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
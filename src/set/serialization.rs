@@ -0,0 +1,146 @@
+use super::core::{default_ceiling, Set};
+use super::element::Element;
+use super::indicator::{Indicator, CHUNK_BITS};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serialized form of a `Set`.
+///
+/// Only the values are stored, either as the element list or, when that would
+/// be larger, as the chunked indicator. Positions and extremes are rebuilt on
+/// load.
+#[derive(Serialize)]
+#[serde(rename = "Set")]
+struct ReprRef<'a, T> {
+    max: T,
+    ceiling: T,
+    hybrid: bool,
+    values: ValuesRef<'a, T>,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "Values")]
+enum ValuesRef<'a, T> {
+    Elements(&'a [T]),
+    Bitmap(&'a Indicator),
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Set", bound = "T: Element")]
+struct Repr<T> {
+    max: T,
+    ceiling: T,
+    #[serde(default)]
+    hybrid: bool,
+    values: Values<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Values", bound = "T: Element")]
+enum Values<T> {
+    Elements(Vec<T>),
+    Bitmap(Indicator),
+}
+
+/// Serializes a `Set` as its values rather than its internal structures.
+///
+/// Sparse sets are written as their element list, in storage order. Dense sets,
+/// and sets with hybrid containers, are written as their allocated indicator
+/// chunks instead, and come back with their elements in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::Set;
+///
+/// let set: Set<u32> = Set::from(vec![3, 1, 2]);
+/// let json = serde_json::to_string(&set).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"max":3,"ceiling":4294967295,"hybrid":false,"values":{"Elements":[3,1,2]}}"#
+/// );
+///
+/// let back: Set<u32> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(back, set);
+/// ```
+impl<T: Element, const PAGE: usize> Serialize for Set<T, PAGE> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let list_bits = self.elements.len() * std::mem::size_of::<T>() * 8;
+        let bitmap_bits = self.indicator.allocated_chunks() * CHUNK_BITS;
+        let values = if self.indicator.is_hybrid() || list_bits > bitmap_bits {
+            ValuesRef::Bitmap(&self.indicator)
+        } else {
+            ValuesRef::Elements(&self.elements)
+        };
+        ReprRef {
+            max: self.max,
+            ceiling: self.ceiling,
+            hybrid: self.indicator.is_hybrid(),
+            values,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Rebuilds a `Set` from its values, checking every invariant on the way.
+///
+/// Values above the maximum, a maximum above the ceiling, duplicate elements
+/// and malformed indicator chunks are all reported as errors, so a corrupt
+/// payload never yields a Set whose structures disagree.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::Set;
+///
+/// let duplicate = r#"{"max":9,"ceiling":9,"values":{"Elements":[4,4]}}"#;
+/// assert!(serde_json::from_str::<Set>(duplicate).is_err());
+///
+/// let out_of_range = r#"{"max":9,"ceiling":9,"values":{"Elements":[10]}}"#;
+/// assert!(serde_json::from_str::<Set>(out_of_range).is_err());
+/// ```
+impl<'de, T: Element, const PAGE: usize> Deserialize<'de> for Set<T, PAGE> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = Repr::<T>::deserialize(deserializer)?;
+        if repr.ceiling > default_ceiling() || repr.max > repr.ceiling {
+            return Err(D::Error::custom(format!(
+                "set maximum {} and ceiling {} are out of range",
+                repr.max, repr.ceiling
+            )));
+        }
+        let mut set = match repr.values {
+            Values::Elements(elements) => {
+                let mut set = Set::try_with_max(repr.max).map_err(D::Error::custom)?;
+                set.indicator.set_hybrid(repr.hybrid);
+                set.elements
+                    .try_reserve(elements.len())
+                    .map_err(D::Error::custom)?;
+                for value in elements {
+                    if value > repr.max {
+                        return Err(D::Error::custom(format!(
+                            "element {} is above the set's maximum {}",
+                            value, repr.max
+                        )));
+                    }
+                    if !set.insert_unchecked(value) {
+                        return Err(D::Error::custom(format!("duplicate element {}", value)));
+                    }
+                }
+                set
+            }
+            Values::Bitmap(mut indicator) => {
+                if indicator.len() != repr.max.to_index() + 1 {
+                    return Err(D::Error::custom(format!(
+                        "bitmap of {} slots does not match the set's maximum {}",
+                        indicator.len(),
+                        repr.max
+                    )));
+                }
+                indicator.set_hybrid(repr.hybrid);
+                Set::from_indicator(indicator)
+            }
+        };
+        set.ceiling = repr.ceiling;
+        Ok(set)
+    }
+}
@@ -0,0 +1,417 @@
+use super::container::{Chunk, CHUNK_WORDS};
+use super::core::{default_ceiling, Set};
+use super::element::Element;
+use super::error::Error;
+use super::indicator::{Indicator, CHUNK_BITS};
+use std::io::{self, Read, Write};
+
+/// Leading bytes of every snapshot.
+const MAGIC: [u8; 4] = *b"FSET";
+
+/// Layout version written by [`Set::write_to`].
+///
+/// Readers keep a decoder for every version they have ever written, so a
+/// snapshot taken by an older release still loads after the layout evolves.
+const VERSION: u16 = 1;
+
+/// Set in the flags byte when the set uses hybrid containers.
+const FLAG_HYBRID: u8 = 1;
+
+/// How the values of a [`Set`] are laid out in a snapshot.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{Encoding, Set};
+///
+/// let set: Set = (0..1_000).collect();
+/// let mut bitmap = Vec::new();
+/// set.write_encoded_to(&mut bitmap, Encoding::Bitmap).unwrap();
+/// let mut deltas = Vec::new();
+/// set.write_encoded_to(&mut deltas, Encoding::DeltaVarint).unwrap();
+///
+/// // A contiguous run takes one byte per value as deltas
+/// assert!(deltas.len() < bitmap.len());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// The raw 64-bit words of every allocated 2¹⁶-slot chunk.
+    ///
+    /// Costs 8 KiB per allocated chunk, whatever its density.
+    Bitmap,
+    /// The values in ascending order, each stored as its LEB128-encoded gap
+    /// from the previous one.
+    ///
+    /// Costs one to ten bytes per value, fewer the closer the values are.
+    DeltaVarint,
+}
+
+impl Encoding {
+    fn tag(self) -> u8 {
+        match self {
+            Encoding::Bitmap => 0,
+            Encoding::DeltaVarint => 1,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Encoding::Bitmap),
+            1 => Some(Encoding::DeltaVarint),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Element, const PAGE: usize> Set<T, PAGE> {
+    /// Writes the Set to `writer` in the versioned snapshot format, picking
+    /// whichever [`Encoding`] is smaller.
+    ///
+    /// The format is independent of serde and of the Set's fields:
+    ///
+    /// | Field    | Size     | Contents                                      |
+    /// |----------|----------|-----------------------------------------------|
+    /// | magic    | 4 bytes  | `FSET`                                        |
+    /// | version  | 2 bytes  | layout version, currently 1                   |
+    /// | width    | 1 byte   | size of the element type in bytes             |
+    /// | encoding | 1 byte   | 0 for [`Encoding::Bitmap`], 1 for deltas      |
+    /// | flags    | 1 byte   | bit 0 set for hybrid containers               |
+    /// | max      | 8 bytes  | the Set's maximum value                       |
+    /// | ceiling  | 8 bytes  | the Set's ceiling                             |
+    /// | len      | 8 bytes  | the number of values                          |
+    /// | payload  | variable | the values, in the chosen encoding            |
+    /// | crc      | 4 bytes  | CRC-32 (IEEE) of every preceding byte         |
+    ///
+    /// Integers are little-endian. The bitmap payload is a LEB128 chunk count
+    /// followed, for each allocated chunk, by the LEB128 gap from the previous
+    /// chunk index and its 1024 words.
+    ///
+    /// Values come back in ascending order, whatever their storage order.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error raised by `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::Set;
+    ///
+    /// let set: Set<u32> = Set::from(vec![40, 2, 7_000_000]);
+    /// let mut bytes = Vec::new();
+    /// set.write_to(&mut bytes).unwrap();
+    ///
+    /// let loaded: Set<u32> = Set::read_from(bytes.as_slice()).unwrap();
+    /// assert_eq!(loaded, set);
+    /// ```
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        let bitmap = self.indicator.chunk_words().count() * CHUNK_WORDS * 8;
+        let mut previous = 0;
+        let deltas: usize = self
+            .indicator
+            .ones()
+            .map(|value| {
+                let size = varint_len((value - previous) as u64);
+                previous = value;
+                size
+            })
+            .sum();
+        let encoding = match deltas <= bitmap {
+            true => Encoding::DeltaVarint,
+            false => Encoding::Bitmap,
+        };
+        self.write_encoded_to(writer, encoding)
+    }
+
+    /// Writes the Set to `writer` in the snapshot format, with the given
+    /// encoding.
+    ///
+    /// See [`Set::write_to`] for the layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error raised by `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::{Encoding, Set};
+    ///
+    /// let set: Set = (0..100_000).filter(|v| v % 2 == 0).collect();
+    /// let mut bytes = Vec::new();
+    /// set.write_encoded_to(&mut bytes, Encoding::Bitmap).unwrap();
+    ///
+    /// let loaded: Set = Set::read_from(bytes.as_slice()).unwrap();
+    /// assert_eq!(loaded, set);
+    /// ```
+    pub fn write_encoded_to<W: Write>(&self, writer: W, encoding: Encoding) -> io::Result<()> {
+        let mut out = Checksummed::new(writer);
+        out.write_all(&MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        let flags = match self.indicator.is_hybrid() {
+            true => FLAG_HYBRID,
+            false => 0,
+        };
+        out.write_all(&[std::mem::size_of::<T>() as u8, encoding.tag(), flags])?;
+        for field in [self.max, self.ceiling] {
+            out.write_all(&(field.to_index() as u64).to_le_bytes())?;
+        }
+        out.write_all(&(self.len() as u64).to_le_bytes())?;
+        match encoding {
+            Encoding::Bitmap => {
+                write_varint(&mut out, self.indicator.chunk_words().count() as u64)?;
+                let mut previous = 0;
+                for (c, words) in self.indicator.chunk_words() {
+                    write_varint(&mut out, (c - previous) as u64)?;
+                    previous = c;
+                    for word in words.iter() {
+                        out.write_all(&word.to_le_bytes())?;
+                    }
+                }
+            }
+            Encoding::DeltaVarint => {
+                let mut previous = 0;
+                for value in self.indicator.ones() {
+                    write_varint(&mut out, (value - previous) as u64)?;
+                    previous = value;
+                }
+            }
+        }
+        let crc = out.crc();
+        out.inner.write_all(&crc.to_le_bytes())
+    }
+
+    /// Reads a Set written by [`Set::write_to`] from `reader`.
+    ///
+    /// Snapshots of any element width load as long as their values fit `T`.
+    /// Every field is validated and the checksum verified, so a truncated or
+    /// corrupt snapshot is rejected instead of producing an inconsistent Set.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error raised by `reader`, including `UnexpectedEof` for a
+    /// truncated snapshot. Malformed snapshots fail with `InvalidData`,
+    /// wrapping a [`fastset::Error`](Error) that describes the problem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::{Error, Set};
+    ///
+    /// let set: Set = Set::from(vec![1, 5, 9]);
+    /// let mut bytes = Vec::new();
+    /// set.write_to(&mut bytes).unwrap();
+    ///
+    /// let last = bytes.len() - 1;
+    /// bytes[last] ^= 0xff;
+    /// let error = Set::<usize>::read_from(bytes.as_slice()).unwrap_err();
+    /// assert_eq!(
+    ///     error.get_ref().and_then(|e| e.downcast_ref::<Error>()),
+    ///     Some(&Error::ChecksumMismatch)
+    /// );
+    /// ```
+    pub fn read_from<R: Read>(reader: R) -> io::Result<Self> {
+        let mut input = Checksummed::new(reader);
+        let magic: [u8; 4] = read_array(&mut input)?;
+        if magic != MAGIC {
+            return Err(invalid(Error::InvalidSnapshot("missing magic header")));
+        }
+        let set = match u16::from_le_bytes(read_array(&mut input)?) {
+            1 => Self::read_v1(&mut input)?,
+            version => return Err(invalid(Error::UnsupportedVersion(version))),
+        };
+        let crc = input.crc();
+        if u32::from_le_bytes(read_array(&mut input.inner)?) != crc {
+            return Err(invalid(Error::ChecksumMismatch));
+        }
+        Ok(set)
+    }
+
+    /// Decodes the body of a version 1 snapshot, after the version field.
+    fn read_v1<R: Read>(input: &mut Checksummed<R>) -> io::Result<Self> {
+        let [width, tag, flags] = read_array(input)?;
+        if !matches!(width, 2 | 4 | 8) || flags & !FLAG_HYBRID != 0 {
+            return Err(invalid(Error::InvalidSnapshot("unknown width or flags")));
+        }
+        let encoding = Encoding::from_tag(tag)
+            .ok_or_else(|| invalid(Error::InvalidSnapshot("unknown encoding")))?;
+        let hybrid = flags & FLAG_HYBRID != 0;
+        let max = read_value::<T, R>(input)?;
+        let ceiling = read_value::<T, R>(input)?;
+        let len = u64::from_le_bytes(read_array(input)?);
+        if max > ceiling {
+            return Err(invalid(Error::InvalidSnapshot("maximum above the ceiling")));
+        }
+        let slots = max.to_index() + 1;
+
+        let mut set = match encoding {
+            Encoding::Bitmap => {
+                let count = read_varint(input)?;
+                let mut chunks = Vec::new();
+                let mut words = vec![0u64; CHUNK_WORDS];
+                let mut c = 0usize;
+                for i in 0..count {
+                    let gap = usize::try_from(read_varint(input)?).ok();
+                    c = match gap.and_then(|gap| c.checked_add(gap)) {
+                        Some(next) if i == 0 || next > c => next,
+                        _ => return Err(invalid(Error::InvalidSnapshot("chunks out of order"))),
+                    };
+                    if c >= slots.div_ceil(CHUNK_BITS) {
+                        return Err(invalid(Error::InvalidSnapshot("chunk beyond the maximum")));
+                    }
+                    for word in words.iter_mut() {
+                        *word = u64::from_le_bytes(read_array(input)?);
+                    }
+                    let chunk = Chunk::from_words(&words, hybrid)
+                        .ok_or_else(|| invalid(Error::InvalidSnapshot("empty chunk")))?;
+                    chunks.push((c, chunk));
+                }
+                let indicator = Indicator::from_chunks(slots, hybrid, chunks)
+                    .map_err(|reason| invalid(Error::InvalidSnapshot(reason)))?;
+                Set::from_indicator(indicator)
+            }
+            Encoding::DeltaVarint => {
+                let mut set = Set::try_with_max(max).map_err(invalid)?;
+                set.indicator.set_hybrid(hybrid);
+                let mut value = 0usize;
+                for i in 0..len {
+                    let gap = usize::try_from(read_varint(input)?).ok();
+                    value = match gap.and_then(|gap| value.checked_add(gap)) {
+                        Some(next) if next < slots && (i == 0 || next > value) => next,
+                        _ => return Err(invalid(Error::InvalidSnapshot("values out of order"))),
+                    };
+                    set.try_insert(T::from_index(value)).map_err(invalid)?;
+                }
+                set
+            }
+        };
+        if set.len() as u64 != len {
+            return Err(invalid(Error::InvalidSnapshot(
+                "length does not match the values",
+            )));
+        }
+        set.ceiling = ceiling;
+        Ok(set)
+    }
+}
+
+/// Reads a little-endian `u64` and narrows it to `T`.
+fn read_value<T: Element, R: Read>(input: &mut Checksummed<R>) -> io::Result<T> {
+    let value = u64::from_le_bytes(read_array(input)?);
+    let ceiling: T = default_ceiling();
+    match usize::try_from(value) {
+        Ok(index) if index <= ceiling.to_index() => Ok(T::from_index(index)),
+        _ => Err(invalid(Error::OutOfRange {
+            value,
+            ceiling: ceiling.to_index() as u64,
+        })),
+    }
+}
+
+fn read_array<const N: usize, R: Read>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    while value >= 0x80 {
+        writer.write_all(&[value as u8 | 0x80])?;
+        value >>= 7;
+    }
+    writer.write_all(&[value as u8])
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let [byte] = read_array(reader)?;
+        // The tenth byte holds only bit 63
+        if shift == 63 && byte & 0x7e != 0 {
+            break;
+        }
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid(Error::InvalidSnapshot("overlong varint")))
+}
+
+fn varint_len(value: u64) -> usize {
+    (64 - (value | 1).leading_zeros() as usize).div_ceil(7)
+}
+
+fn invalid(error: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Returns the CRC-32 of `bytes`, for tests that forge snapshots.
+#[cfg(test)]
+pub(super) fn crc32(bytes: &[u8]) -> u32 {
+    let mut checksum = Checksummed::new(());
+    checksum.update(bytes);
+    checksum.crc()
+}
+
+/// Reader or writer adapter that keeps a running CRC-32 of the bytes passed through.
+struct Checksummed<I> {
+    inner: I,
+    crc: u32,
+}
+
+impl<I> Checksummed<I> {
+    fn new(inner: I) -> Self {
+        Self { inner, crc: !0 }
+    }
+
+    fn crc(&self) -> u32 {
+        !self.crc
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.crc = CRC_TABLE[((self.crc ^ byte as u32) & 0xff) as usize] ^ (self.crc >> 8);
+        }
+    }
+}
+
+impl<R: Read> Read for Checksummed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl<W: Write> Write for Checksummed<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Lookup table for the reflected IEEE CRC-32 polynomial.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                1 => 0xedb8_8320 ^ (crc >> 1),
+                _ => crc >> 1,
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
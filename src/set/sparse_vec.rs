@@ -0,0 +1,183 @@
+use std::collections::TryReserveError;
+
+/// A sparse map from `usize` keys to values, kept as parallel sorted vectors.
+///
+/// Lookups binary search the keys, except when the keys from the smallest one
+/// up to the probe are contiguous, in which case the position is found by
+/// subtraction. Densely populated universes therefore pay O(1) per lookup,
+/// while sparse ones pay O(log n) in the number of entries rather than memory
+/// proportional to the largest key.
+#[derive(Clone)]
+pub(super) struct SparseVec<V> {
+    keys: Vec<usize>,
+    values: Vec<V>,
+}
+
+impl<V> SparseVec<V> {
+    #[inline(always)]
+    pub(super) fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Builds the map from entries with strictly ascending keys.
+    pub(super) fn from_sorted(entries: impl IntoIterator<Item = (usize, V)>) -> Self {
+        let (keys, values): (Vec<usize>, Vec<V>) = entries.into_iter().unzip();
+        debug_assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        Self { keys, values }
+    }
+
+    /// Returns the number of entries.
+    #[inline(always)]
+    pub(super) fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if there are no entries.
+    #[cfg(test)]
+    pub(super) fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the position of `key`, or where it would be inserted.
+    #[inline(always)]
+    pub(super) fn position(&self, key: usize) -> Result<usize, usize> {
+        if let Some(&first) = self.keys.first() {
+            let guess = key.wrapping_sub(first);
+            if self.keys.get(guess) == Some(&key) {
+                return Ok(guess);
+            }
+        }
+        self.keys.binary_search(&key)
+    }
+
+    /// Returns the position of the first entry whose key is at least `key`.
+    #[inline(always)]
+    pub(super) fn lower_bound(&self, key: usize) -> usize {
+        self.position(key).unwrap_or_else(|pos| pos)
+    }
+
+    #[inline(always)]
+    pub(super) fn get(&self, key: usize) -> Option<&V> {
+        self.position(key).ok().map(|pos| &self.values[pos])
+    }
+
+    #[inline(always)]
+    pub(super) fn get_mut(&mut self, key: usize) -> Option<&mut V> {
+        self.position(key).ok().map(|pos| &mut self.values[pos])
+    }
+
+    /// Returns the entry for `key`, inserting `make()` first if it is missing.
+    #[inline(always)]
+    pub(super) fn get_or_insert_with(&mut self, key: usize, make: impl FnOnce() -> V) -> &mut V {
+        let pos = match self.position(key) {
+            Ok(pos) => pos,
+            Err(pos) => {
+                self.keys.insert(pos, key);
+                self.values.insert(pos, make());
+                pos
+            }
+        };
+        &mut self.values[pos]
+    }
+
+    /// Removes the entry for `key`, returning its value.
+    #[inline(always)]
+    pub(super) fn remove(&mut self, key: usize) -> Option<V> {
+        let pos = self.position(key).ok()?;
+        self.keys.remove(pos);
+        Some(self.values.remove(pos))
+    }
+
+    /// Returns the key at position `pos`, which must be in range.
+    #[inline(always)]
+    pub(super) fn key(&self, pos: usize) -> usize {
+        self.keys[pos]
+    }
+
+    /// Returns the value at position `pos`, which must be in range.
+    #[inline(always)]
+    pub(super) fn value(&self, pos: usize) -> &V {
+        &self.values[pos]
+    }
+
+    /// Returns the key and value at position `pos`.
+    #[inline(always)]
+    pub(super) fn entry(&self, pos: usize) -> Option<(usize, &V)> {
+        Some((*self.keys.get(pos)?, &self.values[pos]))
+    }
+
+    /// Returns the first entry with a key of at least `key`.
+    #[inline(always)]
+    pub(super) fn next(&self, key: usize) -> Option<(usize, &V)> {
+        self.entry(self.lower_bound(key))
+    }
+
+    /// Returns the last entry with a key of at most `key`.
+    #[inline(always)]
+    pub(super) fn prev(&self, key: usize) -> Option<(usize, &V)> {
+        match self.position(key) {
+            Ok(pos) => self.entry(pos),
+            Err(pos) => self.entry(pos.checked_sub(1)?),
+        }
+    }
+
+    /// Returns the last entry, mutably.
+    #[inline(always)]
+    pub(super) fn last_mut(&mut self) -> Option<(usize, &mut V)> {
+        Some((*self.keys.last()?, self.values.last_mut()?))
+    }
+
+    /// Removes the last entry.
+    #[inline(always)]
+    pub(super) fn pop(&mut self) -> Option<(usize, V)> {
+        Some((self.keys.pop()?, self.values.pop()?))
+    }
+
+    /// Removes every entry with a key of at least `key`.
+    pub(super) fn truncate(&mut self, key: usize) {
+        let pos = self.lower_bound(key);
+        self.keys.truncate(pos);
+        self.values.truncate(pos);
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.keys.clear();
+        self.values.clear();
+    }
+
+    /// Reserves room for `additional` more entries, failing instead of aborting.
+    pub(super) fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.keys.try_reserve(additional)?;
+        self.values.try_reserve(additional)
+    }
+
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.keys.shrink_to_fit();
+        self.values.shrink_to_fit();
+    }
+
+    /// Iterates over the entries in ascending key order.
+    #[inline(always)]
+    pub(super) fn iter(&self) -> impl DoubleEndedIterator<Item = (usize, &V)> + '_ {
+        self.keys.iter().copied().zip(&self.values)
+    }
+
+    /// Iterates over the entries with keys in `start..end`, in ascending order.
+    #[inline(always)]
+    pub(super) fn range(&self, start: usize, end: usize) -> impl Iterator<Item = (usize, &V)> + '_ {
+        let from = self.lower_bound(start);
+        let to = self.lower_bound(end).max(from);
+        self.keys[from..to]
+            .iter()
+            .copied()
+            .zip(&self.values[from..to])
+    }
+
+    #[inline(always)]
+    pub(super) fn values_mut(&mut self) -> std::slice::IterMut<'_, V> {
+        self.values.iter_mut()
+    }
+}
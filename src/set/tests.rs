@@ -1,24 +1,28 @@
 use super::*;
-use crate::MAX_CAPACITY;
 use nanorand::{Rng, WyRand};
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 use statrs::distribution::{ChiSquared, ContinuousCDF};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::hash::{Hash, Hasher};
 
+/// A large maximum value, formerly the crate-wide capacity limit.
+const MAX_CAPACITY: usize = 1_000_000_000;
+
 #[test]
 fn new_with_zero_max_element() {
-    let set = Set::with_max(0);
+    let set: Set = Set::with_max(0);
     assert!(set.is_empty());
     assert!(set.elements.is_empty());
-    assert!(!set.indicator.is_empty());
+    assert_eq!(set.indicator.len(), 1);
     assert_eq!(set.max, 0);
 }
 
 #[test]
 fn new_with_nonzero_max_element() {
     let max_element = 10;
-    let set = Set::with_max(max_element);
+    let set: Set = Set::with_max(max_element);
     assert_eq!(set.elements.len(), 0);
     assert_eq!(set.len(), 0);
     assert_eq!(set.max, max_element);
@@ -27,15 +31,15 @@ fn new_with_nonzero_max_element() {
 #[test]
 fn new_with_large_max_element() {
     let max_element = 1000000;
-    let set = Set::with_max(max_element);
+    let set: Set = Set::with_max(max_element);
     assert_eq!(set.elements.len(), 0);
     assert_eq!(set.max, max_element);
 }
 
 #[test]
 fn new_with_multiple_calls() {
-    let set1 = Set::with_max(5);
-    let set2 = Set::with_max(10);
+    let set1: Set = Set::with_max(5);
+    let set2: Set = Set::with_max(10);
 
     assert_eq!(set1.max, 5);
     assert_eq!(set2.max, 10);
@@ -43,19 +47,19 @@ fn new_with_multiple_calls() {
 
 #[test]
 fn with_capacity_zero() {
-    let set = Set::with_capacity(0);
+    let set: Set = Set::with_capacity(0);
 
     // Now with proper protection, indicator will always have at least 1 element
     assert_eq!(set.indicator.len(), 1);
     assert!(set.elements.is_empty());
-    assert!(set.pages.is_empty());
+    assert!(set.positions.is_empty());
     assert_eq!(set.max, 0);
 }
 
 #[test]
 fn with_capacity_nonzero() {
     let capacity = 10;
-    let set = Set::with_capacity(capacity);
+    let set: Set = Set::with_capacity(capacity);
 
     assert_eq!(set.elements.len(), 0);
     // indicator.len() is now capacity + 1 to handle values 0..=capacity
@@ -67,7 +71,7 @@ fn with_capacity_nonzero() {
 #[test]
 fn with_capacity_large() {
     let capacity = 1000000;
-    let set = Set::with_capacity(capacity);
+    let set: Set = Set::with_capacity(capacity);
 
     assert_eq!(set.elements.len(), 0);
     // indicator.len() is now capacity + 1 to handle values 0..=capacity
@@ -78,8 +82,8 @@ fn with_capacity_large() {
 
 #[test]
 fn with_capacity_multiple_calls() {
-    let set1 = Set::with_capacity(5);
-    let set2 = Set::with_capacity(10);
+    let set1: Set = Set::with_capacity(5);
+    let set2: Set = Set::with_capacity(10);
 
     // max is now equal to capacity (not capacity-1)
     assert_eq!(set1.max, 5);
@@ -88,7 +92,7 @@ fn with_capacity_multiple_calls() {
 
 #[test]
 fn reserve_increase_capacity() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.reserve(10);
 
     assert_eq!(set.max, 10);
@@ -97,7 +101,7 @@ fn reserve_increase_capacity() {
 
 #[test]
 fn reserve_no_increase_capacity() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.reserve(3);
 
     assert_eq!(set.max, 5);
@@ -106,7 +110,7 @@ fn reserve_no_increase_capacity() {
 
 #[test]
 fn reserve_same_capacity() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.reserve(5);
 
     assert_eq!(set.max, 5);
@@ -115,7 +119,7 @@ fn reserve_same_capacity() {
 
 #[test]
 fn reserve_large_capacity() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.reserve(100);
 
     assert_eq!(set.max, 100);
@@ -124,13 +128,13 @@ fn reserve_large_capacity() {
 
 #[test]
 fn len_empty_set() {
-    let set = Set::with_max(5);
+    let set: Set = Set::with_max(5);
     assert_eq!(set.len(), 0);
 }
 
 #[test]
 fn len_non_empty_set() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.insert(1);
     set.insert(2);
     set.insert(3);
@@ -140,13 +144,13 @@ fn len_non_empty_set() {
 
 #[test]
 fn is_empty_empty_set() {
-    let set = Set::with_max(5);
+    let set: Set = Set::with_max(5);
     assert!(set.is_empty());
 }
 
 #[test]
 fn is_empty_non_empty_set() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.insert(1);
 
     assert!(!set.is_empty());
@@ -154,7 +158,7 @@ fn is_empty_non_empty_set() {
 
 #[test]
 fn iter_empty_set() {
-    let set = Set::with_max(5);
+    let set: Set = Set::with_max(5);
     let mut iter = set.iter();
 
     assert_eq!(iter.next(), None);
@@ -162,7 +166,7 @@ fn iter_empty_set() {
 
 #[test]
 fn iter_non_empty_set() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.insert(1);
     set.insert(2);
 
@@ -175,7 +179,7 @@ fn iter_non_empty_set() {
 
 #[test]
 fn clear() {
-    let mut set = Set::with_max(3);
+    let mut set: Set = Set::with_max(3);
     set.insert(1);
     set.insert(2);
     set.insert(3);
@@ -188,7 +192,7 @@ fn clear() {
 
 #[test]
 fn clear_empty_set() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.clear();
 
     assert!(set.is_empty());
@@ -196,7 +200,7 @@ fn clear_empty_set() {
 
 #[test]
 fn clear_non_empty_set() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.insert(1);
     set.insert(2);
     set.clear();
@@ -206,7 +210,7 @@ fn clear_non_empty_set() {
 
 #[test]
 fn insert() {
-    let mut set = Set::with_max(MAX_CAPACITY);
+    let mut set: Set = Set::with_max(MAX_CAPACITY);
     // Insert a value and check its presence
     set.insert(1);
     assert!(set.contains(&1), "Set should contain 1");
@@ -227,7 +231,7 @@ fn insert() {
 
 #[test]
 fn insert_within_capacity() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     assert!(set.insert(1));
     assert!(set.contains(&1));
     assert_eq!(set.len(), 1);
@@ -235,7 +239,7 @@ fn insert_within_capacity() {
 
 #[test]
 fn insert_beyond_capacity_and_within_max() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     assert!(set.insert(6));
     assert!(set.contains(&6));
     assert_eq!(set.len(), 1);
@@ -244,7 +248,7 @@ fn insert_beyond_capacity_and_within_max() {
 #[test]
 #[should_panic]
 fn insert_beyond_max_capacity() {
-    let mut set = Set::with_max(usize::MAX);
+    let mut set: Set = Set::with_max(usize::MAX);
     assert!(!set.insert(usize::MAX));
     assert!(!set.contains(&usize::MAX));
     assert_eq!(set.len(), 0);
@@ -252,7 +256,7 @@ fn insert_beyond_max_capacity() {
 
 #[test]
 fn remove() {
-    let mut set = Set::with_max(MAX_CAPACITY / 3000);
+    let mut set: Set = Set::with_max(MAX_CAPACITY / 3000);
     // Insert some values
     set.insert(1);
     set.insert(2);
@@ -285,7 +289,7 @@ fn remove() {
 
 #[test]
 fn remove_existing_element() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.insert(3);
     assert!(set.remove(&3));
     assert!(!set.contains(&3));
@@ -294,7 +298,7 @@ fn remove_existing_element() {
 
 #[test]
 fn remove_non_existing_element() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.insert(3);
     assert!(!set.remove(&5));
     assert_eq!(set.len(), 1);
@@ -302,7 +306,7 @@ fn remove_non_existing_element() {
 
 #[test]
 fn remove_element_beyond_capacity() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.insert(3);
     assert!(!set.remove(&(usize::MAX)));
     assert_eq!(set.len(), 1);
@@ -310,7 +314,7 @@ fn remove_element_beyond_capacity() {
 
 #[test]
 fn contains() {
-    let mut set = Set::with_max(MAX_CAPACITY);
+    let mut set: Set = Set::with_max(MAX_CAPACITY);
     // Insert some values
     set.insert(1);
     set.insert(2);
@@ -327,150 +331,150 @@ fn contains() {
 
 #[test]
 fn contains_existing_element() {
-    let set = Set::from(vec![1, 2, 3]);
+    let set: Set = Set::from(vec![1, 2, 3]);
     assert!(set.contains(&2));
 }
 
 #[test]
 fn contains_non_existing_element() {
-    let set = Set::from(vec![1, 2, 3]);
+    let set: Set = Set::from(vec![1, 2, 3]);
     assert!(!set.contains(&5));
 }
 
 #[test]
 fn contains_element_beyond_capacity() {
-    let set = Set::with_max(5);
+    let set: Set = Set::with_max(5);
     assert!(!set.contains(&(usize::MAX)));
 }
 
 #[test]
 fn get_existing_element() {
-    let set = Set::from(vec![1, 2, 3]);
+    let set: Set = Set::from(vec![1, 2, 3]);
     assert_eq!(set.get(&2), Some(2));
 }
 
 #[test]
 fn get_non_existing_element() {
-    let set = Set::from(vec![1, 2, 3]);
+    let set: Set = Set::from(vec![1, 2, 3]);
     assert_eq!(set.get(&5), None);
 }
 
 #[test]
 fn get_element_beyond_capacity() {
-    let set = Set::with_max(5);
+    let set: Set = Set::with_max(5);
     assert_eq!(set.get(&(usize::MAX)), None);
 }
 
 #[test]
 fn take_existing_element() {
-    let mut set = Set::from(vec![1, 2, 3]);
+    let mut set: Set = Set::from(vec![1, 2, 3]);
     assert_eq!(set.take(&2), Some(2));
     assert!(!set.contains(&2));
 }
 
 #[test]
 fn take_non_existing_element() {
-    let mut set = Set::from(vec![1, 2, 3]);
+    let mut set: Set = Set::from(vec![1, 2, 3]);
     assert_eq!(set.take(&5), None);
     assert_eq!(set.len(), 3);
 }
 
 #[test]
 fn take_element_beyond_capacity() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     assert_eq!(set.take(&(usize::MAX)), None);
 }
 
 #[test]
 fn max_empty_set() {
-    let set = Set::with_max(5);
+    let set: Set = Set::with_max(5);
     assert_eq!(set.max(), None);
 }
 
 #[test]
 fn max_non_empty_set() {
-    let set = Set::from(vec![1, 2, 3]);
+    let set: Set = Set::from(vec![1, 2, 3]);
     assert_eq!(set.max(), Some(3));
 }
 
 #[test]
 fn max_set_with_single_element() {
-    let set = Set::from(vec![5]);
+    let set: Set = Set::from(vec![5]);
     assert_eq!(set.max(), Some(5));
 }
 
 #[test]
 fn min_empty_set() {
-    let set = Set::with_max(5);
+    let set: Set = Set::with_max(5);
     assert_eq!(set.min(), None);
 }
 
 #[test]
 fn min_non_empty_set() {
-    let set = Set::from(vec![3, 1, 5]);
+    let set: Set = Set::from(vec![3, 1, 5]);
     assert_eq!(set.min(), Some(1));
 }
 
 #[test]
 fn min_set_with_single_element() {
-    let set = Set::from(vec![5]);
+    let set: Set = Set::from(vec![5]);
     assert_eq!(set.min(), Some(5));
 }
 
 #[test]
 fn range_cardinality_empty_set() {
-    let set = Set::with_max(10);
+    let set: Set = Set::with_max(10);
     assert_eq!(set.range_cardinality(..), 0);
     assert_eq!(set.range_cardinality(0..5), 0);
 }
 
 #[test]
 fn range_cardinality_full_set() {
-    let set = Set::from(vec![1, 2, 3, 4, 5]);
+    let set: Set = Set::from(vec![1, 2, 3, 4, 5]);
     assert_eq!(set.range_cardinality(..), 5);
     assert_eq!(set.range_cardinality(1..4), 3);
 }
 
 #[test]
 fn range_cardinality_out_of_bounds() {
-    let set = Set::from(vec![1, 2, 3, 4, 5]);
+    let set: Set = Set::from(vec![1, 2, 3, 4, 5]);
     assert_eq!(set.range_cardinality(6..10), 0);
 }
 
 #[test]
 fn rank_empty_set() {
-    let set = Set::with_max(10);
+    let set: Set = Set::with_max(10);
     assert_eq!(set.rank(5), 0);
 }
 
 #[test]
 fn rank_non_empty_set() {
-    let set = Set::from(vec![1, 3, 5, 7, 9]);
+    let set: Set = Set::from(vec![1, 3, 5, 7, 9]);
     assert_eq!(set.rank(5), 2);
 }
 
 #[test]
 fn rank_non_existing_element() {
-    let set = Set::from(vec![1, 3, 5, 7, 9]);
+    let set: Set = Set::from(vec![1, 3, 5, 7, 9]);
     assert_eq!(set.rank(6), 3);
 }
 
 #[test]
 fn remove_largest_from_empty_set() {
-    let mut set = Set::with_max(10);
+    let mut set: Set = Set::with_max(10);
     assert_eq!(set.remove_largest(), None);
 }
 
 #[test]
 fn remove_largest_from_non_empty_set() {
-    let mut set = Set::from(vec![1, 3, 5, 7, 9]);
+    let mut set: Set = Set::from(vec![1, 3, 5, 7, 9]);
     assert_eq!(set.remove_largest(), Some(9));
     assert!(!set.contains(&9));
 }
 
 #[test]
 fn remove_largest_from_unsorted_set() {
-    let mut set = Set::with_max(100);
+    let mut set: Set = Set::with_max(100);
     set.insert(10);
     set.insert(1);
     set.insert(7);
@@ -480,20 +484,20 @@ fn remove_largest_from_unsorted_set() {
 
 #[test]
 fn remove_smallest_from_empty_set() {
-    let mut set = Set::with_max(10);
+    let mut set: Set = Set::with_max(10);
     assert_eq!(set.remove_smallest(), None);
 }
 
 #[test]
 fn remove_smallest_from_non_empty_set() {
-    let mut set = Set::from(vec![1, 3, 5, 7, 9]);
+    let mut set: Set = Set::from(vec![1, 3, 5, 7, 9]);
     assert_eq!(set.remove_smallest(), Some(1));
     assert!(!set.contains(&1));
 }
 
 #[test]
 fn remove_smallest_from_unsorted_set() {
-    let mut set = Set::with_max(100);
+    let mut set: Set = Set::with_max(100);
     set.insert(10);
     set.insert(1);
     set.insert(7);
@@ -503,7 +507,7 @@ fn remove_smallest_from_unsorted_set() {
 
 #[test]
 fn random() {
-    let mut set = Set::with_max(MAX_CAPACITY / 3000);
+    let mut set: Set = Set::with_max(MAX_CAPACITY / 3000);
     set.insert(1);
     set.insert(2);
     set.insert(3);
@@ -511,7 +515,7 @@ fn random() {
     set.insert(5);
     // Test with non-empty set
     let mut observed_values = HashSet::new();
-    let mut rng = WyRand::new();
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
     for _ in 0..100 {
         if let Some(value) = set.random(&mut rng) {
             assert!(
@@ -536,14 +540,211 @@ fn random() {
 
 #[test]
 fn random_returns_none_for_empty_set() {
-    let set = Set::with_max(10);
-    let mut rng = WyRand::new();
+    let set: Set = Set::with_max(10);
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
     assert_eq!(set.random(&mut rng), None);
 }
 
+#[test]
+fn random_is_reproducible_under_a_seeded_rng() {
+    let set: Set<u32> = Set::from_iter((0..10_000).step_by(7));
+    let draw = |seed| {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        (0..64)
+            .map(|_| set.random(&mut rng).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(draw(3), draw(3));
+    assert_ne!(draw(3), draw(4));
+    assert!(draw(3).iter().all(|value| set.contains(value)));
+
+    // Trait objects work too
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let dynamic: &mut dyn rand_chacha::rand_core::RngCore = &mut rng;
+    assert_eq!(set.random(dynamic), draw(3).first().copied());
+}
+
+#[test]
+fn pop_random_drains_the_set_uniformly() {
+    let mut rng = ChaCha8Rng::seed_from_u64(18);
+    let mut set: Set<u32> = Set::from_iter((0..5_000).map(|x| x * 7));
+    set.enable_rank_index();
+    let mut reference: std::collections::BTreeSet<u32> = set.iter().copied().collect();
+    while let Some(value) = set.pop_random(&mut rng) {
+        assert!(reference.remove(&value));
+        assert!(!set.contains(&value));
+        assert_eq!(set.len(), reference.len());
+        assert_eq!(set.max(), reference.last().copied());
+        assert_eq!(set.min(), reference.first().copied());
+        if reference.len().is_multiple_of(499) {
+            assert_eq!(set.rank(17_500), reference.range(..17_500).count());
+            for &value in reference.iter().step_by(31) {
+                assert!(set.contains(&value));
+            }
+        }
+    }
+    assert!(set.is_empty());
+    assert_eq!(set.pop_random(&mut rng), None);
+
+    // Survivors stay consistent with the indicator after refilling
+    set.extend([3, 7, 11]);
+    set.pop_random(&mut rng);
+    assert_eq!(set.iter_sorted().count(), 2);
+
+    // The first pop from a 4-element set hits each element a quarter of the time
+    let mut counts = [0usize; 4];
+    for _ in 0..40_000 {
+        let mut set: Set = Set::from_iter(0..4);
+        counts[set.pop_random(&mut rng).unwrap()] += 1;
+    }
+    assert!(
+        counts.iter().all(|&c| c.abs_diff(10_000) < 400),
+        "{:?}",
+        counts
+    );
+}
+
+#[test]
+fn random_in_range_draws_uniformly_from_the_range() {
+    let mut rng = ChaCha8Rng::seed_from_u64(20);
+    let mut set: Set<u32> = Set::from_iter((0..300_000).step_by(3));
+    set.remove(&150_000);
+    for indexed in [false, true] {
+        if indexed {
+            set.enable_rank_index();
+        }
+        // Ranges inside one chunk, across chunks, around a gap and of one slot
+        for (start, end) in [(10, 20), (65_000, 140_000), (149_990, 150_004), (0, 1)] {
+            for _ in 0..200 {
+                let value = set.random_in_range(start..end, &mut rng).unwrap();
+                assert!((start..end).contains(&value) && set.contains(&value));
+            }
+        }
+        assert!(set.random_in_range(299_990.., &mut rng).unwrap() >= 299_990);
+        assert_eq!(set.random_in_range(150_000..150_003, &mut rng), None);
+        assert_eq!(set.random_in_range(400_000.., &mut rng), None);
+    }
+
+    let mut counts = [0usize; 4];
+    for _ in 0..40_000 {
+        let value = set.random_in_range(66..=75, &mut rng).unwrap();
+        counts[(value as usize - 66) / 3] += 1;
+    }
+    assert!(
+        counts.iter().all(|&c| c.abs_diff(10_000) < 400),
+        "{:?}",
+        counts
+    );
+}
+
+#[test]
+fn random_where_falls_back_to_a_scan_for_rare_matches() {
+    let mut rng = ChaCha8Rng::seed_from_u64(21);
+    let set: Set = Set::from_iter(0..100_000);
+    for _ in 0..100 {
+        let value = set.random_where(|value| value % 10 == 3, &mut rng).unwrap();
+        assert_eq!(value % 10, 3);
+    }
+
+    // Two matches among 100k: the draws almost always miss and the scan picks
+    let mut counts = [0usize; 2];
+    for _ in 0..2_000 {
+        match set.random_where(|&value| value == 17 || value == 99_999, &mut rng) {
+            Some(17) => counts[0] += 1,
+            Some(99_999) => counts[1] += 1,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+    assert!(
+        counts.iter().all(|&c| c.abs_diff(1_000) < 150),
+        "{:?}",
+        counts
+    );
+
+    assert_eq!(set.random_where(|&value| value > 100_000, &mut rng), None);
+    let empty: Set = Set::with_max(10);
+    assert_eq!(empty.random_where(|_| true, &mut rng), None);
+}
+
+#[test]
+fn sample_draws_distinct_elements_uniformly() {
+    let set: Set<u32> = Set::from_iter((0..200).map(|x| x * 3));
+    let mut rng = ChaCha8Rng::seed_from_u64(8);
+    // Floyd below half the length, scanning `out` up to 64 picks and hashing
+    // above, Fisher–Yates above half the length, and the edges
+    for k in [0, 1, 7, 64, 65, 99, 100, 150, 200, 500] {
+        let batch = set.sample(k, &mut rng);
+        assert_eq!(batch.len(), k.min(200));
+        let distinct: HashSet<u32> = batch.iter().copied().collect();
+        assert_eq!(distinct.len(), batch.len());
+        assert!(batch.iter().all(|value| set.contains(value)));
+    }
+    let empty: Set<u32> = Set::with_max(10);
+    assert!(empty.sample(3, &mut rng).is_empty());
+
+    // Each element of a small set lands in a k-of-5 sample k/5 of the time
+    for k in [2, 3] {
+        let set: Set = Set::from_iter(0..5);
+        let mut counts = [0usize; 5];
+        for _ in 0..50_000 {
+            for value in set.sample(k, &mut rng) {
+                counts[value] += 1;
+            }
+        }
+        let expected = 50_000 * k / 5;
+        for count in counts {
+            assert!(count.abs_diff(expected) < expected / 50, "{:?}", counts);
+        }
+    }
+}
+
+#[test]
+fn sample_into_replaces_and_reuses_the_buffer() {
+    let set: Set = Set::from_iter(0..1_000);
+    let mut rng = ChaCha8Rng::seed_from_u64(2);
+    let mut batch = vec![5_000; 3];
+    set.sample_into(&mut batch, 64, &mut rng);
+    assert_eq!(batch.len(), 64);
+    assert!(batch.iter().all(|value| set.contains(value)));
+    let capacity = batch.capacity();
+    for _ in 0..10 {
+        set.sample_into(&mut batch, 64, &mut rng);
+        assert_eq!(batch.len(), 64);
+    }
+    assert_eq!(batch.capacity(), capacity);
+
+    let mut again = Vec::new();
+    set.sample_into(&mut again, 64, &mut ChaCha8Rng::seed_from_u64(9));
+    assert_eq!(again, set.sample(64, &mut ChaCha8Rng::seed_from_u64(9)));
+}
+
+#[cfg(feature = "nanorand")]
+#[test]
+fn nano_rng_adapts_nanorand_generators() {
+    use crate::NanoRng;
+    use rand_chacha::rand_core::RngCore;
+
+    let mut adapted = NanoRng(WyRand::new_seed(9));
+    let mut raw = WyRand::new_seed(9);
+    assert_eq!(adapted.next_u64(), u64::from_le_bytes(raw.rand()));
+    let mut bytes = [0u8; 11];
+    adapted.fill_bytes(&mut bytes);
+    let (first, second) = (raw.rand(), raw.rand());
+    assert_eq!(bytes[..8], first);
+    assert_eq!(bytes[8..], second[..3]);
+
+    let set: Set = Set::from(vec![4, 8, 15]);
+    let mut a = NanoRng::from(WyRand::new_seed(1));
+    let mut b = NanoRng::from(WyRand::new_seed(1));
+    for _ in 0..32 {
+        assert_eq!(set.random(&mut a), set.random(&mut b));
+    }
+    assert!(set.contains(&crate::random!(set).unwrap()));
+}
+
 #[test]
 fn insert_unchecked_adds_element_correctly() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
 
     // Insert an element without bounds checking
     let result = set.insert_unchecked(3);
@@ -558,7 +759,7 @@ fn insert_unchecked_adds_element_correctly() {
 
 #[test]
 fn remove_unchecked_removes_element_correctly() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.insert(3);
 
     // Remove the element without bounds checking
@@ -575,7 +776,7 @@ fn remove_unchecked_removes_element_correctly() {
 #[test]
 #[should_panic]
 fn remove_unchecked_panics_for_out_of_bounds() {
-    let mut set = Set::with_max(5);
+    let mut set: Set = Set::with_max(5);
     set.insert(3);
     // Attempt to remove an out-of-bounds element without bounds checking should panic
     unsafe { assert!(!set.remove_unchecked(&6)) };
@@ -583,7 +784,7 @@ fn remove_unchecked_panics_for_out_of_bounds() {
 
 #[test]
 fn contains_returns_true_for_existing_element() {
-    let mut set = Set::with_max(100);
+    let mut set: Set = Set::with_max(100);
     set.insert(42);
 
     // Check if the set contains the inserted value
@@ -599,7 +800,7 @@ fn contains_returns_false_for_nonexistent_element() {
 
 #[test]
 fn iter_returns_correct_values() {
-    let mut set = Set::with_max(2);
+    let mut set: Set = Set::with_max(2);
     set.insert(42);
     set.insert(100);
     // Create an iterator from the set
@@ -612,7 +813,7 @@ fn iter_returns_correct_values() {
 
 #[test]
 fn max_returns_correct_value() {
-    let mut set = HashSet::new();
+    let mut set: HashSet<usize> = HashSet::new();
     set.insert(42);
     set.insert(100);
     // Check if the maximum value in the set is returned
@@ -621,7 +822,7 @@ fn max_returns_correct_value() {
 
 #[test]
 fn max_returns_none_for_empty_set() {
-    let set = HashSet::new();
+    let set: HashSet<usize> = HashSet::new();
 
     // Check if None is returned for an empty set
     assert_eq!(set.max(), None);
@@ -629,8 +830,8 @@ fn max_returns_none_for_empty_set() {
 
 #[test]
 fn is_subset_returns_true_for_subset() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(1..=10);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(1..=10);
 
     // Check if set1 is a subset of set2
     assert!(set1.is_subset(&set2));
@@ -638,8 +839,8 @@ fn is_subset_returns_true_for_subset() {
 
 #[test]
 fn is_subset_returns_false_for_non_subset() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(6..=10);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(6..=10);
 
     // Check if set1 is a subset of set2
     assert!(!set1.is_subset(&set2));
@@ -647,8 +848,8 @@ fn is_subset_returns_false_for_non_subset() {
 
 #[test]
 fn is_superset_returns_true_for_superset() {
-    let set1 = Set::from_iter(1..=10);
-    let set2 = Set::from_iter(1..=5);
+    let set1: Set = Set::from_iter(1..=10);
+    let set2: Set = Set::from_iter(1..=5);
 
     // Check if set1 is a superset of set2
     assert!(set1.is_superset(&set2));
@@ -656,8 +857,8 @@ fn is_superset_returns_true_for_superset() {
 
 #[test]
 fn is_superset_returns_false_for_non_superset() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(6..=10);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(6..=10);
 
     // Check if set1 is a superset of set2
     assert!(!set1.is_superset(&set2));
@@ -665,8 +866,8 @@ fn is_superset_returns_false_for_non_superset() {
 
 #[test]
 fn is_disjoint_returns_true_for_disjoint_sets() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(6..=10);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(6..=10);
 
     // Check if set1 and set2 are disjoint
     assert!(set1.is_disjoint(&set2));
@@ -674,8 +875,8 @@ fn is_disjoint_returns_true_for_disjoint_sets() {
 
 #[test]
 fn is_disjoint_returns_false_for_non_disjoint_sets() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=10);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=10);
 
     // Check if set1 and set2 are disjoint
     assert!(!set1.is_disjoint(&set2));
@@ -683,8 +884,8 @@ fn is_disjoint_returns_false_for_non_disjoint_sets() {
 
 #[test]
 fn test_intersection() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     let intersection = set1.intersection(&set2);
 
@@ -696,8 +897,8 @@ fn test_intersection() {
 
 #[test]
 fn test_difference() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     let difference = set1.difference(&set2);
 
@@ -709,8 +910,8 @@ fn test_difference() {
 
 #[test]
 fn test_symmetric_difference() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     let symmetric_difference = set1.symmetric_difference(&set2);
 
@@ -725,8 +926,8 @@ fn test_symmetric_difference() {
 
 #[test]
 fn test_empty_set_operations() {
-    let set1 = Set::with_max(100);
-    let set2 = Set::with_max(100);
+    let set1: Set = Set::with_max(100);
+    let set2: Set = Set::with_max(100);
 
     assert!(set1.union(&set2).is_empty());
     assert!(set1.intersection(&set2).is_empty());
@@ -736,8 +937,8 @@ fn test_empty_set_operations() {
 
 #[test]
 fn test_sets_with_same_elements() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(1..=5);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(1..=5);
 
     assert_eq!(set1.union(&set2), set1.intersection(&set2));
     assert!(set1.difference(&set2).is_empty());
@@ -747,8 +948,8 @@ fn test_sets_with_same_elements() {
 #[test]
 fn test_boundary_cases() {
     // Test when one set is empty
-    let set1 = Set::with_max(10);
-    let set2 = Set::from_iter(1..=5);
+    let set1: Set = Set::with_max(10);
+    let set2: Set = Set::from_iter(1..=5);
 
     assert!(set1.is_subset(&set2));
     assert!(!set2.is_subset(&set1));
@@ -757,8 +958,8 @@ fn test_boundary_cases() {
     assert!(set1.is_disjoint(&set2));
 
     // Test when one set is a subset/superset of the other
-    let set3 = Set::from_iter(1..=5);
-    let set4 = Set::from_iter(1..=10);
+    let set3: Set = Set::from_iter(1..=5);
+    let set4: Set = Set::from_iter(1..=10);
 
     assert!(set3.is_subset(&set4));
     assert!(!set4.is_subset(&set3));
@@ -767,8 +968,8 @@ fn test_boundary_cases() {
     assert!(!set3.is_disjoint(&set4));
 
     // Test when the sets have only one common element
-    let set5 = Set::from_iter(1..=5);
-    let set6 = Set::from_iter(4..=8);
+    let set5: Set = Set::from_iter(1..=5);
+    let set6: Set = Set::from_iter(4..=8);
 
     assert!(!set5.is_subset(&set6));
     assert!(!set6.is_subset(&set5));
@@ -779,8 +980,8 @@ fn test_boundary_cases() {
 
 #[test]
 fn test_bit_xor_sets() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     let result = &set1 ^ &set2;
 
@@ -793,8 +994,8 @@ fn test_bit_xor_sets() {
 
 #[test]
 fn test_bit_xor_assignment_sets() {
-    let mut set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let mut set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     set1 ^= &set2;
 
@@ -812,7 +1013,7 @@ fn test_bit_xor_assignment_sets() {
 
 #[test]
 fn test_bit_xor_assignment_set_and_hashset() {
-    let mut set = Set::from_iter(1..=5);
+    let mut set: Set = Set::from_iter(1..=5);
     let hash_set = (4..=8).collect::<HashSet<_>>();
 
     set ^= &hash_set;
@@ -829,8 +1030,8 @@ fn test_bit_xor_assignment_set_and_hashset() {
 
 #[test]
 fn test_sub_sets() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     let result = &set1 - &set2;
 
@@ -843,7 +1044,7 @@ fn test_sub_sets() {
 
 #[test]
 fn test_sub_set_and_hashset() {
-    let set = Set::from_iter(1..=5);
+    let set: Set = Set::from_iter(1..=5);
     let hash_set = (4..=8).collect::<HashSet<_>>();
 
     let result = &set - &hash_set;
@@ -858,8 +1059,8 @@ fn test_sub_set_and_hashset() {
 
 #[test]
 fn test_sub_assignment_sets() {
-    let mut set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let mut set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     set1 -= &set2;
 
@@ -872,7 +1073,7 @@ fn test_sub_assignment_sets() {
 
 #[test]
 fn test_sub_assignment_set_and_hashset() {
-    let mut set = Set::from_iter(1..=5);
+    let mut set: Set = Set::from_iter(1..=5);
     let hash_set = (4..=8).collect::<HashSet<_>>();
 
     set -= &hash_set;
@@ -886,8 +1087,8 @@ fn test_sub_assignment_set_and_hashset() {
 
 #[test]
 fn test_bitand_sets() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     let result = &set1 & &set2;
 
@@ -899,7 +1100,7 @@ fn test_bitand_sets() {
 
 #[test]
 fn test_bitand_set_and_hashset() {
-    let set = Set::from_iter(1..=5);
+    let set: Set = Set::from_iter(1..=5);
     let hash_set = (4..=8).collect::<HashSet<_>>();
 
     let result = &set & &hash_set;
@@ -914,8 +1115,8 @@ fn test_bitand_set_and_hashset() {
 
 #[test]
 fn test_bitand_assignment_sets() {
-    let mut set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let mut set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     set1 &= &set2;
 
@@ -927,7 +1128,7 @@ fn test_bitand_assignment_sets() {
 
 #[test]
 fn test_bitand_assignment_set_and_hashset() {
-    let mut set = Set::from_iter(1..=5);
+    let mut set: Set = Set::from_iter(1..=5);
     let hash_set = (4..=8).collect::<HashSet<_>>();
 
     set &= &hash_set;
@@ -936,7 +1137,7 @@ fn test_bitand_assignment_set_and_hashset() {
     assert!(set.contains(&4));
     assert!(set.contains(&5));
 
-    let mut set2 = Set::from_iter(1..5);
+    let mut set2: Set = Set::from_iter(1..5);
     let hash_set2 = (4..=8).collect::<HashSet<_>>();
 
     set2 &= &hash_set2;
@@ -945,7 +1146,7 @@ fn test_bitand_assignment_set_and_hashset() {
     assert!(set2.contains(&4));
     assert!(!set2.contains(&5));
 
-    let mut set3 = Set::from_iter(1..5);
+    let mut set3: Set = Set::from_iter(1..5);
     let hash_set3 = (6..=8).collect::<HashSet<_>>();
 
     set3 &= &hash_set3;
@@ -956,7 +1157,7 @@ fn test_bitand_assignment_set_and_hashset() {
 
 #[test]
 fn debug_format() {
-    let mut set = Set::with_max(5); // Assuming a 'with_max' method with a 'max' parameter.
+    let mut set: Set = Set::with_max(5); // Assuming a 'with_max' method with a 'max' parameter.
 
     // Simulate `from_iter` functionality for the test.
     for i in 1..=5 {
@@ -981,7 +1182,7 @@ fn debug_format() {
 
 #[test]
 fn display_format() {
-    let set = Set::from_iter(1..=3);
+    let set: Set = Set::from_iter(1..=3);
     let display_output = format!("{}", set);
     assert!(display_output == "{1, 2, 3}" || display_output == "{3, 2, 1}");
 }
@@ -994,43 +1195,43 @@ fn test_default() {
 
 #[test]
 fn test_partial_eq_sets_equal() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(1..=5);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(1..=5);
     assert_eq!(set1, set2);
 }
 
 #[test]
 fn test_partial_eq_sets_not_equal() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(6..=10);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(6..=10);
     assert_ne!(set1, set2);
 }
 
 #[test]
 fn test_eq_sets_equal() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter((1..=5).rev());
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter((1..=5).rev());
     assert_eq!(set1, set2);
 }
 
 #[test]
 fn test_eq_sets_not_equal() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(1..=4);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(1..=4);
     assert_ne!(set1, set2);
 }
 
 #[test]
 fn test_partial_eq_with_hashset() {
-    let set = Set::from_iter(1..=5);
+    let set: Set = Set::from_iter(1..=5);
     let hash_set: HashSet<usize> = (1..=5).collect();
     assert_eq!(set, hash_set);
 }
 
 #[test]
 fn test_hash() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(1..=5);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(1..=5);
 
     let mut hasher1 = DefaultHasher::new();
     let mut hasher2 = DefaultHasher::new();
@@ -1044,7 +1245,7 @@ fn test_hash() {
 #[test]
 fn test_from_vec() {
     let vec = vec![1, 2, 3, 4, 5];
-    let set = Set::from(vec.clone());
+    let set: Set = Set::from(vec.clone());
 
     for item in vec {
         assert!(set.contains(&item));
@@ -1054,7 +1255,7 @@ fn test_from_vec() {
 #[test]
 fn test_from_slice() {
     let items = &[1, 2, 3, 4, 5];
-    let set = Set::from(items);
+    let set: Set = Set::from(items);
 
     for &item in items {
         assert!(set.contains(&item));
@@ -1064,7 +1265,7 @@ fn test_from_slice() {
 #[test]
 fn test_from_array() {
     let items = &[1, 2, 3, 4, 5];
-    let set = Set::from(items);
+    let set: Set = Set::from(items);
 
     for &item in items {
         assert!(set.contains(&item));
@@ -1078,7 +1279,7 @@ fn test_from_hashset_owned() {
     hash_set.insert(2);
     hash_set.insert(3);
 
-    let set = Set::from(hash_set.clone());
+    let set: Set = Set::from(hash_set.clone());
 
     for item in hash_set {
         assert!(set.contains(&item));
@@ -1092,7 +1293,7 @@ fn test_from_hashset_ref() {
     hash_set.insert(2);
     hash_set.insert(3);
 
-    let set = Set::from(&hash_set);
+    let set: Set = Set::from(&hash_set);
 
     for &item in &hash_set {
         assert!(set.contains(&item));
@@ -1101,7 +1302,7 @@ fn test_from_hashset_ref() {
 
 #[test]
 fn test_extend_usize() {
-    let mut set = Set::with_max(0);
+    let mut set: Set = Set::with_max(0);
     set.extend(vec![1, 2, 3]);
 
     assert!(set.contains(&1));
@@ -1111,7 +1312,7 @@ fn test_extend_usize() {
 
 #[test]
 fn test_extend_ref_usize() {
-    let mut set = Set::with_max(0);
+    let mut set: Set = Set::with_max(0);
     let values = [1, 2, 3];
     set.extend(values.iter());
 
@@ -1143,7 +1344,7 @@ fn test_from_iterator_ref_usize() {
 
 #[test]
 fn test_into_iter_owned() {
-    let set = Set::from(vec![1, 2, 3]);
+    let set: Set = Set::from(vec![1, 2, 3]);
     let mut iter = set.into_iter();
 
     assert_eq!(iter.next(), Some(1));
@@ -1154,7 +1355,7 @@ fn test_into_iter_owned() {
 
 #[test]
 fn test_into_iter_ref() {
-    let set = Set::from(vec![1, 2, 3]);
+    let set: Set = Set::from(vec![1, 2, 3]);
 
     let mut values = Vec::new();
     for &value in &set {
@@ -1166,7 +1367,7 @@ fn test_into_iter_ref() {
 
 #[test]
 fn test_into_iter_mut_ref() {
-    let mut set = Set::from(vec![1, 2, 3]);
+    let mut set: Set = Set::from(vec![1, 2, 3]);
 
     for value in &mut set {
         *value += 1;
@@ -1177,7 +1378,7 @@ fn test_into_iter_mut_ref() {
 
 #[test]
 fn comparison() {
-    let mut set = Set::with_capacity(1_000_000);
+    let mut set: Set = Set::with_capacity(1_000_000);
     let mut std_set = HashSet::new();
     let mut rng = WyRand::new();
     for iteration in 0..10000 {
@@ -1230,8 +1431,8 @@ fn comparison() {
 fn test_max_element_reached() {
     // Create sets with maximum element reached
     let max_element = MAX_CAPACITY / 3000 - 1; // Assuming MAX_CAPACITY is defined somewhere
-    let set1 = Set::from_iter(0..max_element);
-    let set2 = Set::from_iter((max_element - 4)..=max_element);
+    let set1: Set = Set::from_iter(0..max_element);
+    let set2: Set = Set::from_iter((max_element - 4)..=max_element);
 
     // Perform set operations
     let union = set1.union(&set2);
@@ -1279,8 +1480,8 @@ fn test_randomized_operations() {
 
 #[test]
 fn test_bit_or_sets() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     let result = &set1 | &set2;
 
@@ -1298,7 +1499,7 @@ fn test_bit_or_sets() {
 
 #[test]
 fn test_bit_or_set_and_hashset() {
-    let set = Set::from_iter(1..=5);
+    let set: Set = Set::from_iter(1..=5);
     let hash_set = (4..=8).collect::<HashSet<_>>();
 
     let result = &set | &hash_set;
@@ -1317,8 +1518,8 @@ fn test_bit_or_set_and_hashset() {
 
 #[test]
 fn test_bit_or_assignment_sets() {
-    let mut set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let mut set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     set1 |= &set2;
 
@@ -1336,7 +1537,7 @@ fn test_bit_or_assignment_sets() {
 
 #[test]
 fn test_bit_or_assignment_set_and_hashset() {
-    let mut set = Set::from_iter(1..=5);
+    let mut set: Set = Set::from_iter(1..=5);
     let hash_set = (4..=8).collect::<HashSet<_>>();
     set |= &hash_set;
 
@@ -1355,8 +1556,8 @@ fn test_bit_or_assignment_set_and_hashset() {
 #[test]
 fn test_overlapping_ranges() {
     // Create two sets with overlapping ranges
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     // Perform set operations
     let union = set1.union(&set2);
@@ -1374,9 +1575,9 @@ fn test_overlapping_ranges() {
 #[test]
 fn test_nested_set_operations() {
     // Create sets
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
-    let set3 = Set::from_iter(6..=10);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
+    let set3: Set = Set::from_iter(6..=10);
 
     // Perform set operations
     let union = set1.union(&set2);
@@ -1388,8 +1589,8 @@ fn test_nested_set_operations() {
 
 #[test]
 fn test_union() {
-    let set1 = Set::from_iter(1..=5);
-    let set2 = Set::from_iter(4..=8);
+    let set1: Set = Set::from_iter(1..=5);
+    let set2: Set = Set::from_iter(4..=8);
 
     let union = set1.union(&set2);
 
@@ -1401,7 +1602,7 @@ fn test_union() {
 
 #[test]
 fn test_current_max_min_tracking() {
-    let mut set = Set::with_max(100);
+    let mut set: Set = Set::with_max(100);
 
     // Test empty set
     assert_eq!(set.current_max, None);
@@ -1438,7 +1639,7 @@ fn test_current_max_min_tracking() {
 
 #[test]
 fn test_remove_largest_smallest() {
-    let mut set = Set::with_max(100);
+    let mut set: Set = Set::with_max(100);
 
     // Test with unsorted insertion
     set.insert(30);
@@ -1466,8 +1667,8 @@ fn sampling_is_uniformly_at_random() {
     const EDGE_OF_THE_UNIVERSE: usize = 10000;
 
     let elements = (1..=EDGE_OF_THE_UNIVERSE).collect::<Vec<_>>();
-    let set = Set::from(elements.clone());
-    let mut rng = WyRand::new_seed(42u64);
+    let set: Set = Set::from(elements.clone());
+    let mut rng = ChaCha8Rng::seed_from_u64(42);
     let mut counts = vec![0f64; elements.len()];
 
     for _ in 0..SAMPLES {
@@ -1491,3 +1692,1654 @@ fn sampling_is_uniformly_at_random() {
         acceptable,
     );
 }
+
+#[test]
+fn word_level_set_algebra_matches_hashset() {
+    let mut rng = WyRand::new_seed(7u64);
+    for _ in 0..20 {
+        let a: Vec<usize> = (0..300).map(|_| rng.generate_range(0..1000usize)).collect();
+        let b: Vec<usize> = (0..300).map(|_| rng.generate_range(0..1500usize)).collect();
+        let (set_a, set_b): (Set, Set) = (Set::from(a.clone()), Set::from(b.clone()));
+        let (hash_a, hash_b): (HashSet<usize>, HashSet<usize>) =
+            (a.into_iter().collect(), b.into_iter().collect());
+
+        assert_eq!(
+            &set_a | &set_b,
+            hash_a.union(&hash_b).copied().collect::<HashSet<_>>()
+        );
+        assert_eq!(
+            &set_a & &set_b,
+            hash_a
+                .intersection(&hash_b)
+                .copied()
+                .collect::<HashSet<_>>()
+        );
+        assert_eq!(
+            &set_a - &set_b,
+            hash_a.difference(&hash_b).copied().collect::<HashSet<_>>()
+        );
+        assert_eq!(
+            &set_a ^ &set_b,
+            hash_a
+                .symmetric_difference(&hash_b)
+                .copied()
+                .collect::<HashSet<_>>()
+        );
+        assert_eq!(set_a.is_subset(&set_b), hash_a.is_subset(&hash_b));
+        assert_eq!(set_a.is_disjoint(&set_b), hash_a.is_disjoint(&hash_b));
+    }
+}
+
+#[test]
+fn word_level_union_rebuilds_positions() {
+    let set1: Set = Set::from(vec![130, 1, 64]);
+    let set2: Set = Set::from(vec![63, 200]);
+    let mut union = &set1 | &set2;
+
+    assert_eq!(union.elements, vec![1, 63, 64, 130, 200]);
+    assert_eq!(union.min(), Some(1));
+    assert_eq!(union.max(), Some(200));
+    assert!(union.remove(&64));
+    assert!(union.remove(&200));
+    assert_eq!(union.max(), Some(130));
+    assert!(!union.contains(&64));
+    assert_eq!(union.len(), 3);
+}
+
+#[test]
+fn range_cardinality_across_words() {
+    let set: Set = Set::from_iter((0..500).step_by(3));
+    for (start, end) in [(0, 1), (0, 64), (63, 65), (1, 499), (64, 128), (100, 1000)] {
+        let expected = (start..end.min(500)).filter(|v| v % 3 == 0).count();
+        assert_eq!(set.range_cardinality(start..end), expected);
+    }
+}
+
+#[test]
+fn shrink_to_clears_truncated_slots() {
+    let mut set: Set = Set::from(vec![3, 70]);
+    set.remove(&70);
+    set.shrink_to(5);
+    set.reserve(100);
+    assert!(!set.contains(&70));
+    assert_eq!(set.range_cardinality(..), 1);
+}
+
+#[test]
+fn remove_smallest_drains_in_ascending_order() {
+    let mut rng = WyRand::new_seed(11u64);
+    let values: Vec<usize> = (0..2000)
+        .map(|_| rng.generate_range(0..300_000usize))
+        .collect();
+    let mut set: Set = Set::from(values.clone());
+    let mut expected: Vec<usize> = values
+        .into_iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    expected.sort_unstable();
+
+    let drained: Vec<usize> = std::iter::from_fn(|| set.remove_smallest()).collect();
+    assert_eq!(drained, expected);
+    assert_eq!(set.min(), None);
+    assert_eq!(set.max(), None);
+}
+
+#[test]
+fn remove_largest_drains_in_descending_order() {
+    let mut set: Set = Set::from_iter((0..270_000).step_by(997));
+    let mut expected: Vec<usize> = (0..270_000).step_by(997).collect();
+    expected.reverse();
+
+    let drained: Vec<usize> = std::iter::from_fn(|| set.remove_largest()).collect();
+    assert_eq!(drained, expected);
+    assert!(set.is_empty());
+}
+
+#[test]
+fn extremes_track_random_removals() {
+    let mut rng = WyRand::new_seed(5u64);
+    let mut set: Set = Set::with_max(10_000);
+    let mut reference = std::collections::BTreeSet::new();
+    for _ in 0..20_000 {
+        let value = rng.generate_range(0..10_000usize);
+        if rng.generate::<bool>() {
+            set.insert(value);
+            reference.insert(value);
+        } else {
+            set.remove(&value);
+            reference.remove(&value);
+        }
+        assert_eq!(set.min(), reference.first().copied());
+        assert_eq!(set.max(), reference.last().copied());
+    }
+}
+
+#[test]
+fn extremes_survive_growth_and_shrinking() {
+    let mut set: Set = Set::with_max(10);
+    set.insert(3);
+    set.insert(5000);
+    set.insert(300_000);
+    assert_eq!(set.remove_largest(), Some(300_000));
+    assert_eq!(set.max(), Some(5000));
+    set.shrink_to_fit();
+    assert_eq!(set.remove_largest(), Some(5000));
+    assert_eq!(set.max(), Some(3));
+    assert_eq!(set.remove_smallest(), Some(3));
+    assert_eq!(set.min(), None);
+}
+
+#[test]
+fn navigation_matches_btreeset() {
+    let mut rng = WyRand::new_seed(3u64);
+    let values: Vec<usize> = (0..500)
+        .map(|_| rng.generate_range(0..20_000usize))
+        .collect();
+    let set: Set = Set::from(values.clone());
+    let reference: std::collections::BTreeSet<usize> = values.into_iter().collect();
+
+    assert_eq!(set.first(), reference.first().copied());
+    assert_eq!(set.last(), reference.last().copied());
+    for probe in (0..21_000).step_by(37) {
+        assert_eq!(
+            set.next_after(probe),
+            reference.range(probe + 1..).next().copied()
+        );
+        assert_eq!(
+            set.prev_before(probe),
+            reference.range(..probe).next_back().copied()
+        );
+    }
+}
+
+#[test]
+fn navigation_at_edges() {
+    let set: Set = Set::from(vec![0, 64, 4096]);
+    assert_eq!(set.prev_before(0), None);
+    assert_eq!(set.next_after(usize::MAX), None);
+    assert_eq!(set.prev_before(usize::MAX), Some(4096));
+    assert_eq!(set.next_after(0), Some(64));
+    assert_eq!(set.prev_before(4096), Some(64));
+
+    let empty: Set = Set::with_max(100);
+    assert_eq!(empty.first(), None);
+    assert_eq!(empty.next_after(3), None);
+    assert_eq!(empty.prev_before(50), None);
+    assert_eq!(empty.range(..).next(), None);
+}
+
+#[test]
+fn range_matches_btreeset() {
+    let mut rng = WyRand::new_seed(9u64);
+    let values: Vec<usize> = (0..400)
+        .map(|_| rng.generate_range(0..5_000usize))
+        .collect();
+    let set: Set = Set::from(values.clone());
+    let reference: std::collections::BTreeSet<usize> = values.into_iter().collect();
+
+    for _ in 0..200 {
+        let a = rng.generate_range(0..6_000usize);
+        let b = rng.generate_range(a..6_001usize);
+        let expected: Vec<usize> = reference.range(a..b).copied().collect();
+        assert_eq!(set.range(a..b).collect::<Vec<_>>(), expected);
+        let mut reversed = expected.clone();
+        reversed.reverse();
+        assert_eq!(set.range(a..b).rev().collect::<Vec<_>>(), reversed);
+    }
+    assert_eq!(
+        set.range(..).collect::<Vec<_>>(),
+        reference.iter().copied().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn range_from_both_ends() {
+    let set: Set = Set::from_iter(60..70);
+    let mut range = set.range(61..=68);
+    assert_eq!(range.next(), Some(61));
+    assert_eq!(range.next_back(), Some(68));
+    assert_eq!(range.next_back(), Some(67));
+    assert_eq!(range.next(), Some(62));
+    assert_eq!(range.by_ref().count(), 4);
+    assert_eq!(range.next(), None);
+    assert_eq!(range.next_back(), None);
+}
+
+#[test]
+fn select_inverts_rank() {
+    let mut rng = WyRand::new_seed(21u64);
+    let values: Vec<usize> = (0..3000)
+        .map(|_| rng.generate_range(0..100_000usize))
+        .collect();
+    let mut set: Set = Set::from(values);
+    let mut sorted = set.elements.clone();
+    sorted.sort_unstable();
+
+    for indexed in [false, true] {
+        if indexed {
+            set.enable_rank_index();
+        }
+        for (k, &value) in sorted.iter().enumerate() {
+            assert_eq!(set.rank(value), k);
+            assert_eq!(set.select(k), Some(value));
+        }
+        assert_eq!(set.select(sorted.len()), None);
+        assert_eq!(set.select(usize::MAX), None);
+        assert_eq!(set.rank(usize::MAX), sorted.len());
+    }
+}
+
+#[test]
+fn rank_index_follows_mutations() {
+    let mut set: Set = Set::with_max(5000);
+    set.enable_rank_index();
+    let mut reference = std::collections::BTreeSet::new();
+    let mut rng = WyRand::new_seed(4u64);
+    for step in 0..2000 {
+        let value = rng.generate_range(0..6000usize);
+        if rng.generate::<bool>() {
+            set.insert(value);
+            reference.insert(value);
+        } else {
+            set.remove(&value);
+            reference.remove(&value);
+        }
+        if step % 50 == 0 {
+            let probe = rng.generate_range(0..6500usize);
+            assert_eq!(set.rank(probe), reference.range(..probe).count());
+            let k = rng.generate_range(0..reference.len() + 1);
+            assert_eq!(set.select(k), reference.iter().nth(k).copied());
+        }
+    }
+    set.shrink_to_fit();
+    assert_eq!(set.rank(usize::MAX), reference.len());
+    assert!(set.has_rank_index());
+}
+
+#[test]
+fn rank_index_survives_set_algebra() {
+    let mut set: Set = Set::from_iter((0..10_000).step_by(3));
+    set.enable_rank_index();
+    let other: Set = Set::from_iter(5_000..20_000);
+    let hash: HashSet<usize> = (5_000..20_000).collect();
+
+    for result in [
+        &set | &other,
+        &set & &other,
+        &set - &other,
+        &set ^ &other,
+        &set | &hash,
+        &set & &hash,
+        &set - &hash,
+        &set ^ &hash,
+    ] {
+        assert!(result.has_rank_index());
+        let k = result.len() / 2;
+        assert_eq!(result.rank(result.select(k).unwrap()), k);
+    }
+    let mut assigned = set.clone();
+    assigned |= &other;
+    assigned -= &hash;
+    assert!(assigned.has_rank_index());
+    assert!(!(&other | &set).has_rank_index());
+}
+
+#[test]
+fn select_on_empty_set() {
+    let mut set: Set = Set::with_max(100);
+    assert_eq!(set.select(0), None);
+    set.enable_rank_index();
+    assert_eq!(set.select(0), None);
+    assert_eq!(set.rank(50), 0);
+}
+
+#[test]
+fn iter_sorted_is_ascending_after_removals() {
+    let mut rng = WyRand::new_seed(13u64);
+    let mut set: Set = Set::with_max(50_000);
+    let mut reference = std::collections::BTreeSet::new();
+    for _ in 0..5000 {
+        let value = rng.generate_range(0..50_000usize);
+        set.insert(value);
+        reference.insert(value);
+    }
+    for _ in 0..2000 {
+        let value = rng.generate_range(0..50_000usize);
+        set.remove(&value);
+        reference.remove(&value);
+    }
+
+    let expected: Vec<usize> = reference.iter().copied().collect();
+    assert_eq!(set.iter_sorted().len(), expected.len());
+    assert_eq!(set.iter_sorted().collect::<Vec<_>>(), expected);
+    assert_eq!(
+        set.iter_sorted().rev().collect::<Vec<_>>(),
+        expected.iter().rev().copied().collect::<Vec<_>>()
+    );
+    assert_eq!(set.into_sorted_iter().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn sorted_iterators_track_remaining_length() {
+    let set: Set = Set::from(vec![64, 0, 128, 63]);
+    let mut iter = set.iter_sorted();
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(128));
+    assert_eq!(iter.len(), 2);
+
+    let mut owned = set.clone().into_sorted_iter();
+    assert_eq!(owned.next_back(), Some(128));
+    assert_eq!(owned.next_back(), Some(64));
+    assert_eq!(owned.len(), 2);
+    assert_eq!(owned.collect::<Vec<_>>(), vec![0, 63]);
+
+    let empty: Set = Set::with_max(10);
+    assert_eq!(empty.iter_sorted().next(), None);
+    assert_eq!(empty.into_sorted_iter().next_back(), None);
+}
+
+#[test]
+fn u32_set_mirrors_usize_set() {
+    let mut rng = WyRand::new_seed(17u64);
+    let mut narrow: Set<u32> = Set::with_max(20_000);
+    let mut wide: Set = Set::with_max(20_000);
+    for _ in 0..10_000 {
+        let value = rng.generate_range(0..30_000u32);
+        if rng.generate_range(0..3u8) == 0 {
+            assert_eq!(narrow.remove(&value), wide.remove(&(value as usize)));
+        } else {
+            assert_eq!(narrow.insert(value), wide.insert(value as usize));
+        }
+    }
+
+    assert_eq!(narrow.len(), wide.len());
+    assert_eq!(narrow.min().map(|v| v as usize), wide.min());
+    assert_eq!(narrow.max().map(|v| v as usize), wide.max());
+    assert_eq!(narrow.rank(12_345), wide.rank(12_345));
+    assert_eq!(narrow.select(100).map(|v| v as usize), wide.select(100));
+    assert_eq!(
+        narrow
+            .range(1_000..2_000)
+            .map(|v| v as usize)
+            .collect::<Vec<_>>(),
+        wide.range(1_000..2_000).collect::<Vec<_>>()
+    );
+    assert!(narrow.iter().all(|&v| wide.contains(&(v as usize))));
+}
+
+#[test]
+fn u16_set_spans_its_whole_domain() {
+    let mut set: Set<u16> = (0..=u16::MAX).collect();
+    assert_eq!(set.len(), 1 << 16);
+    assert_eq!(set.capacity(), u16::MAX);
+    assert_eq!(set.max(), Some(u16::MAX));
+
+    // Positions near the top of the domain must still round-trip through the pages
+    for value in (0..=u16::MAX).step_by(3) {
+        assert!(set.remove(&value));
+    }
+    assert!(set.iter().all(|&v| v % 3 != 0));
+    assert_eq!(set.len(), (1 << 16) - 21_846);
+    assert_eq!(set.max(), Some(u16::MAX - 1));
+    assert_eq!(set.next_after(u16::MAX - 1), None);
+    assert_eq!(set.remove_smallest(), Some(1));
+}
+
+#[test]
+fn u64_set_algebra_and_conversions() {
+    let a: Set<u64> = Set::from(vec![1, 5, 9, 400]);
+    let b: HashSet<u64> = [5, 9, 12].into_iter().collect();
+    let c: Set<u64> = Set::from(&b);
+
+    assert_eq!(&a | &b, Set::from(vec![1, 5, 9, 12, 400]));
+    assert_eq!(&a & &c, Set::from(vec![5, 9]));
+    assert_eq!(&a - &c, Set::from(vec![1, 400]));
+    assert_eq!(&a ^ &b, Set::from(vec![1, 12, 400]));
+    assert!(Set::<u64>::from(vec![5, 9]).is_subset(&b));
+    assert_eq!(c, b);
+    assert_eq!(a.iter_sorted().collect::<Vec<u64>>(), vec![1, 5, 9, 400]);
+}
+
+#[test]
+fn page_sizes_agree_with_default() {
+    fn churn<const PAGE: usize>(seed: u64) -> Vec<usize> {
+        let mut rng = WyRand::new_seed(seed);
+        let mut set: Set<usize, PAGE> = Set::with_max(5_000);
+        for _ in 0..4_000 {
+            let value = rng.generate_range(0..20_000usize);
+            if rng.generate_range(0..3u8) == 0 {
+                set.remove(&value);
+            } else {
+                set.insert(value);
+            }
+        }
+        // Removing every element exercises the position pages on each swap
+        let mut drained = Vec::with_capacity(set.len());
+        while let Some(value) = set.remove_smallest() {
+            drained.push(value);
+        }
+        drained
+    }
+
+    let expected = churn::<16>(23);
+    assert_eq!(churn::<1>(23), expected);
+    assert_eq!(churn::<4>(23), expected);
+    assert_eq!(churn::<64>(23), expected);
+    assert_eq!(churn::<1024>(23), expected);
+}
+
+#[test]
+fn set_algebra_across_page_sizes() {
+    let a: Set<u32, 4> = Set::from(vec![1, 70, 300]);
+    let b: Set<u32, 256> = Set::from(vec![70, 300, 512]);
+
+    assert_eq!(a.union(&b), Set::from(vec![1, 70, 300, 512]));
+    assert_eq!(a.intersection(&b), Set::from(vec![70, 300]));
+    assert!(!a.is_subset(&b));
+    assert!(a.intersection(&b).is_subset(&b));
+    assert!(b.is_superset(&a.intersection(&b)));
+}
+
+#[test]
+fn indicator_chunks_are_allocated_on_demand() {
+    let mut set: Set = Set::with_max(MAX_CAPACITY);
+    assert_eq!(set.indicator.allocated_chunks(), 0);
+
+    set.insert(MAX_CAPACITY);
+    set.insert(MAX_CAPACITY - 1);
+    assert_eq!(set.indicator.allocated_chunks(), 1);
+    set.insert(3);
+    assert_eq!(set.indicator.allocated_chunks(), 2);
+
+    // Emptying a chunk frees it again
+    set.remove(&MAX_CAPACITY);
+    assert_eq!(set.indicator.allocated_chunks(), 2);
+    set.remove(&(MAX_CAPACITY - 1));
+    assert_eq!(set.indicator.allocated_chunks(), 1);
+    set.remove(&3);
+    assert_eq!(set.indicator.allocated_chunks(), 0);
+    assert!(set.positions.is_empty());
+
+    set.insert(12_345);
+    set.clear();
+    assert_eq!(set.indicator.allocated_chunks(), 0);
+    assert!(set.positions.is_empty());
+    assert_eq!(set.max(), None);
+}
+
+#[test]
+fn navigation_skips_absent_chunks() {
+    let values = [0, 65_535, 65_536, 3_000_000, 3_000_063, 40_000_000];
+    let mut set: Set = Set::with_max(50_000_000);
+    for &value in &values {
+        set.insert(value);
+    }
+    assert_eq!(set.indicator.allocated_chunks(), 4);
+
+    assert_eq!(set.next_after(65_536), Some(3_000_000));
+    assert_eq!(set.prev_before(40_000_000), Some(3_000_063));
+    assert_eq!(set.next_after(40_000_000), None);
+    assert_eq!(set.iter_sorted().collect::<Vec<_>>(), values);
+    assert_eq!(
+        set.range(1..=3_000_000).rev().collect::<Vec<_>>(),
+        vec![3_000_000, 65_536, 65_535]
+    );
+    assert_eq!(set.range_cardinality(65_535..40_000_000), 4);
+
+    for enabled in [false, true] {
+        if enabled {
+            set.enable_rank_index();
+        }
+        for (k, &value) in values.iter().enumerate() {
+            assert_eq!(set.rank(value), k);
+            assert_eq!(set.select(k), Some(value));
+        }
+        assert_eq!(set.rank(50_000_000), values.len());
+        assert_eq!(set.select(values.len()), None);
+    }
+}
+
+#[test]
+fn algebra_and_shrinking_across_sparse_chunks() {
+    let a: Set = Set::from(vec![1, 200_000, 9_000_000]);
+    let b: Set = Set::from(vec![200_000, 5_000_000]);
+
+    assert_eq!(
+        a.union(&b),
+        Set::from(vec![1, 200_000, 5_000_000, 9_000_000])
+    );
+    assert_eq!(a.intersection(&b), Set::from(vec![200_000]));
+    assert_eq!(a.difference(&b).indicator.allocated_chunks(), 2);
+    assert!(a.intersection(&b).is_subset(&b));
+    assert!(!a.is_subset(&b));
+
+    let mut c = a.clone();
+    c.remove(&9_000_000);
+    c.shrink_to_fit();
+    assert_eq!(c.indicator.allocated_chunks(), 2);
+    assert_eq!(c.iter_sorted().collect::<Vec<_>>(), vec![1, 200_000]);
+    assert_eq!(c.next_after(1), Some(200_000));
+}
+
+#[test]
+fn sparse_churn_matches_btreeset() {
+    let mut rng = WyRand::new_seed(41u64);
+    let mut set: Set = Set::with_max(2_000_000);
+    set.enable_rank_index();
+    let mut reference = std::collections::BTreeSet::new();
+    for _ in 0..3_000 {
+        // Cluster values into a handful of chunks so most of the universe stays absent
+        let value = rng.generate_range(0..8usize) * 250_000 + rng.generate_range(0..70_000usize);
+        if rng.generate_range(0..3u8) == 0 {
+            assert_eq!(set.remove(&value), reference.remove(&value));
+        } else {
+            assert_eq!(set.insert(value), reference.insert(value));
+        }
+    }
+
+    assert_eq!(
+        set.iter_sorted().collect::<Vec<_>>(),
+        reference.iter().copied().collect::<Vec<_>>()
+    );
+    for probe in (0..2_000_000).step_by(4_999) {
+        assert_eq!(
+            set.next_after(probe),
+            reference.range(probe + 1..).next().copied()
+        );
+        assert_eq!(
+            set.prev_before(probe),
+            reference.range(..probe).next_back().copied()
+        );
+        assert_eq!(set.rank(probe), reference.range(..probe).count());
+        assert_eq!(
+            set.range_cardinality(probe..probe + 100_000),
+            reference.range(probe..probe + 100_000).count()
+        );
+    }
+    for (k, &value) in reference.iter().enumerate().step_by(7) {
+        assert_eq!(set.select(k), Some(value));
+    }
+}
+
+#[test]
+fn hybrid_containers_follow_density() {
+    const BLOCK: usize = 1 << 16;
+    let mut rng = WyRand::new_seed(5u64);
+    let mut set: Set = Set::with_max(4 * BLOCK);
+    set.enable_hybrid_containers();
+    assert!(set.has_hybrid_containers());
+
+    set.extend((0..BLOCK).step_by(997));
+    set.extend(BLOCK + 100..2 * BLOCK - 100);
+    while set.range_cardinality(2 * BLOCK..3 * BLOCK) < 30_000 {
+        set.insert(2 * BLOCK + rng.generate_range(0..BLOCK));
+    }
+    let kinds = set.indicator.container_kinds();
+    assert_eq!(kinds.get("array"), Some(&1));
+    assert_eq!(kinds.get("runs"), Some(&1));
+    assert_eq!(kinds.get("bitmap"), Some(&1));
+
+    // Thinning the bitmap block turns it back into an array
+    let dense: Vec<usize> = set.range(2 * BLOCK..3 * BLOCK).collect();
+    for value in &dense[2_000..] {
+        set.remove(value);
+    }
+    assert_eq!(set.indicator.container_kinds().get("array"), Some(&2));
+
+    // Punching holes into the run block splits it until an array is smaller
+    for value in (BLOCK + 100..2 * BLOCK - 100).step_by(2) {
+        set.remove(&value);
+    }
+    assert_eq!(set.indicator.container_kinds().get("runs"), None);
+    assert_eq!(set.range_cardinality(BLOCK..2 * BLOCK), (BLOCK - 200) / 2);
+
+    set.disable_hybrid_containers();
+    assert_eq!(set.indicator.container_kinds().get("bitmap"), Some(&3));
+    assert_eq!(set.range_cardinality(BLOCK..2 * BLOCK), (BLOCK - 200) / 2);
+}
+
+#[test]
+fn set_algebra_results_keep_hybrid_containers() {
+    let mut set: Set = Set::from_iter((0..200_000).step_by(1_000));
+    set.enable_hybrid_containers();
+    let hash: HashSet<usize> = (0..50_000).step_by(500).collect();
+    let tree: BTreeSet<usize> = hash.iter().copied().collect();
+
+    for result in [
+        &set | &hash,
+        &set & &hash,
+        &set - &tree,
+        &set ^ &tree,
+        &set | (0..10usize),
+        &set | &set,
+    ] {
+        assert!(result.has_hybrid_containers());
+        assert_eq!(result.indicator.container_kinds().get("bitmap"), None);
+    }
+}
+
+#[test]
+fn hybrid_churn_matches_btreeset() {
+    let mut rng = WyRand::new_seed(17u64);
+    let mut set: Set = Set::with_max(1 << 20);
+    set.enable_hybrid_containers();
+    let mut plain: Set = Set::with_max(1 << 20);
+    let mut reference = std::collections::BTreeSet::new();
+    for round in 0..60_000 {
+        // Blocks of increasing density, with one block filled in long runs
+        let block = rng.generate_range(0..6usize);
+        let value = match block {
+            5 => (block << 16) + (round % 40_000),
+            _ => (block << 16) + rng.generate_range(0..(1usize << (10 + 2 * block)).min(1 << 16)),
+        };
+        if block != 5 && rng.generate_range(0..4u8) == 0 {
+            assert_eq!(set.remove(&value), reference.remove(&value));
+            plain.remove(&value);
+        } else {
+            assert_eq!(set.insert(value), reference.insert(value));
+            plain.insert(value);
+        }
+    }
+    assert!(set.indicator.container_kinds().len() > 1);
+    assert_eq!(set, plain);
+
+    set.enable_rank_index();
+    assert_eq!(
+        set.iter_sorted().collect::<Vec<_>>(),
+        reference.iter().copied().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        set.iter_sorted().rev().collect::<Vec<_>>(),
+        reference.iter().rev().copied().collect::<Vec<_>>()
+    );
+    for probe in (0..(6 << 16)).step_by(997) {
+        assert_eq!(set.contains(&probe), reference.contains(&probe));
+        assert_eq!(
+            set.next_after(probe),
+            reference.range(probe + 1..).next().copied()
+        );
+        assert_eq!(
+            set.prev_before(probe),
+            reference.range(..probe).next_back().copied()
+        );
+        assert_eq!(set.rank(probe), reference.range(..probe).count());
+        assert_eq!(
+            set.range_cardinality(probe..probe + 3_000),
+            reference.range(probe..probe + 3_000).count()
+        );
+    }
+    for (k, &value) in reference.iter().enumerate().step_by(13) {
+        assert_eq!(set.select(k), Some(value));
+    }
+    let mut draws = ChaCha8Rng::seed_from_u64(17);
+    for _ in 0..1_000 {
+        let value = set.random(&mut draws).unwrap();
+        assert!(reference.contains(&value));
+    }
+
+    // Algebra between hybrid and plain sets agrees with plain-only algebra
+    let other: Set = Set::from_iter((0..(6 << 16)).step_by(3));
+    assert_eq!(set.intersection(&other), plain.intersection(&other));
+    assert_eq!(set.difference(&other), plain.difference(&other));
+    assert!(set.intersection(&other).has_hybrid_containers());
+    assert!(set.intersection(&other).is_subset(&plain));
+
+    set.shrink_to_fit();
+    assert_eq!(set, plain);
+}
+
+#[test]
+fn ceiling_defaults_to_the_element_range() {
+    assert_eq!(Set::<u16>::with_max(10).ceiling(), u16::MAX);
+    assert_eq!(Set::<u32>::with_max(10).ceiling(), u32::MAX);
+    assert_eq!(Set::<usize>::with_max(10).ceiling(), usize::MAX - 1);
+
+    // Values past the old crate-wide limit of 10⁹ are stored, not dropped
+    let mut set: Set = Set::with_max(10);
+    assert!(set.insert(3_000_000_000));
+    assert!(!set.insert(3_000_000_000));
+    assert!(set.contains(&3_000_000_000));
+    assert_eq!(set.max(), Some(3_000_000_000));
+
+    let mut wide: Set<u64> = Set::with_max(10);
+    assert!(wide.insert(u64::MAX >> 8));
+    assert_eq!(wide.iter_sorted().collect::<Vec<_>>(), vec![u64::MAX >> 8]);
+}
+
+#[test]
+fn with_ceiling_bounds_growth() {
+    let mut set: Set = Set::with_max(100).with_ceiling(1_000);
+    assert_eq!(set.ceiling(), 1_000);
+    assert!(set.insert(1_000));
+    set.reserve(1_000);
+    assert_eq!(set.max_value(), 1_000);
+
+    // Shrinking and cloning keep the ceiling
+    set.shrink_to_fit();
+    assert_eq!(set.clone().ceiling(), 1_000);
+}
+
+#[test]
+fn shrink_to_clamps_to_the_ceiling() {
+    let mut set: Set = Set::with_max(10).with_ceiling(100);
+    set.insert(5);
+    set.shrink_to(1_000);
+    assert_eq!(set.max_value(), 100);
+    let json = serde_json::to_string(&set).unwrap();
+    let back: Set = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, set);
+
+    let mut set: Set = Set::from(vec![1, 2, 3]);
+    set.shrink_to(usize::MAX);
+    assert_eq!(set.max_value(), set.ceiling());
+    assert!(set.contains(&3));
+}
+
+#[test]
+fn set_algebra_keeps_the_ceiling() {
+    let set: Set = Set::from_iter(0..100).with_ceiling(1_000);
+    let wide: Set = Set::from_iter(50..900);
+    let far: Set = Set::from_iter([5_000]);
+    let hash: HashSet<usize> = (50..900).collect();
+
+    for result in [
+        &set | &wide,
+        &set & &far,
+        &set - &far,
+        &set ^ &wide,
+        &set | &hash,
+        &set & &hash,
+        &set - (0..5_000),
+        &set ^ &hash,
+    ] {
+        assert_eq!(result.ceiling(), 1_000);
+        assert!(result.max_value() <= 1_000);
+    }
+
+    let mut assigned = set.clone();
+    assigned |= &wide;
+    assigned &= &hash;
+    assigned ^= 0..10;
+    assigned -= &far;
+    assert_eq!(assigned.ceiling(), 1_000);
+    assert_eq!(assigned, Set::from_iter((0..10).chain(50..900)));
+}
+
+#[test]
+#[should_panic(expected = "above the set's ceiling")]
+fn union_above_ceiling_panics() {
+    let set: Set = Set::from_iter(0..100).with_ceiling(1_000);
+    let _ = &set | &Set::from_iter([1_001]);
+}
+
+#[test]
+#[should_panic(expected = "above the set's ceiling")]
+fn union_assign_above_ceiling_panics() {
+    let mut set: Set = Set::from_iter(0..100).with_ceiling(1_000);
+    set |= &HashSet::from([1_001]);
+}
+
+#[test]
+#[should_panic(expected = "above the set's ceiling")]
+fn insert_above_ceiling_panics() {
+    let mut set: Set = Set::with_max(100).with_ceiling(1_000);
+    set.insert(1_001);
+}
+
+#[test]
+#[should_panic(expected = "above the set's ceiling")]
+fn reserve_above_ceiling_panics() {
+    let mut set: Set<u32> = Set::with_max(100).with_ceiling(1_000);
+    set.reserve(5_000);
+}
+
+#[test]
+#[should_panic(expected = "below the set's maximum value")]
+fn ceiling_below_max_panics() {
+    let _set: Set = Set::with_max(100).with_ceiling(99);
+}
+
+#[test]
+fn try_insert_tells_outcomes_apart() {
+    let mut set: Set<u32> = Set::with_max(10).with_ceiling(1_000);
+
+    assert_eq!(set.try_insert(5), Ok(InsertOutcome::Inserted));
+    assert_eq!(set.try_insert(5), Ok(InsertOutcome::AlreadyPresent));
+    assert_eq!(set.try_insert(1_000), Ok(InsertOutcome::Inserted));
+    assert_eq!(
+        set.try_insert(1_001),
+        Err(Error::OutOfRange {
+            value: 1_001,
+            ceiling: 1_000
+        })
+    );
+
+    assert_eq!(set.iter_sorted().collect::<Vec<_>>(), vec![5, 1_000]);
+    assert_eq!(set.max_value(), 1_000);
+    assert_eq!(set.positions.get(1_000), Some(1));
+}
+
+#[test]
+fn try_reserve_and_try_with_max_report_out_of_range() {
+    let mut set: Set = Set::try_with_max(100).unwrap().with_ceiling(500);
+    assert_eq!(set.try_reserve(500), Ok(()));
+    assert_eq!(set.max_value(), 500);
+    assert!(matches!(
+        set.try_reserve(501),
+        Err(Error::OutOfRange { value: 501, .. })
+    ));
+    assert_eq!(set.max_value(), 500);
+
+    assert!(matches!(
+        Set::<usize>::try_with_max(usize::MAX),
+        Err(Error::OutOfRange { .. })
+    ));
+    let error = Set::<usize>::try_from_iter([1, usize::MAX]).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "value {} is above the set's ceiling {}",
+            usize::MAX,
+            usize::MAX - 1
+        )
+    );
+}
+
+#[test]
+fn try_from_usize_rejects_ids_that_do_not_fit() {
+    let set = Set::<u16>::try_from(vec![3usize, 1, 65_535]).unwrap();
+    assert_eq!(set.iter_sorted().collect::<Vec<_>>(), vec![1, 3, 65_535]);
+
+    assert_eq!(
+        Set::<u16>::try_from(&[1usize, 65_536][..]).err(),
+        Some(Error::OutOfRange {
+            value: 65_536,
+            ceiling: 65_535
+        })
+    );
+
+    let huge: Vec<usize> = vec![0, 1 << 40];
+    assert!(Set::<u32>::try_from(huge.clone()).is_err());
+    let set = Set::<u64>::try_from(huge).unwrap();
+    assert!(set.contains(&(1 << 40)));
+
+    // Set<usize> has no fallible TryFrom of its own, but try_from_values
+    assert_eq!(
+        Set::<usize>::try_from_values(&[1, usize::MAX]).err(),
+        Some(Error::OutOfRange {
+            value: usize::MAX as u64,
+            ceiling: (usize::MAX - 1) as u64
+        })
+    );
+    let set = Set::<usize>::try_from_values(&[5, 1 << 40]).unwrap();
+    assert_eq!(set.iter_sorted().collect::<Vec<_>>(), vec![5, 1 << 40]);
+}
+
+#[test]
+fn try_insert_allocates_the_chunk_it_fills() {
+    for hybrid in [false, true] {
+        let mut set: Set = Set::with_max(10);
+        if hybrid {
+            set.enable_hybrid_containers();
+        }
+        for value in [3, 1 << 20, 1 << 40, 5 << 20] {
+            assert_eq!(set.try_insert(value), Ok(InsertOutcome::Inserted));
+        }
+        assert_eq!(set.indicator.allocated_chunks(), 4);
+        assert_eq!(set.next_after(3), Some(1 << 20));
+        assert_eq!(set.next_after(1 << 20), Some(5 << 20));
+        assert_eq!(set.prev_before(1 << 40), Some(5 << 20));
+    }
+}
+
+#[test]
+fn serde_round_trips_sparse_and_dense_sets() {
+    let mut sparse: Set<u64> = Set::with_max(10).with_ceiling(1 << 50);
+    for value in [7u64, 1 << 40, 3, 1 << 20] {
+        sparse.insert(value);
+    }
+    let json = serde_json::to_string(&sparse).unwrap();
+    assert!(json.contains("Elements"));
+    let back: Set<u64> = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        back.iter().copied().collect::<Vec<_>>(),
+        vec![7, 1 << 40, 3, 1 << 20]
+    );
+    assert_eq!(back.ceiling(), 1 << 50);
+    assert_eq!((back.min(), back.max()), (Some(3), Some(1 << 40)));
+    for value in [7u64, 1 << 40, 3, 1 << 20] {
+        assert_eq!(
+            back.positions.get(value as usize),
+            sparse.positions.get(value as usize)
+        );
+    }
+
+    let dense: Set = (0..50_000).filter(|v| v % 3 != 0).collect();
+    let json = serde_json::to_string(&dense).unwrap();
+    assert!(json.contains("Bitmap"));
+    assert!(json.len() < 20_000);
+    let mut back: Set = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, dense);
+    assert_eq!(back.max_value(), dense.max_value());
+    assert!(back.remove(&49_999));
+    assert!(!back.contains(&49_999));
+
+    let mut hybrid: Set<u32> = (0..10_000).map(|v| v * 7).collect();
+    hybrid.enable_hybrid_containers();
+    let back: Set<u32> = serde_json::from_str(&serde_json::to_string(&hybrid).unwrap()).unwrap();
+    assert!(back.has_hybrid_containers());
+    assert_eq!(back, hybrid);
+}
+
+#[test]
+fn serde_rejects_inconsistent_input() {
+    let rejected = [
+        r#"{"max":9,"ceiling":9,"values":{"Elements":[1,1]}}"#,
+        r#"{"max":9,"ceiling":9,"values":{"Elements":[10]}}"#,
+        r#"{"max":9,"ceiling":8,"values":{"Elements":[1]}}"#,
+        r#"{"max":65535,"ceiling":65535,"values":{"Bitmap":{"len":10,"chunks":[]}}}"#,
+        r#"{"max":9,"ceiling":9,"values":{"Bitmap":{"len":10,"chunks":[[0,{"Array":[12]}]]}}}"#,
+        r#"{"max":9,"ceiling":9,"values":{"Bitmap":{"len":10,"chunks":[[1,{"Array":[1]}]]}}}"#,
+    ];
+    for json in rejected {
+        assert!(serde_json::from_str::<Set<u16>>(json).is_err(), "{}", json);
+    }
+
+    let set: Set<u16> =
+        serde_json::from_str(r#"{"max":9,"ceiling":9,"values":{"Elements":[4,2]}}"#).unwrap();
+    assert_eq!(set.iter_sorted().collect::<Vec<_>>(), vec![2, 4]);
+    assert_eq!(set.ceiling(), 9);
+}
+
+/// Returns the `fastset::Error` wrapped in a snapshot read failure.
+fn snapshot_error(bytes: &[u8]) -> Option<Error> {
+    let error = Set::<u32>::read_from(bytes).err()?;
+    error.get_ref()?.downcast_ref::<Error>().cloned()
+}
+
+#[test]
+fn snapshot_round_trips_both_encodings() {
+    let mut rng = WyRand::new_seed(13);
+    let mut set: Set<u32> = Set::with_max(10).with_ceiling(50_000_000);
+    for _ in 0..20_000 {
+        set.insert(rng.generate_range(0..40_000_000u32));
+    }
+    for _ in 0..5_000 {
+        set.insert(rng.generate_range(1_000_000..1_060_000u32));
+    }
+    for encoding in [Encoding::Bitmap, Encoding::DeltaVarint] {
+        let mut bytes = Vec::new();
+        set.write_encoded_to(&mut bytes, encoding).unwrap();
+        let loaded: Set<u32> = Set::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(loaded, set);
+        assert_eq!(loaded.max_value(), set.max_value());
+        assert_eq!(loaded.ceiling(), 50_000_000);
+        assert!(loaded.iter().zip(loaded.iter().skip(1)).all(|(a, b)| a < b));
+    }
+
+    let mut bytes = Vec::new();
+    set.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes[7], 1, "sparse sets pick the delta encoding");
+
+    // Snapshots are portable across element widths when the values fit
+    let wide: Set<u64> = Set::read_from(bytes.as_slice()).unwrap();
+    assert!(wide.iter_sorted().eq(set.iter_sorted().map(u64::from)));
+
+    let mut dense: Set<u16> = (0..u16::MAX).filter(|v| v % 7 != 0).collect();
+    dense.enable_hybrid_containers();
+    let mut bytes = Vec::new();
+    dense.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes[7], 0, "dense sets pick the bitmap encoding");
+    let loaded: Set<u16> = Set::read_from(bytes.as_slice()).unwrap();
+    assert!(loaded.has_hybrid_containers());
+    assert_eq!(loaded, dense);
+
+    let empty: Set = Set::with_max(0);
+    let mut bytes = Vec::new();
+    empty.write_to(&mut bytes).unwrap();
+    assert!(Set::<usize>::read_from(bytes.as_slice())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn snapshot_rejects_corrupt_input() {
+    let set: Set<u32> = Set::from(vec![3, 70_000, 9]);
+    let mut bytes = Vec::new();
+    set.write_encoded_to(&mut bytes, Encoding::DeltaVarint)
+        .unwrap();
+    // Re-seals a tampered snapshot so that only the layout checks can catch it
+    let resealed = |mut bytes: Vec<u8>| {
+        bytes.truncate(bytes.len() - 4);
+        let crc = snapshot::crc32(&bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        bytes
+    };
+    assert!(Set::<u32>::read_from(resealed(bytes.clone()).as_slice()).is_ok());
+
+    let mut bad = bytes.clone();
+    bad[0] = b'X';
+    assert!(matches!(
+        snapshot_error(&bad),
+        Some(Error::InvalidSnapshot(_))
+    ));
+
+    let mut bad = bytes.clone();
+    bad[4] = 9;
+    assert_eq!(snapshot_error(&bad), Some(Error::UnsupportedVersion(9)));
+
+    let mut bad = bytes.clone();
+    bad[17] ^= 1;
+    assert_eq!(snapshot_error(&bad), Some(Error::ChecksumMismatch));
+
+    let mut bad = bytes.clone();
+    bad[34] = 0; // a zero gap repeats the previous value
+    assert!(matches!(
+        snapshot_error(&resealed(bad)),
+        Some(Error::InvalidSnapshot(_))
+    ));
+
+    // A ten-byte gap whose last byte sets bits beyond the 64th
+    let mut bad = bytes.clone();
+    bad.splice(34..35, [0x80; 9].into_iter().chain([0x02]));
+    assert_eq!(
+        snapshot_error(&resealed(bad)),
+        Some(Error::InvalidSnapshot("overlong varint"))
+    );
+
+    let mut bitmap = Vec::new();
+    set.write_encoded_to(&mut bitmap, Encoding::Bitmap).unwrap();
+    let mut bad = bitmap.clone();
+    bad[25] = 4; // claims four values
+    assert!(matches!(
+        snapshot_error(&resealed(bad)),
+        Some(Error::InvalidSnapshot(_))
+    ));
+
+    let truncated = &bytes[..bytes.len() - 6];
+    let error = Set::<u32>::read_from(truncated).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    let wide: Set<u64> = Set::from(vec![1u64 << 40]);
+    let mut bytes = Vec::new();
+    wide.write_to(&mut bytes).unwrap();
+    assert!(matches!(
+        snapshot_error(&bytes),
+        Some(Error::OutOfRange { value, .. }) if value == 1 << 40
+    ));
+}
+
+#[test]
+fn roaring_round_trips_every_container_kind() {
+    let mut set: Set<u32> = Set::with_max(0);
+    set.extend([1, 5, 9]); // array
+    set.extend((65_536..65_536 + 10_000).filter(|v| v % 3 == 0)); // bitmap
+    set.extend(131_072..200_000); // runs
+    set.extend([4_000_000_000, 4_000_000_002]); // array near the top
+    set.extend((7 << 16..8 << 16).filter(|v| v % 97 != 0)); // runs over a full block
+
+    let bytes = set.to_roaring_bytes().unwrap();
+    assert_eq!(u16::from_le_bytes([bytes[0], bytes[1]]), 12347);
+    let back: Set<u32> = Set::from_roaring_bytes(&bytes).unwrap();
+    assert_eq!(back, set);
+    assert_eq!(back.max_value(), 4_000_000_002);
+
+    let sparse: Set<u32> = Set::from(vec![10, 1 << 20, 3 << 20]);
+    let bytes = sparse.to_roaring_bytes().unwrap();
+    assert_eq!(&bytes[..4], &12346u32.to_le_bytes());
+    assert_eq!(Set::<u32>::from_roaring_bytes(&bytes).unwrap(), sparse);
+
+    let empty: Set<u32> = Set::with_max(0);
+    let bytes = empty.to_roaring_bytes().unwrap();
+    assert_eq!(bytes, [0x3a, 0x30, 0, 0, 0, 0, 0, 0]);
+    assert!(Set::<u32>::from_roaring_bytes(&bytes).unwrap().is_empty());
+}
+
+#[test]
+fn roaring_reads_run_streams_and_rejects_bad_input() {
+    // {1, 2, 3} as a single run container, without offsets
+    let runs = [0x3b, 0x30, 0, 0, 1, 0, 0, 2, 0, 1, 0, 1, 0, 2, 0];
+    let set: Set = Set::from_roaring_bytes(&runs).unwrap();
+    assert_eq!(set.iter_sorted().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    let array = [
+        0x3a, 0x30, 0, 0, 1, 0, 0, 0, 0, 0, 2, 0, 0x10, 0, 0, 0, 1, 0, 2, 0, 3, 0,
+    ];
+    let invalid = |bytes: &[u8]| {
+        matches!(
+            Set::<u32>::from_roaring_bytes(bytes),
+            Err(Error::InvalidRoaring(_))
+        )
+    };
+    assert!(invalid(&array[..array.len() - 1]));
+    assert!(invalid(&[0, 0, 0, 0]));
+    let mut unsorted = array;
+    unsorted[18] = 1;
+    assert!(invalid(&unsorted));
+    let mut overfull = runs;
+    overfull[13] = 3;
+    assert!(invalid(&overfull));
+
+    let wide: Set<u32> = Set::from(vec![70_000]);
+    assert_eq!(
+        Set::<u16>::from_roaring_bytes(&wide.to_roaring_bytes().unwrap()),
+        Err(Error::OutOfRange {
+            value: 70_000,
+            ceiling: 65_535
+        })
+    );
+    let huge: Set<u64> = Set::from(vec![1u64 << 32]);
+    assert!(matches!(
+        huge.to_roaring_bytes(),
+        Err(Error::OutOfRange { .. })
+    ));
+}
+
+#[test]
+fn view_answers_queries_like_the_set() {
+    let mut rng = WyRand::new_seed(15);
+    let mut set: Set<u32> = Set::with_max(10);
+    for _ in 0..5_000 {
+        set.insert(rng.generate_range(0..3_000_000u32));
+    }
+    set.extend(1_000_000..1_070_000);
+    set.remove(&1_000_512);
+    set.enable_hybrid_containers();
+    let mut bytes = Vec::new();
+    set.write_view_to(&mut bytes).unwrap();
+
+    let view: SetView<u32> = SetView::new(&bytes).unwrap();
+    assert_eq!(view.len(), set.len());
+    assert_eq!(view.max_value(), set.max_value());
+    for probe in (0..3_100_000).step_by(997).chain(999_990..1_070_010) {
+        assert_eq!(view.contains(&probe), set.contains(&probe), "{}", probe);
+        assert_eq!(view.rank(probe), set.rank(probe), "{}", probe);
+    }
+    assert_eq!(view.rank(u32::MAX), set.len());
+    let mut draws = ChaCha8Rng::seed_from_u64(15);
+    for _ in 0..1_000 {
+        assert!(set.contains(&view.random(&mut draws).unwrap()));
+    }
+
+    let empty: Set<u32> = Set::with_max(0);
+    let mut bytes = Vec::new();
+    empty.write_view_to(&mut bytes).unwrap();
+    let view: SetView<u32> = SetView::new(&bytes).unwrap();
+    assert!(view.is_empty());
+    assert_eq!((view.random(&mut draws), view.rank(5)), (None, 0));
+}
+
+#[test]
+fn view_rejects_malformed_buffers() {
+    let set: Set = Set::from(vec![3, 200_000]);
+    let mut bytes = Vec::new();
+    set.write_view_to(&mut bytes).unwrap();
+    assert!(SetView::<usize>::new(&bytes).is_ok());
+
+    let invalid =
+        |bytes: &[u8]| matches!(SetView::<usize>::new(bytes), Err(Error::InvalidSnapshot(_)));
+    assert!(invalid(&bytes[..bytes.len() - 1]));
+    assert!(invalid(&bytes[..16]));
+    let mut bad = bytes.clone();
+    bad[6] = 4;
+    assert!(invalid(&bad));
+    let mut bad = bytes.clone();
+    bad[24] = 200; // claims more chunks than the buffer holds
+    assert!(invalid(&bad));
+    let mut bad = bytes.clone();
+    bad[32] = 9; // first chunk index after the second
+    assert!(invalid(&bad));
+    let mut bad = bytes;
+    bad[4] = 2;
+    assert!(matches!(
+        SetView::<usize>::new(&bad),
+        Err(Error::UnsupportedVersion(2))
+    ));
+}
+
+#[test]
+fn weighted_set_tracks_weights_through_churn() {
+    let mut rng = ChaCha8Rng::seed_from_u64(19);
+    let mut set: WeightedSet<u32> = WeightedSet::with_max(1_000);
+    let mut reference = std::collections::HashMap::new();
+    let mut draws = WyRand::new_seed(19);
+    for round in 0..20_000 {
+        let value = draws.generate_range(0..2_000u32);
+        if draws.generate_range(0..3u8) == 0 {
+            assert_eq!(set.remove(&value), reference.remove(&value));
+        } else {
+            let weight = f64::from(draws.generate_range(0..100u32)) / 8.0;
+            assert_eq!(set.insert(value, weight), reference.insert(value, weight));
+        }
+        if round % 997 == 0 {
+            let total: f64 = reference.values().sum();
+            assert!((set.total_weight() - total).abs() < 1e-6);
+            for _ in 0..100 {
+                let value = set.random(&mut rng).unwrap();
+                assert!(reference[&value] > 0.0);
+            }
+        }
+    }
+    assert_eq!(set.len(), reference.len());
+    for (value, weight) in set.iter() {
+        assert_eq!(reference.get(&value), Some(&weight));
+        assert_eq!(set.weight(&value), Some(weight));
+    }
+    assert_eq!(set.as_set().len(), reference.len());
+
+    set.clear();
+    assert!(set.is_empty());
+    assert_eq!((set.total_weight(), set.random(&mut rng)), (0.0, None));
+    set.insert(5, 0.0);
+    assert_eq!(set.random(&mut rng), None);
+}
+
+#[test]
+fn weighted_set_samples_in_proportion_to_weight() {
+    const SAMPLES: usize = 200_000;
+    let mut set: WeightedSet = WeightedSet::with_max(64);
+    for value in 0..40 {
+        set.insert(value, (value % 7) as f64);
+    }
+    // Removals swap members between slots, which must carry their weights along
+    for value in (0..40).step_by(5) {
+        set.remove(&value);
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(20);
+    let mut counts = [0f64; 40];
+    for _ in 0..SAMPLES {
+        counts[set.random(&mut rng).unwrap()] += 1.0;
+    }
+    let total = set.total_weight();
+    let mut statistic = 0.0;
+    let mut cells = 0;
+    for (value, &count) in counts.iter().enumerate() {
+        let expected = set.weight(&value).unwrap_or(0.0) / total * SAMPLES as f64;
+        if expected == 0.0 {
+            assert_eq!(count, 0.0, "{} has no weight", value);
+        } else {
+            statistic += (count - expected) * (count - expected) / expected;
+            cells += 1;
+        }
+    }
+    let chi = ChiSquared::new((cells - 1) as f64).unwrap();
+    assert!(statistic < chi.inverse_cdf(0.99), "{}", statistic);
+}
+
+#[test]
+fn weighted_set_never_draws_zero_weights() {
+    let mut rng = ChaCha8Rng::seed_from_u64(21);
+    let mut set: WeightedSet = WeightedSet::with_max(10);
+    for (value, weight) in [(0, 0.1), (1, 0.2), (2, 0.3)] {
+        set.insert(value, weight);
+    }
+    for value in 0..3 {
+        set.insert(value, 0.0);
+    }
+    assert_eq!((set.total_weight(), set.random(&mut rng)), (0.0, None));
+
+    // Subnormal and huge weights get buckets of their own
+    set.insert(4, f64::MIN_POSITIVE / 8.0);
+    for _ in 0..100 {
+        assert_eq!(set.random(&mut rng), Some(4));
+    }
+    set.insert(5, 1e300);
+    for _ in 0..100 {
+        assert_eq!(set.random(&mut rng), Some(5));
+    }
+    set.remove(&5);
+    assert_eq!(set.random(&mut rng), Some(4));
+}
+
+#[test]
+#[should_panic(expected = "must be finite and non-negative")]
+fn weighted_set_rejects_negative_weights() {
+    let mut set: WeightedSet = WeightedSet::with_max(10);
+    set.insert(1, -1.0);
+}
+
+#[test]
+fn distinct_counter_estimates_within_epsilon() {
+    // 30k distinct values, each repeated, in a stream far longer than the buffer
+    let stream: Vec<u64> = (0..300_000u64)
+        .map(|i| (i * 104_729) % 30_000 * 5)
+        .collect();
+    let mut misses = 0;
+    for seed in 0..10 {
+        let rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut counter: DistinctCounter<_, u64> =
+            DistinctCounter::new(0.2, 0.1, stream.len() as u64, rng);
+        assert!(counter.threshold() < 30_000);
+        for &value in &stream {
+            counter.feed(value);
+        }
+        let error = (counter.estimate() - 30_000.0).abs() / 30_000.0;
+        if error > 0.2 {
+            misses += 1;
+        }
+    }
+    assert!(misses <= 2, "{} of 10 estimates missed", misses);
+
+    // Below the threshold the buffer holds every distinct value
+    let mut counter: DistinctCounter<_, u32> =
+        DistinctCounter::with_threshold(1_000, ChaCha8Rng::seed_from_u64(1));
+    for value in (0..5_000).map(|i| i % 700) {
+        counter.feed(value);
+    }
+    assert_eq!(counter.estimate(), 700.0);
+}
+
+#[test]
+fn distinct_counter_accepts_scattered_and_extreme_values() {
+    let mut counter: DistinctCounter<_, u64> =
+        DistinctCounter::with_threshold(10_000, ChaCha8Rng::seed_from_u64(2));
+    let values: Vec<u64> = (0..5_000u64)
+        .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .chain([u64::MAX, u64::MAX - 1, 0])
+        .collect();
+    for &value in values.iter().chain(&values) {
+        counter.feed(value);
+    }
+    assert_eq!(counter.estimate(), 5_002.0);
+
+    // Once halving, scattered values still estimate the count
+    let mut counter: DistinctCounter<_, u64> =
+        DistinctCounter::with_threshold(1_000, ChaCha8Rng::seed_from_u64(3));
+    for i in 0..50_000u64 {
+        counter.feed(u64::MAX - i.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    }
+    assert!((35_000.0..65_000.0).contains(&counter.estimate()));
+}
+
+#[test]
+#[should_panic(expected = "must lie strictly between 0 and 1")]
+fn distinct_counter_rejects_bad_parameters() {
+    DistinctCounter::<_, u32>::new(0.1, 1.5, 100, ChaCha8Rng::seed_from_u64(1));
+}
+
+#[test]
+fn union_size_estimate_matches_the_materialized_union() {
+    let mut rng = ChaCha8Rng::seed_from_u64(22);
+    let mut data = WyRand::new_seed(22);
+    let sets: Vec<Set<u32>> = (0..12)
+        .map(|i| {
+            let start = i * 7_000;
+            (0..8_000)
+                .map(|_| data.generate_range(start..start + 20_000))
+                .collect()
+        })
+        .collect();
+    let union = sets
+        .iter()
+        .fold(Set::<u32>::with_max(0), |acc, set| &acc | set);
+    let delphic: Vec<&dyn DelphicSet<u32>> = sets.iter().map(|set| set as _).collect();
+    let mut misses = 0;
+    for _ in 0..10 {
+        let estimate = union_size_estimate(&delphic, 0.2, 0.1, &mut rng);
+        if (estimate - union.len() as f64).abs() > 0.2 * union.len() as f64 {
+            misses += 1;
+        }
+    }
+    assert!(misses <= 2, "{} of 10 estimates missed", misses);
+
+    // Small unions fit the buffer and come out exact
+    let small: Vec<Set<u32>> = vec![Set::from_iter(0..50), Set::from_iter(25..80)];
+    let delphic: Vec<&dyn DelphicSet<u32>> = small.iter().map(|set| set as _).collect();
+    assert_eq!(union_size_estimate(&delphic, 0.5, 0.5, &mut rng), 80.0);
+    assert_eq!(union_size_estimate::<u32, _>(&[], 0.5, 0.5, &mut rng), 0.0);
+}
+
+#[test]
+fn binomial_pow2_has_the_binomial_mean() {
+    let mut rng = ChaCha8Rng::seed_from_u64(23);
+    for k in [0u32, 1, 3, 6, 10, 40] {
+        let n = 1 << 16;
+        let runs = 200;
+        let total: usize = (0..runs)
+            .map(|_| random::binomial_pow2(&mut rng, n, k))
+            .sum();
+        let mean = total as f64 / runs as f64;
+        let expected = n as f64 / 2f64.powi(k as i32);
+        // Within five standard errors of the mean
+        let tolerance = 5.0 * (expected / runs as f64).sqrt() + 1e-9;
+        assert!((mean - expected).abs() <= tolerance, "k {}: {}", k, mean);
+    }
+    assert_eq!(random::binomial_pow2(&mut rng, 0, 3), 0);
+}
+
+#[test]
+fn sample_from_union_is_uniform_over_overlaps() {
+    const SAMPLES: usize = 200_000;
+    // 0..40 appears in all three sets, 40..60 in two, 60..100 in one
+    let a: Set = Set::from_iter(0..60);
+    let b: Set = Set::from_iter(0..100);
+    let c: Set = Set::from_iter(0..40);
+    let empty: Set = Set::with_max(10);
+    let mut rng = ChaCha8Rng::seed_from_u64(23);
+    let mut counts = vec![0f64; 100];
+    for _ in 0..SAMPLES {
+        counts[sample_from_union(&[&a, &empty, &b, &c], &mut rng).unwrap()] += 1.0;
+    }
+    let e = SAMPLES as f64 / 100.0;
+    let statistic: f64 = counts.iter().map(|&o| (o - e) * (o - e) / e).sum();
+    let chi = ChiSquared::new(99.0).unwrap();
+    assert!(statistic < chi.inverse_cdf(0.99), "{}", statistic);
+
+    // Trait objects mix with other Delphic sets
+    let high: Set<u32> = Set::from_iter(500..510);
+    let plain: Set<u32> = Set::from_iter(0..10);
+    let sets: [&dyn DelphicSet<u32>; 2] = [&high, &plain];
+    let value = sample_from_union(&sets, &mut rng).unwrap();
+    assert!(value < 10 || (500..510).contains(&value));
+    assert_eq!(sample_from_union(&[&empty, &empty], &mut rng), None);
+}
+
+#[test]
+fn key_set_matches_hashset_and_samples_its_keys() {
+    let mut rng = ChaCha8Rng::seed_from_u64(24);
+    let mut data = WyRand::new_seed(24);
+    let mut set: KeySet<(u8, u32)> = KeySet::new();
+    let mut reference = HashSet::new();
+    for _ in 0..20_000 {
+        let key = (
+            data.generate_range(0..4u8),
+            data.generate_range(0..2_000u32),
+        );
+        match data.generate_range(0..4u8) {
+            0 => assert_eq!(set.remove(&key), reference.remove(&key)),
+            1 => {
+                if let Some(popped) = set.pop_random(&mut rng) {
+                    assert!(reference.remove(&popped));
+                }
+            }
+            _ => assert_eq!(set.insert(key), reference.insert(key)),
+        }
+        assert_eq!(set.len(), reference.len());
+    }
+    assert_eq!(set.iter().copied().collect::<HashSet<_>>(), reference);
+    for key in &reference {
+        assert!(set.contains(key));
+    }
+    for _ in 0..1_000 {
+        assert!(reference.contains(set.random(&mut rng).unwrap()));
+    }
+    set.clear();
+    assert_eq!(set.random(&mut rng), None);
+    assert_eq!(set.pop_random(&mut rng), None);
+}
+
+#[test]
+fn delphic_estimators_accept_any_implementor() {
+    /// A fake set of every multiple of `step` below `end`.
+    struct Multiples {
+        step: u32,
+        end: u32,
+    }
+
+    impl DelphicSet<u32> for Multiples {
+        fn contains(&self, value: &u32) -> bool {
+            value.is_multiple_of(self.step) && *value < self.end
+        }
+
+        fn len(&self) -> usize {
+            self.end.div_ceil(self.step) as usize
+        }
+
+        fn sample(&self, rng: &mut dyn rand_chacha::rand_core::RngCore) -> Option<u32> {
+            let len = self.len() as u64;
+            (len > 0).then(|| self.step * (rng.next_u64() % len) as u32)
+        }
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(25);
+    let set: Set<u32> = Set::from_iter(0..40);
+    let mut bytes = Vec::new();
+    Set::<u32>::from_iter(30..60)
+        .write_view_to(&mut bytes)
+        .unwrap();
+    let view: SetView<u32> = SetView::new(&bytes).unwrap();
+    let fake = Multiples { step: 10, end: 100 };
+    let keys: KeySet<u32> = (95..100).collect();
+    let sets: [&dyn DelphicSet<u32>; 4] = [&set, &view, &fake, &keys];
+
+    // 0..60, then 60, 70, 80, 90, then 95..100: small enough to be exact
+    assert_eq!(union_size_estimate(&sets, 0.5, 0.5, &mut rng), 69.0);
+    for _ in 0..1_000 {
+        let value = sample_from_union(&sets, &mut rng).unwrap();
+        assert!(sets.iter().any(|set| set.contains(&value)));
+    }
+
+    let names: [KeySet<String>; 2] = [
+        ["ada", "alan"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        ["alan", "grace"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+    ];
+    let sets: [&dyn DelphicSet<String>; 2] = [&names[0], &names[1]];
+    assert_eq!(union_size_estimate(&sets, 0.5, 0.5, &mut rng), 3.0);
+}
+
+#[test]
+fn set_algebra_accepts_ordered_sets_ranges_and_sorted_slices() {
+    let mut rng = WyRand::new_seed(11u64);
+    for _ in 0..20 {
+        let a: Vec<usize> = (0..200).map(|_| rng.generate_range(0..600usize)).collect();
+        let b: Vec<usize> = (0..200).map(|_| rng.generate_range(0..900usize)).collect();
+        let set: Set = Set::from(a.clone());
+        let hash_a: HashSet<usize> = a.into_iter().collect();
+        let tree: BTreeSet<usize> = b.into_iter().collect();
+        let sorted: Vec<usize> = tree.iter().copied().collect();
+        let slice = SortedSlice::new(&sorted).unwrap();
+        let range = 250..700usize;
+        let hash_range: HashSet<usize> = range.clone().collect();
+        let hash_tree: HashSet<usize> = tree.iter().copied().collect();
+
+        assert_eq!(&set | &tree, &set | &hash_tree);
+        assert_eq!(&set & &tree, &set & &hash_tree);
+        assert_eq!(&set - &tree, &set - &hash_tree);
+        assert_eq!(&set ^ &tree, &set ^ &hash_tree);
+        assert_eq!(&set | slice, &set | &hash_tree);
+        assert_eq!(&set & slice, &set & &hash_tree);
+        assert_eq!(&set - slice, &set - &hash_tree);
+        assert_eq!(&set ^ slice, &set ^ &hash_tree);
+        assert_eq!(&set | range.clone(), &set | &hash_range);
+        assert_eq!(&set & range.clone(), &set & &hash_range);
+        assert_eq!(&set - range.clone(), &set - &hash_range);
+        assert_eq!(&set ^ range.clone(), &set ^ &hash_range);
+
+        assert_eq!(set.is_subset(&tree), hash_a.is_subset(&hash_tree));
+        assert_eq!(set.is_superset(&slice), hash_a.is_superset(&hash_tree));
+        assert_eq!(set.is_disjoint(&range), hash_a.is_disjoint(&hash_range));
+
+        let mut assigned = set.clone();
+        assigned |= &tree;
+        assigned -= range.clone();
+        assigned ^= slice;
+        assigned &= 0..800;
+        let mut expected = set.clone();
+        expected |= &hash_tree;
+        expected -= &hash_range;
+        expected ^= &hash_tree;
+        expected &= &(0..800).collect::<HashSet<usize>>();
+        assert_eq!(assigned, expected);
+    }
+}
+
+#[test]
+fn set_ops_implementors_report_len_max_and_values() {
+    let set: Set = Set::from(vec![3, 9, 4]);
+    let hash: HashSet<usize> = [3, 9, 4].into_iter().collect();
+    let tree: BTreeSet<usize> = [3, 9, 4].into_iter().collect();
+    let sorted = [3usize, 4, 9];
+    let slice = SortedSlice::new(&sorted).unwrap();
+
+    fn check<S: SetOps>(set: &S) {
+        assert_eq!(set.len(), 3);
+        assert!(!set.is_empty());
+        assert_eq!(set.max(), Some(9));
+        let mut values: Vec<usize> = set.iter().collect();
+        values.sort_unstable();
+        assert_eq!(values, vec![3, 4, 9]);
+        assert!(set.contains(&4) && !set.contains(&5));
+    }
+    check(&set);
+    check(&hash);
+    check(&tree);
+    check(&slice);
+
+    let range = 4..9usize;
+    assert_eq!(SetOps::len(&range), 5);
+    assert_eq!(SetOps::max(&range), Some(8));
+    assert_eq!(
+        SetOps::iter(&range).collect::<Vec<_>>(),
+        vec![4, 5, 6, 7, 8]
+    );
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = 9..4usize;
+    assert!(SetOps::is_empty(&empty));
+    assert_eq!(SetOps::max(&empty), None);
+    assert_eq!(SetOps::iter(&empty).count(), 0);
+
+    assert!(SortedSlice::new(&[1usize, 1]).is_none());
+    assert!(SortedSlice::<usize>::new(&[]).is_some());
+    let wide: Set<u64> = Set::from_iter([5u64, 1 << 40]);
+    assert_eq!(&wide & (0..u64::MAX), wide);
+}
+
+#[test]
+fn set_operators_accept_hash_sets_with_any_hasher() {
+    use std::hash::BuildHasherDefault;
+
+    let set: Set = Set::from_iter(0..10);
+    let other: HashSet<usize, BuildHasherDefault<DefaultHasher>> = (5..15).collect();
+    assert_eq!(&set | &other, set.union(&other));
+    assert_eq!(&set & &other, Set::from_iter(5..10));
+    assert_eq!(set.clone() - other.clone(), Set::from_iter(0..5));
+    let mut xor = set.clone();
+    xor ^= &other;
+    assert_eq!(xor, &Set::from_iter(0..5) | &Set::from_iter(10..15));
+    let mut union = set;
+    union |= other;
+    assert_eq!(union, Set::from_iter(0..15));
+}
+
+#[cfg(feature = "hashbrown")]
+#[test]
+fn set_algebra_accepts_hashbrown_sets() {
+    let set: Set = Set::from_iter(0..10);
+    let other: hashbrown::HashSet<usize> = (5..15).collect();
+    assert_eq!(SetOps::len(&other), 10);
+    assert_eq!(SetOps::max(&other), Some(14));
+    assert_eq!(&set | &other, Set::from_iter(0..15));
+    assert_eq!(&set & &other, Set::from_iter(5..10));
+    assert_eq!(set.clone() - other.clone(), Set::from_iter(0..5));
+    let mut xor = set.clone();
+    xor ^= &other;
+    assert_eq!(xor, &Set::from_iter(0..5) | &Set::from_iter(10..15));
+    assert!(!set.is_subset(&other));
+}
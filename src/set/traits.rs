@@ -1,4 +1,5 @@
 use super::core::Set;
+use super::element::Element;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 
@@ -8,23 +9,22 @@ use std::hash::{Hash, Hasher};
 ///
 /// ```
 /// use fastset::Set;
-/// let set = Set::from_iter(0..5);
+/// let set: Set = Set::from_iter(0..5);
 /// println!("{:?}", set);
 /// ```
-impl std::fmt::Debug for Set {
+impl<T: Element, const PAGE: usize> std::fmt::Debug for Set<T, PAGE> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // Generate a detailed string for each element that is present.
         let element_details: Vec<String> = self
             .elements
             .iter()
             .map(|&e| {
-                let indicator = self.indicator[e]; // Check if the indicator for this element is true.
-                                                   // To find the page and in-page index for the element
-                let (page_idx, in_page_idx) = Set::page_indices(e);
-                let page = &self.pages[page_idx];
-                let mapped_index = page
-                    .as_ref()
-                    .map_or("None".to_string(), |p| p[in_page_idx].to_string());
+                let indicator = self.indicator.contains(e.to_index()); // Check if the indicator for this element is true.
+                                                                       // To find the page and in-page index for the element
+                let mapped_index = self
+                    .positions
+                    .get(e.to_index())
+                    .map_or("None".to_string(), |p| p.to_string());
 
                 format!(
                     "Element: {}, Indicator: {}, Mapped Index: {}",
@@ -50,10 +50,10 @@ impl std::fmt::Debug for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set = Set::from_iter(0..5);
+/// let set: Set = Set::from_iter(0..5);
 /// println!("{}", set);
 /// ```
-impl std::fmt::Display for Set {
+impl<T: Element, const PAGE: usize> std::fmt::Display for Set<T, PAGE> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
@@ -77,10 +77,10 @@ impl std::fmt::Display for Set {
 /// use fastset::Set;
 /// let set: Set = Default::default();
 /// ```
-impl Default for Set {
+impl<T: Element, const PAGE: usize> Default for Set<T, PAGE> {
     fn default() -> Self {
         // Create a small set with minimal overhead
-        Self::with_max(64)
+        Self::with_max(T::from_index(64))
     }
 }
 
@@ -90,11 +90,11 @@ impl Default for Set {
 ///
 /// ```
 /// use fastset::Set;
-/// let set1 = Set::from_iter(0..5);
-/// let set2 = Set::from_iter(0..5);
+/// let set1: Set = Set::from_iter(0..5);
+/// let set2: Set = Set::from_iter(0..5);
 /// assert_eq!(set1, set2);
 /// ```
-impl PartialEq for Set {
+impl<T: Element, const PAGE: usize> PartialEq for Set<T, PAGE> {
     fn eq(&self, other: &Self) -> bool {
         match self.elements.len() == other.elements.len() {
             true => self.elements.iter().all(|&item| other.contains(&item)),
@@ -111,26 +111,26 @@ impl PartialEq for Set {
 ///
 /// ```
 /// # use fastset::Set;
-/// let set1 = Set::from(vec![1, 2, 3]);
-/// let set2 = Set::from(vec![3, 2, 1]);
+/// let set1: Set = Set::from(vec![1, 2, 3]);
+/// let set2: Set = Set::from(vec![3, 2, 1]);
 ///
 /// assert_eq!(set1, set2);
 /// ```
-impl Eq for Set {}
+impl<T: Element, const PAGE: usize> Eq for Set<T, PAGE> {}
 
-/// Implements the `PartialEq` trait for `Set` with `HashSet<usize>`.
+/// Implements the `PartialEq` trait for `Set<T>` with `HashSet<T>`.
 ///
 /// # Examples
 ///
 /// ```
 /// use std::collections::HashSet;
 /// use fastset::Set;
-/// let set = Set::from_iter(0..5);
+/// let set: Set = Set::from_iter(0..5);
 /// let hash_set: HashSet<usize> = (0..5).collect();
 /// assert_eq!(set, hash_set);
 /// ```
-impl PartialEq<HashSet<usize>> for Set {
-    fn eq(&self, other: &HashSet<usize>) -> bool {
+impl<T: Element, const PAGE: usize> PartialEq<HashSet<T>> for Set<T, PAGE> {
+    fn eq(&self, other: &HashSet<T>) -> bool {
         match self.len() == other.len() {
             true => self.iter().all(|&item| other.contains(&item)),
             false => false,
@@ -147,7 +147,7 @@ impl PartialEq<HashSet<usize>> for Set {
 /// use std::hash::{Hash, Hasher};
 /// use fastset::Set;
 ///
-/// let set = Set::from_iter(1..=5);
+/// let set: Set = Set::from_iter(1..=5);
 ///
 /// let mut hasher = DefaultHasher::new();
 /// set.hash(&mut hasher);
@@ -155,13 +155,11 @@ impl PartialEq<HashSet<usize>> for Set {
 ///
 /// println!("Hash value of the set: {}", hash);
 /// ```
-impl Hash for Set {
+impl<T: Element, const PAGE: usize> Hash for Set<T, PAGE> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        // Hash the indices of true bits to properly represent the set
-        for (idx, &bit) in self.indicator.iter().enumerate() {
-            if bit {
-                idx.hash(state);
-            }
+        // Hash the indices of set bits, in ascending order, to properly represent the set
+        for idx in self.indicator.ones() {
+            idx.hash(state);
         }
     }
 }
@@ -0,0 +1,331 @@
+use super::container::{count_ones, test_bit, CHUNK_WORDS};
+use super::core::{default_ceiling, Set};
+use super::element::Element;
+use super::error::Error;
+use super::indicator::{ChunkDirectory, RankedDirectory, CHUNK_BITS, CHUNK_BLOCKS};
+use super::random::uniform_index;
+use rand_core::RngCore;
+use std::io::{self, Write};
+
+/// Leading bytes of every view buffer.
+const MAGIC: [u8; 4] = *b"FSVW";
+
+/// Layout version written by [`Set::write_view_to`].
+const VERSION: u16 = 1;
+
+/// Size of the fixed header in bytes.
+const HEADER_BYTES: usize = 32;
+
+/// Size of one chunk's directory entry: its index and the rank before it.
+const ENTRY_BYTES: usize = 16;
+
+/// Size of one chunk's block prefix counts.
+const BLOCKS_BYTES: usize = CHUNK_BLOCKS * 2;
+
+/// Size of one chunk's bitmap.
+const BITMAP_BYTES: usize = CHUNK_WORDS * 8;
+
+/// A read-only Set borrowed from a serialized buffer, answering queries
+/// without deserializing it.
+///
+/// The buffer holds the Set's allocated indicator chunks as raw bitmaps,
+/// the same rank directory [`Set::enable_rank_index`] builds, and the element
+/// list in storage order, all at fixed offsets. Opening a view only checks
+/// the header and the chunk directory, so it takes time proportional to the
+/// number of chunks rather than the number of values, and the buffer can be
+/// backed by a memory map. Queries read the buffer in place:
+///
+/// - `contains` finds the chunk by binary search and tests one bit,
+/// - `rank` adds the chunk and block prefixes to a popcount over at most
+///   eight words,
+/// - `random` draws a uniform index into the element list.
+///
+/// The bits and elements themselves are not validated. A corrupt buffer can
+/// make queries return wrong answers, but never panic or read out of bounds.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::{Set, SetView};
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+///
+/// let set: Set<u32> = Set::from(vec![5, 10, 15, 70_000]);
+/// let mut bytes = Vec::new();
+/// set.write_view_to(&mut bytes).unwrap();
+///
+/// let view: SetView<u32> = SetView::new(&bytes).unwrap();
+/// assert!(view.contains(&70_000));
+/// assert_eq!(view.rank(12), 2);
+/// assert!(set.contains(&view.random(&mut ChaCha8Rng::seed_from_u64(7)).unwrap()));
+/// ```
+#[derive(Clone, Copy)]
+pub struct SetView<'a, T = usize> {
+    directory: &'a [u8],
+    blocks: &'a [u8],
+    bitmaps: &'a [u8],
+    elements: &'a [u8],
+    max: T,
+    len: usize,
+}
+
+impl<T: Element, const PAGE: usize> Set<T, PAGE> {
+    /// Writes the Set in the layout read by [`SetView`].
+    ///
+    /// All integers are little-endian:
+    ///
+    /// | Section   | Size              | Contents                                        |
+    /// |-----------|-------------------|-------------------------------------------------|
+    /// | header    | 32 bytes          | `FSVW`, version, element width, max, len, count |
+    /// | directory | 16 bytes a chunk  | chunk index and values before it (u64)          |
+    /// | blocks    | 256 bytes a chunk | values before each 512-slot block (u16)         |
+    /// | bitmaps   | 8 KiB a chunk     | the chunk's 1024 words                          |
+    /// | elements  | width × len bytes | the element list, in storage order              |
+    ///
+    /// The header holds the magic, a `u16` version, a `u8` element width and a
+    /// zero byte, followed by the maximum value, the number of elements and
+    /// the number of chunks as `u64`s. Every section starts at a multiple of
+    /// eight bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error raised by `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::{Set, SetView};
+    ///
+    /// let set: Set = (0..1_000).collect();
+    /// let mut bytes = Vec::new();
+    /// set.write_view_to(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(SetView::<usize>::new(&bytes).unwrap().len(), 1_000);
+    /// ```
+    pub fn write_view_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let (prefixes, blocks) = self.indicator.rank_layout();
+        let chunks = prefixes.len() - 1;
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&[std::mem::size_of::<T>() as u8, 0])?;
+        for field in [self.max.to_index(), self.len(), chunks] {
+            writer.write_all(&(field as u64).to_le_bytes())?;
+        }
+        for ((c, _), prefix) in self.indicator.chunk_words().zip(&prefixes) {
+            writer.write_all(&(c as u64).to_le_bytes())?;
+            writer.write_all(&(*prefix as u64).to_le_bytes())?;
+        }
+        for block in blocks {
+            writer.write_all(&block.to_le_bytes())?;
+        }
+        for (_, words) in self.indicator.chunk_words() {
+            for word in words.iter() {
+                writer.write_all(&word.to_le_bytes())?;
+            }
+        }
+        for element in &self.elements {
+            let bytes = (element.to_index() as u64).to_le_bytes();
+            writer.write_all(&bytes[..std::mem::size_of::<T>()])?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: Element> SetView<'a, T> {
+    /// Opens a view over a buffer written by [`Set::write_view_to`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSnapshot`] if the header is malformed, the
+    /// element width differs from `T`, the sections do not add up to the
+    /// buffer's length or the chunks are out of order, and
+    /// [`Error::UnsupportedVersion`] for a layout this release cannot read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::{Error, Set, SetView};
+    ///
+    /// let set: Set<u16> = Set::from(vec![1, 2, 3]);
+    /// let mut bytes = Vec::new();
+    /// set.write_view_to(&mut bytes).unwrap();
+    ///
+    /// assert!(SetView::<u16>::new(&bytes).is_ok());
+    /// assert!(matches!(
+    ///     SetView::<u32>::new(&bytes),
+    ///     Err(Error::InvalidSnapshot(_))
+    /// ));
+    /// ```
+    pub fn new(bytes: &'a [u8]) -> Result<Self, Error> {
+        let invalid = Error::InvalidSnapshot;
+        if bytes.len() < HEADER_BYTES || bytes[..4] != MAGIC {
+            return Err(invalid("missing view header"));
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let width = std::mem::size_of::<T>();
+        if bytes[6] as usize != width || bytes[7] != 0 {
+            return Err(invalid("element width does not match"));
+        }
+        let max = read_u64(bytes, 8);
+        let ceiling: T = default_ceiling();
+        let max = match usize::try_from(max) {
+            Ok(max) if max <= ceiling.to_index() => max,
+            _ => return Err(invalid("maximum out of range")),
+        };
+        let (len, chunks) = (read_u64(bytes, 16), read_u64(bytes, 24));
+        let sections = [ENTRY_BYTES, BLOCKS_BYTES, BITMAP_BYTES].map(|size| {
+            usize::try_from(chunks)
+                .ok()
+                .and_then(|chunks| chunks.checked_mul(size))
+        });
+        let elements = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_mul(width));
+        let [Some(directory), Some(blocks), Some(bitmaps)] = sections else {
+            return Err(invalid("sections do not match the buffer"));
+        };
+        let total = [directory, blocks, bitmaps, elements.unwrap_or(usize::MAX)]
+            .into_iter()
+            .try_fold(HEADER_BYTES, usize::checked_add);
+        if total != Some(bytes.len()) {
+            return Err(invalid("sections do not match the buffer"));
+        }
+        let (directory, rest) = bytes[HEADER_BYTES..].split_at(directory);
+        let (blocks, rest) = rest.split_at(blocks);
+        let (bitmaps, elements) = rest.split_at(bitmaps);
+
+        let limit = (max + 1).div_ceil(CHUNK_BITS) as u64;
+        let mut previous = None;
+        for entry in directory.chunks_exact(ENTRY_BYTES) {
+            let (c, prefix) = (read_u64(entry, 0), read_u64(entry, 8));
+            if previous.is_some_and(|(p, q)| p >= c || q > prefix) || c >= limit || prefix > len {
+                return Err(invalid("chunks out of order or range"));
+            }
+            previous = Some((c, prefix));
+        }
+        Ok(Self {
+            directory,
+            blocks,
+            bitmaps,
+            elements,
+            max: T::from_index(max),
+            len: len as usize,
+        })
+    }
+
+    /// Returns the number of elements in the view.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the view contains no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum value of the Set the view was written from.
+    #[inline(always)]
+    pub fn max_value(&self) -> T {
+        self.max
+    }
+
+    /// Returns `true` if the view contains `value`.
+    ///
+    /// Runs in O(log c) in the number of allocated chunks.
+    #[inline(always)]
+    pub fn contains(&self, value: &T) -> bool {
+        self.contains_slot(value.to_index())
+    }
+
+    /// Returns the number of elements strictly less than `value`.
+    ///
+    /// Mirrors [`Set::rank`] with the rank index enabled, and runs the same
+    /// code: a binary search over the allocated chunks, then a popcount over
+    /// at most eight words.
+    #[inline(always)]
+    pub fn rank(&self, value: T) -> usize {
+        self.rank_slot(value.to_index().min(self.max.to_index() + 1))
+    }
+
+    /// Returns a random element, drawn uniformly, or `None` if the view is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::{Set, SetView};
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let set: Set = Set::from(vec![42]);
+    /// let mut bytes = Vec::new();
+    /// set.write_view_to(&mut bytes).unwrap();
+    ///
+    /// let view: SetView = SetView::new(&bytes).unwrap();
+    /// assert_eq!(view.random(&mut ChaCha8Rng::seed_from_u64(7)), Some(42));
+    /// ```
+    #[inline(always)]
+    pub fn random<R: RngCore + ?Sized>(&self, rng: &mut R) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let width = std::mem::size_of::<T>();
+        let at = uniform_index(rng, self.len) * width;
+        let mut bytes = [0; 8];
+        bytes[..width].copy_from_slice(&self.elements[at..at + width]);
+        Some(T::from_index(u64::from_le_bytes(bytes) as usize))
+    }
+
+    /// Returns word `w` of the chunk at directory position `pos`.
+    #[inline(always)]
+    fn word(&self, pos: usize, w: usize) -> u64 {
+        read_u64(self.bitmaps, pos * BITMAP_BYTES + w * 8)
+    }
+}
+
+impl<T: Element> ChunkDirectory for SetView<'_, T> {
+    #[inline(always)]
+    fn chunk_count(&self) -> usize {
+        self.directory.len() / ENTRY_BYTES
+    }
+
+    #[inline(always)]
+    fn chunk_key(&self, pos: usize) -> usize {
+        read_u64(self.directory, pos * ENTRY_BYTES) as usize
+    }
+
+    #[inline(always)]
+    fn chunk_contains(&self, pos: usize, bit: usize) -> bool {
+        test_bit(|w| self.word(pos, w), bit)
+    }
+
+    #[inline(always)]
+    fn chunk_count_range(&self, pos: usize, start: usize, end: usize) -> usize {
+        count_ones(|w| self.word(pos, w), start, end)
+    }
+}
+
+impl<T: Element> RankedDirectory for SetView<'_, T> {
+    #[inline(always)]
+    fn before_chunk(&self, pos: usize) -> usize {
+        match pos < self.chunk_count() {
+            true => read_u64(self.directory, pos * ENTRY_BYTES + 8) as usize,
+            false => self.len,
+        }
+    }
+
+    #[inline(always)]
+    fn before_block(&self, pos: usize, block: usize) -> usize {
+        let at = (pos * CHUNK_BLOCKS + block) * 2;
+        u16::from_le_bytes([self.blocks[at], self.blocks[at + 1]]) as usize
+    }
+}
+
+#[inline(always)]
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().expect("eight bytes"))
+}
@@ -0,0 +1,387 @@
+use super::core::Set;
+use super::element::Element;
+use super::random::{uniform_index, unit_interval};
+use rand_core::RngCore;
+use std::fmt;
+
+/// Number of weight buckets, one per biased binary exponent of a finite
+/// positive `f64`.
+const BUCKETS: usize = 2047;
+
+/// Number of leaves of the bucket tree, the power of two above `BUCKETS`.
+const LEAVES: usize = 2048;
+
+/// A Set whose members each carry a non-negative weight, sampled in
+/// proportion to it.
+///
+/// The members live in an ordinary [`Set`], so membership stays an O(1)
+/// indicator probe and each member's slot in the element list is found
+/// through the Set's position pages. The weights are kept in a parallel list
+/// that follows the Set's swap-remove layout, and each member with a positive
+/// weight is filed in a bucket by the binary exponent of its weight, so the
+/// weights within a bucket differ by less than a factor of two:
+///
+/// - `contains` and `weight` are O(1);
+/// - `insert`, `remove` and weight updates are O(1), moving a member between
+///   at most two buckets and updating a tree of fixed depth over the
+///   buckets' member counts;
+/// - `random` descends that tree to a bucket, then draws a member of it
+///   uniformly and keeps it with probability proportional to its weight,
+///   which is at least one half for normal floats, so it takes O(1)
+///   expected time.
+///
+/// The tree holds member counts scaled by powers of two rather than running
+/// sums, so no rounding error accumulates and a member of weight zero is
+/// never drawn. Weights must sum to less than `f64::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use fastset::WeightedSet;
+/// use rand_chacha::rand_core::SeedableRng;
+/// use rand_chacha::ChaCha8Rng;
+///
+/// let mut set: WeightedSet = WeightedSet::with_max(100);
+/// set.insert(3, 1.0);
+/// set.insert(7, 3.0);
+/// assert_eq!(set.total_weight(), 4.0);
+///
+/// // 7 comes up about three times as often as 3
+/// let mut rng = ChaCha8Rng::seed_from_u64(42);
+/// let sevens = (0..1_000)
+///     .filter(|_| set.random(&mut rng) == Some(7))
+///     .count();
+/// assert!((650..850).contains(&sevens));
+/// ```
+#[derive(Clone)]
+pub struct WeightedSet<T = usize, const PAGE: usize = 16> {
+    set: Set<T, PAGE>,
+    weights: Vec<f64>,
+    /// Position of each slot in its bucket's member list, if its weight is positive.
+    offsets: Vec<usize>,
+    /// Slots of the members in each bucket, allocated with the first positive weight.
+    buckets: Vec<Vec<usize>>,
+    /// Complete binary tree over the buckets, node `i` summing nodes `2i` and
+    /// `2i + 1`, with leaf `LEAVES + b` holding bucket `b`'s member count
+    /// times its scale.
+    tree: Vec<f64>,
+    positive: usize,
+    total: f64,
+    updates: usize,
+}
+
+impl<T: Element, const PAGE: usize> WeightedSet<T, PAGE> {
+    /// Creates an empty WeightedSet able to hold values up to `max` without
+    /// growing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::WeightedSet;
+    ///
+    /// let set: WeightedSet<u32> = WeightedSet::with_max(1_000);
+    /// assert!(set.is_empty());
+    /// ```
+    pub fn with_max(max: T) -> Self {
+        Self {
+            set: Set::with_max(max),
+            weights: Vec::new(),
+            offsets: Vec::new(),
+            buckets: Vec::new(),
+            tree: Vec::new(),
+            positive: 0,
+            total: 0.0,
+            updates: 0,
+        }
+    }
+
+    /// Returns the number of members.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Returns `true` if the WeightedSet has no members.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Returns `true` if `value` is a member.
+    #[inline(always)]
+    pub fn contains(&self, value: &T) -> bool {
+        self.set.contains(value)
+    }
+
+    /// Returns the weight of `value`, or `None` if it is not a member.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::WeightedSet;
+    ///
+    /// let mut set: WeightedSet = WeightedSet::with_max(10);
+    /// set.insert(4, 2.5);
+    /// assert_eq!(set.weight(&4), Some(2.5));
+    /// assert_eq!(set.weight(&5), None);
+    /// ```
+    #[inline(always)]
+    pub fn weight(&self, value: &T) -> Option<f64> {
+        self.slot(value).map(|slot| self.weights[slot])
+    }
+
+    /// Returns the sum of all weights.
+    ///
+    /// The sum is kept up to date by each update and recomputed from the
+    /// exact weights once updates outnumber the members, so rounding errors
+    /// stay bounded at an amortized O(1) cost per update. It is exactly zero
+    /// whenever no weight is positive.
+    #[inline(always)]
+    pub fn total_weight(&self) -> f64 {
+        self.total
+    }
+
+    /// Inserts `value` with `weight`, or updates its weight if it is already
+    /// a member.
+    ///
+    /// Returns the previous weight if `value` was a member, and `None`
+    /// otherwise. A member with weight zero is kept but never sampled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is negative, infinite or NaN, or if `value` is
+    /// above the underlying Set's [ceiling](Set::ceiling).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::WeightedSet;
+    ///
+    /// let mut set: WeightedSet = WeightedSet::with_max(10);
+    /// assert_eq!(set.insert(4, 1.0), None);
+    /// assert_eq!(set.insert(4, 5.0), Some(1.0));
+    /// assert_eq!(set.total_weight(), 5.0);
+    /// ```
+    pub fn insert(&mut self, value: T, weight: f64) -> Option<f64> {
+        assert!(
+            weight.is_finite() && weight >= 0.0,
+            "weight {} must be finite and non-negative",
+            weight
+        );
+        if let Some(slot) = self.slot(&value) {
+            self.detach(slot);
+            let previous = std::mem::replace(&mut self.weights[slot], weight);
+            self.attach(slot);
+            self.tick();
+            return Some(previous);
+        }
+        self.set.insert(value);
+        self.weights.push(weight);
+        self.offsets.push(0);
+        self.attach(self.weights.len() - 1);
+        None
+    }
+
+    /// Removes `value`, returning its weight, or `None` if it was not a member.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::WeightedSet;
+    ///
+    /// let mut set: WeightedSet = WeightedSet::with_max(10);
+    /// set.insert(4, 2.0);
+    /// set.insert(6, 1.0);
+    /// assert_eq!(set.remove(&4), Some(2.0));
+    /// assert_eq!(set.remove(&4), None);
+    /// assert_eq!(set.total_weight(), 1.0);
+    /// ```
+    pub fn remove(&mut self, value: &T) -> Option<f64> {
+        let slot = self.slot(value)?;
+        self.detach(slot);
+        // Mirror the Set's swap-remove: the last member moves into the gap
+        self.set.remove(value);
+        let weight = self.weights.swap_remove(slot);
+        self.offsets.swap_remove(slot);
+        if let Some(&moved) = self.weights.get(slot) {
+            if moved > 0.0 {
+                self.buckets[bucket(moved)][self.offsets[slot]] = slot;
+            }
+        }
+        self.tick();
+        Some(weight)
+    }
+
+    /// Returns a member drawn with probability proportional to its weight, or
+    /// `None` if there are no members or every weight is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::WeightedSet;
+    /// use rand_chacha::rand_core::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// let mut set: WeightedSet = WeightedSet::with_max(10);
+    /// set.insert(1, 0.0);
+    /// set.insert(2, 1.0);
+    ///
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// assert_eq!(set.random(&mut rng), Some(2));
+    /// ```
+    pub fn random<R: RngCore + ?Sized>(&self, rng: &mut R) -> Option<T> {
+        if self.positive == 0 {
+            return None;
+        }
+        loop {
+            // Descend to a bucket in proportion to its scaled member count,
+            // never into a subtree that sums to zero
+            let mut target = unit_interval(rng) * self.tree[1];
+            let mut node = 1;
+            while node < LEAVES {
+                node *= 2;
+                let (left, right) = (self.tree[node], self.tree[node + 1]);
+                if right > 0.0 && (left == 0.0 || target >= left) {
+                    target -= left;
+                    node += 1;
+                }
+            }
+            let b = node - LEAVES;
+            let members = &self.buckets[b];
+            let slot = members[uniform_index(rng, members.len())];
+            // Keep it with probability weight / (2 · scale), in [1/2, 1)
+            if unit_interval(rng) * 2.0 < self.weights[slot] / scale(b) {
+                return Some(self.set.elements[slot]);
+            }
+        }
+    }
+
+    /// Returns an iterator over the members and their weights, in storage order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastset::WeightedSet;
+    ///
+    /// let mut set: WeightedSet = WeightedSet::with_max(10);
+    /// set.insert(4, 2.0);
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), vec![(4, 2.0)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (T, f64)> + '_ {
+        self.set
+            .elements
+            .iter()
+            .copied()
+            .zip(self.weights.iter().copied())
+    }
+
+    /// Returns the members as a plain [`Set`], for set algebra and uniform
+    /// sampling.
+    #[inline(always)]
+    pub fn as_set(&self) -> &Set<T, PAGE> {
+        &self.set
+    }
+
+    /// Removes every member.
+    pub fn clear(&mut self) {
+        self.set.clear();
+        self.weights.clear();
+        self.offsets.clear();
+        self.buckets.clear();
+        self.tree.clear();
+        self.positive = 0;
+        self.total = 0.0;
+        self.updates = 0;
+    }
+
+    /// Finds the slot of a member through the Set's position pages.
+    #[inline(always)]
+    fn slot(&self, value: &T) -> Option<usize> {
+        if !self.set.contains(value) {
+            return None;
+        }
+        self.set
+            .positions
+            .get(value.to_index())
+            .map(|position| position.to_index())
+    }
+
+    /// Files `slot` in the bucket of its weight, if the weight is positive.
+    fn attach(&mut self, slot: usize) {
+        let weight = self.weights[slot];
+        if weight == 0.0 {
+            return;
+        }
+        if self.tree.is_empty() {
+            self.buckets.resize_with(BUCKETS, Vec::new);
+            self.tree.resize(2 * LEAVES, 0.0);
+        }
+        let b = bucket(weight);
+        self.offsets[slot] = self.buckets[b].len();
+        self.buckets[b].push(slot);
+        self.positive += 1;
+        self.total += weight;
+        self.refresh(b);
+    }
+
+    /// Takes `slot` out of the bucket of its weight, if the weight is positive.
+    fn detach(&mut self, slot: usize) {
+        let weight = self.weights[slot];
+        if weight == 0.0 {
+            return;
+        }
+        let (b, offset) = (bucket(weight), self.offsets[slot]);
+        self.buckets[b].swap_remove(offset);
+        if let Some(&moved) = self.buckets[b].get(offset) {
+            self.offsets[moved] = offset;
+        }
+        self.positive -= 1;
+        self.total = match self.positive {
+            0 => 0.0,
+            _ => self.total - weight,
+        };
+        self.refresh(b);
+    }
+
+    /// Recomputes bucket `b`'s leaf and the sums above it.
+    fn refresh(&mut self, b: usize) {
+        let mut node = LEAVES + b;
+        self.tree[node] = self.buckets[b].len() as f64 * scale(b);
+        while node > 1 {
+            node /= 2;
+            self.tree[node] = self.tree[2 * node] + self.tree[2 * node + 1];
+        }
+    }
+
+    /// Counts an update, recomputing the total from the exact weights once
+    /// updates outnumber the members.
+    fn tick(&mut self) {
+        self.updates += 1;
+        if self.updates > self.weights.len() {
+            self.updates = 0;
+            self.total = self.weights.iter().sum();
+        }
+    }
+}
+
+/// Returns the bucket of a positive weight: its biased binary exponent, with
+/// every subnormal weight in bucket 0.
+#[inline(always)]
+fn bucket(weight: f64) -> usize {
+    (weight.to_bits() >> 52) as usize
+}
+
+/// Returns half the bound below which every weight in bucket `b` lies.
+#[inline(always)]
+fn scale(b: usize) -> f64 {
+    match b {
+        0 => f64::from_bits(1 << 51),
+        _ => f64::from_bits((b as u64) << 52),
+    }
+}
+
+impl<T: Element, const PAGE: usize> fmt::Debug for WeightedSet<T, PAGE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}